@@ -0,0 +1,197 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async JSON RPC client for light clients that don't trust the full node they talk to
+//!
+//! `VerifyingClient` keeps track of a `TrustedState` and ratchets it with `get_state_proof`
+//! responses, following epoch changes through the validator change proofs. Account states are
+//! only returned after their proofs are checked against the latest verified ledger info: the
+//! sparse Merkle proof from the account to the state root, and the transaction accumulator proof
+//! from the transaction info to the ledger info.
+
+use anyhow::{ensure, format_err, Result};
+use libra_json_rpc::{
+    views::{AccountStateWithProofView, ResponseAsView, StateProofView},
+    JsonRpcAsyncClient, JsonRpcBatch, JsonRpcResponse,
+};
+use libra_logger::prelude::*;
+use libra_types::{
+    account_address::AccountAddress,
+    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::AccountStateProof,
+    transaction::Version,
+    trusted_state::{TrustedState, TrustedStateChange},
+    validator_change::ValidatorChangeProof,
+    waypoint::Waypoint,
+};
+
+#[cfg(test)]
+mod tests;
+
+pub struct VerifyingClient {
+    client: JsonRpcAsyncClient,
+    /// The latest verified chain state.
+    trusted_state: TrustedState,
+    /// The ledger info `trusted_state` was ratcheted to. This is `None` until the first `sync`.
+    latest_li: Option<LedgerInfoWithSignatures>,
+    /// The most recent epoch change ledger info. This is `None` if we haven't seen an epoch change
+    /// since the initial trusted state.
+    latest_epoch_change_li: Option<LedgerInfoWithSignatures>,
+}
+
+impl VerifyingClient {
+    pub fn new(client: JsonRpcAsyncClient, trusted_state: TrustedState) -> Self {
+        Self {
+            client,
+            trusted_state,
+            latest_li: None,
+            latest_epoch_change_li: None,
+        }
+    }
+
+    pub fn from_waypoint(client: JsonRpcAsyncClient, waypoint: Waypoint) -> Self {
+        Self::new(client, TrustedState::from_waypoint(waypoint))
+    }
+
+    pub fn trusted_state(&self) -> &TrustedState {
+        &self.trusted_state
+    }
+
+    pub fn latest_ledger_info(&self) -> Option<&LedgerInfoWithSignatures> {
+        self.latest_li.as_ref()
+    }
+
+    pub fn latest_epoch_change_li(&self) -> Option<&LedgerInfoWithSignatures> {
+        self.latest_epoch_change_li.as_ref()
+    }
+
+    /// Fetches a state proof relative to the trusted version and ratchets the trusted state to
+    /// the latest ledger info of the full node.
+    pub async fn sync(&mut self) -> Result<()> {
+        let mut batch = JsonRpcBatch::new();
+        batch.add_get_state_proof_request(self.trusted_state.latest_version());
+        let response = self.execute_one(batch).await?;
+        self.verify_state_proof(StateProofView::from_response(response)?)
+    }
+
+    /// Syncs, then returns the verified state of `address` at the latest trusted version, along
+    /// with that version.
+    pub async fn get_account_state(
+        &mut self,
+        address: AccountAddress,
+    ) -> Result<(Option<AccountStateBlob>, Version)> {
+        self.sync().await?;
+        let version = self.trusted_state.latest_version();
+        let blob = self.get_account_state_at_version(address, version).await?;
+        Ok((blob, version))
+    }
+
+    /// Returns the verified state of `address` at `version`, which must not be newer than the
+    /// latest trusted version.
+    pub async fn get_account_state_at_version(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountStateBlob>> {
+        let latest_li = self
+            .latest_li
+            .as_ref()
+            .ok_or_else(|| format_err!("No verified ledger info yet, sync first"))?
+            .ledger_info();
+        ensure!(
+            version <= latest_li.version(),
+            "Version {} is newer than the latest trusted version {}",
+            version,
+            latest_li.version(),
+        );
+
+        let mut batch = JsonRpcBatch::new();
+        batch.add_get_account_state_with_proof_request(address, version, latest_li.version());
+        let response = self.execute_one(batch).await?;
+        verify_account_state(
+            latest_li,
+            version,
+            address,
+            AccountStateWithProofView::from_response(response)?,
+        )
+    }
+
+    fn verify_state_proof(&mut self, state_proof: StateProofView) -> Result<()> {
+        let li: LedgerInfoWithSignatures =
+            lcs::from_bytes(&state_proof.ledger_info_with_signatures.into_bytes()?)?;
+        let validator_change_proof: ValidatorChangeProof =
+            lcs::from_bytes(&state_proof.validator_change_proof.into_bytes()?)?;
+
+        let client_version = self.trusted_state.latest_version();
+        ensure!(
+            li.ledger_info().version() >= client_version,
+            "Got stale ledger_info with version {}, known version: {}",
+            li.ledger_info().version(),
+            client_version,
+        );
+
+        match self
+            .trusted_state
+            .verify_and_ratchet(&li, &validator_change_proof)?
+        {
+            TrustedStateChange::Epoch {
+                new_state,
+                latest_epoch_change_li,
+                latest_validator_set,
+                ..
+            } => {
+                info!(
+                    "Verified epoch change to epoch: {}, validator set: [{}]",
+                    latest_epoch_change_li.ledger_info().epoch(),
+                    latest_validator_set
+                );
+                self.latest_epoch_change_li = Some(latest_epoch_change_li.clone());
+                self.trusted_state = new_state;
+            }
+            TrustedStateChange::Version { new_state, .. } => {
+                self.trusted_state = new_state;
+            }
+        }
+        self.latest_li = Some(li);
+        Ok(())
+    }
+
+    async fn execute_one(&self, batch: JsonRpcBatch) -> Result<JsonRpcResponse> {
+        let mut responses = self.client.execute(batch).await?;
+        ensure!(
+            responses.len() == 1,
+            "Received {} JSON RPC responses for 1 request",
+            responses.len()
+        );
+        responses.remove(0)
+    }
+}
+
+/// Checks the account state returned by `get_account_state_with_proof` against `ledger_info`,
+/// and returns the account blob if it's valid.
+pub fn verify_account_state(
+    ledger_info: &LedgerInfo,
+    version: Version,
+    address: AccountAddress,
+    view: AccountStateWithProofView,
+) -> Result<Option<AccountStateBlob>> {
+    let blob: Option<AccountStateBlob> = match view.blob {
+        Some(blob) => Some(lcs::from_bytes(&blob.into_bytes()?)?),
+        None => None,
+    };
+    let proof = AccountStateProof::new(
+        lcs::from_bytes(
+            &view
+                .proof
+                .ledger_info_to_transaction_info_proof
+                .into_bytes()?,
+        )?,
+        lcs::from_bytes(&view.proof.transaction_info.into_bytes()?)?,
+        lcs::from_bytes(&view.proof.transaction_info_to_account_proof.into_bytes()?)?,
+    );
+
+    let account_state_with_proof = AccountStateWithProof::new(view.version, blob, proof);
+    account_state_with_proof.verify(ledger_info, version, address)?;
+    Ok(account_state_with_proof.blob)
+}
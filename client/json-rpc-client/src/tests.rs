@@ -0,0 +1,239 @@
+use crate::{verify_account_state, VerifyingClient};
+use libra_crypto::hash::CryptoHash;
+use libra_json_rpc::{
+    views::{AccountStateWithProofView, BytesView, StateProofView},
+    JsonRpcAsyncClient,
+};
+use libra_temppath::TempPath;
+use libra_types::{
+    account_address::AccountAddress,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::TransactionToCommit,
+    validator_change::ValidatorChangeProof,
+    validator_info::ValidatorInfo,
+    validator_set::ValidatorSet,
+    validator_signer::ValidatorSigner,
+    waypoint::Waypoint,
+};
+use libradb::{test_helper::arb_blocks_to_commit, LibraDB};
+use proptest::prelude::*;
+use std::convert::TryFrom;
+use storage_interface::{DbReader, DbWriter};
+
+fn new_signers(seed: u8, num: usize) -> Vec<ValidatorSigner> {
+    (0..num)
+        .map(|i| ValidatorSigner::random([seed * 16 + i as u8; 32]))
+        .collect()
+}
+
+fn to_validator_set(signers: &[ValidatorSigner]) -> ValidatorSet {
+    ValidatorSet::new(
+        signers
+            .iter()
+            .map(|signer| {
+                ValidatorInfo::new_with_random_network_keys(
+                    signer.author(),
+                    signer.public_key(),
+                    1, /* voting power */
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Moves `ledger_info` to `epoch` and signs it with `signers`, keeping the version and the
+/// accumulator root hash it's been generated with.
+fn sign_ledger_info(
+    ledger_info: &LedgerInfo,
+    epoch: u64,
+    next_validator_set: Option<ValidatorSet>,
+    signers: &[ValidatorSigner],
+) -> LedgerInfoWithSignatures {
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            epoch,
+            ledger_info.round(),
+            ledger_info.consensus_block_id(),
+            ledger_info.transaction_accumulator_hash(),
+            ledger_info.version(),
+            ledger_info.timestamp_usecs(),
+            next_validator_set,
+        ),
+        ledger_info.consensus_data_hash(),
+    );
+    let signatures = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.sign_message(ledger_info.hash())))
+        .collect();
+    LedgerInfoWithSignatures::new(ledger_info, signatures)
+}
+
+fn to_state_proof_view(
+    ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    validator_change_proof: &ValidatorChangeProof,
+    ledger_consistency_proof: BytesView,
+) -> StateProofView {
+    StateProofView {
+        ledger_info_with_signatures: BytesView::from(
+            &lcs::to_bytes(ledger_info_with_sigs).unwrap(),
+        ),
+        validator_change_proof: BytesView::from(&lcs::to_bytes(validator_change_proof).unwrap()),
+        ledger_consistency_proof,
+    }
+}
+
+fn test_verify_state_proof_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    // Every block but the last one ends an epoch, so each block is in an epoch of its own.
+    let signers: Vec<_> = (0..input.len())
+        .map(|epoch| new_signers(epoch as u8, 2))
+        .collect();
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new(&tmp_dir);
+    let mut cur_ver = 0;
+    let mut ledger_infos = vec![];
+    for (epoch, (txns_to_commit, ledger_info_with_sigs)) in input.iter().enumerate() {
+        let next_validator_set = signers.get(epoch + 1).map(|next| to_validator_set(next));
+        let ledger_info_with_sigs = sign_ledger_info(
+            ledger_info_with_sigs.ledger_info(),
+            epoch as u64,
+            next_validator_set,
+            &signers[epoch],
+        );
+        db.save_transactions(&txns_to_commit, cur_ver, Some(&ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+        ledger_infos.push(ledger_info_with_sigs);
+    }
+    let latest_li = ledger_infos.last().unwrap();
+    let last_epoch_change_li = &ledger_infos[ledger_infos.len() - 2];
+
+    // The client trusts the end of the first epoch, and is never asked to send a request.
+    let waypoint = Waypoint::new(ledger_infos[0].ledger_info()).unwrap();
+    let mut client = VerifyingClient::from_waypoint(
+        JsonRpcAsyncClient::new(reqwest::Client::new(), "localhost", 0),
+        waypoint,
+    );
+    let state_proof =
+        StateProofView::try_from(db.get_state_proof(waypoint.version()).unwrap()).unwrap();
+
+    // A validator change proof whose last ledger info hands the next epoch over to other
+    // validators fails, along with the latest ledger info they signed.
+    let forged_signers = new_signers(0x0f, 2);
+    let mut forged_proof: ValidatorChangeProof = lcs::from_bytes(
+        &state_proof
+            .validator_change_proof
+            .clone()
+            .into_bytes()
+            .unwrap(),
+    )
+    .unwrap();
+    let forged_epoch_change_li = forged_proof.ledger_info_with_sigs.pop().unwrap();
+    let epoch = forged_epoch_change_li.ledger_info().epoch();
+    forged_proof.ledger_info_with_sigs.push(sign_ledger_info(
+        forged_epoch_change_li.ledger_info(),
+        epoch,
+        Some(to_validator_set(&forged_signers)),
+        &forged_signers,
+    ));
+    let forged_latest_li =
+        sign_ledger_info(latest_li.ledger_info(), epoch + 1, None, &forged_signers);
+    assert!(client
+        .verify_state_proof(to_state_proof_view(
+            &forged_latest_li,
+            &forged_proof,
+            state_proof.ledger_consistency_proof.clone(),
+        ))
+        .is_err());
+    assert_eq!(client.trusted_state().latest_version(), waypoint.version());
+    assert!(client.latest_ledger_info().is_none());
+
+    // The real response moves the client to the latest epoch.
+    client.verify_state_proof(state_proof).unwrap();
+    assert_eq!(client.latest_ledger_info(), Some(latest_li));
+    assert_eq!(client.latest_epoch_change_li(), Some(last_epoch_change_li));
+    assert_eq!(
+        client.trusted_state().latest_version(),
+        latest_li.ledger_info().version()
+    );
+
+    // A response carrying an older ledger info is stale, even though it's valid.
+    let (validator_change_proof, ledger_consistency_proof) = db
+        .get_state_proof_with_ledger_info(waypoint.version(), last_epoch_change_li.clone())
+        .unwrap();
+    let stale_state_proof = StateProofView::try_from((
+        last_epoch_change_li.clone(),
+        validator_change_proof,
+        ledger_consistency_proof,
+    ))
+    .unwrap();
+    assert!(client.verify_state_proof(stale_state_proof).is_err());
+    assert_eq!(client.latest_ledger_info(), Some(latest_li));
+}
+
+fn test_verify_account_state_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new(&tmp_dir);
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    let latest_li = db.get_latest_ledger_info().unwrap();
+    let ledger_info = latest_li.ledger_info();
+    let version = ledger_info.version();
+    let (txns_to_commit, _) = input.last().unwrap();
+    let addresses = txns_to_commit
+        .iter()
+        .flat_map(|txn_to_commit| txn_to_commit.account_states().keys().cloned())
+        .chain(std::iter::once(AccountAddress::new(
+            [0; AccountAddress::LENGTH],
+        )));
+
+    for address in addresses {
+        let account_state_with_proof = db
+            .get_account_state_with_proof(address, version, version)
+            .unwrap();
+        let view = AccountStateWithProofView::try_from(account_state_with_proof.clone()).unwrap();
+
+        // A valid response passes.
+        let blob = verify_account_state(ledger_info, version, address, view.clone()).unwrap();
+        assert_eq!(blob, account_state_with_proof.blob);
+
+        // The same response fails for another version, or another account if it proves existence.
+        if version > 0 {
+            assert!(verify_account_state(ledger_info, version - 1, address, view.clone()).is_err());
+        }
+        let other_address = AccountAddress::new([0xff; AccountAddress::LENGTH]);
+        if blob.is_some() && other_address != address {
+            assert!(
+                verify_account_state(ledger_info, version, other_address, view.clone()).is_err()
+            );
+        }
+
+        // A tampered account blob fails.
+        let mut tampered_view = view;
+        tampered_view.blob = Some(BytesView::from(&lcs::to_bytes(&vec![0u8; 3]).unwrap()));
+        assert!(verify_account_state(ledger_info, version, address, tampered_view).is_err());
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_verify_account_state(input in arb_blocks_to_commit()) {
+        test_verify_account_state_impl(input);
+    }
+
+    #[test]
+    fn test_verify_state_proof(
+        input in arb_blocks_to_commit().prop_filter("needs an epoch change", |blocks| blocks.len() > 1)
+    ) {
+        test_verify_state_proof_impl(input);
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::proto::{
     node_debug_interface_client::NodeDebugInterfaceClient, GetEventsRequest, GetEventsResponse,
-    GetNodeDetailsRequest,
+    GetNodeDetailsRequest, SetConfigRequest,
 };
 use anyhow::{Context, Result};
 use std::collections::HashMap;
@@ -99,4 +99,15 @@ impl NodeDebugClient {
             .context("Unable to query Node events")?;
         Ok(response.into_inner())
     }
+
+    /// Changes the setting `name` of the node to `value` at runtime.
+    pub fn set_config<S: Into<String>>(&mut self, name: S, value: S) -> Result<()> {
+        let (rt, client) = self.client()?;
+        let mut request = SetConfigRequest::default();
+        request.name = name.into();
+        request.value = value.into();
+        rt.block_on(client.set_config(request))
+            .context("Unable to set Node config")?;
+        Ok(())
+    }
 }
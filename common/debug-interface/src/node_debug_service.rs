@@ -8,19 +8,32 @@ use crate::{
     json_log::JsonLogEntry,
     proto::{
         node_debug_interface_server::NodeDebugInterface, Event, GetEventsRequest,
-        GetEventsResponse, GetNodeDetailsRequest, GetNodeDetailsResponse,
+        GetEventsResponse, GetNodeDetailsRequest, GetNodeDetailsResponse, SetConfigRequest,
+        SetConfigResponse,
     },
 };
 use libra_logger::prelude::*;
+use std::{collections::HashMap, sync::Arc};
 use tonic::{Request, Response, Status};
 
+/// Applies a new value of a setting, given as a string, to the running node.
+pub type ConfigHandler = Arc<dyn Fn(&str) -> anyhow::Result<()> + Send + Sync>;
+
 #[derive(Clone, Default)]
-pub struct NodeDebugService;
+pub struct NodeDebugService {
+    config_handlers: HashMap<String, ConfigHandler>,
+}
 
 impl NodeDebugService {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Allows the setting `name` to be changed at runtime via `SetConfig`.
+    pub fn with_config_handler<S: Into<String>>(mut self, name: S, handler: ConfigHandler) -> Self {
+        self.config_handlers.insert(name.into(), handler);
+        self
+    }
 }
 
 #[tonic::async_trait]
@@ -52,6 +65,21 @@ impl NodeDebugInterface for NodeDebugService {
         }
         Ok(Response::new(response))
     }
+
+    async fn set_config(
+        &self,
+        request: Request<SetConfigRequest>,
+    ) -> Result<Response<SetConfigResponse>, Status> {
+        let request = request.into_inner();
+        info!("[GRPC] set_config {} = {}", request.name, request.value);
+
+        let handler = self
+            .config_handlers
+            .get(&request.name)
+            .ok_or_else(|| Status::not_found(format!("Unknown config: {}", request.name)))?;
+        handler(&request.value).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(SetConfigResponse::default()))
+    }
 }
 
 pub fn parse_events(events: Vec<Event>) -> Vec<JsonLogEntry> {
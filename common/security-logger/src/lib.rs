@@ -24,6 +24,9 @@ pub enum SecurityEvent {
     /// Mempool received an invalid network event
     InvalidNetworkEventMP,
 
+    /// Mempool banned a peer that broadcast too many invalid transactions
+    MisbehavingPeerMP,
+
     /// Consensus received an invalid vote
     DuplicateConsensusVote,
 
@@ -10,11 +10,28 @@ pub struct MempoolConfig {
     pub shared_mempool_tick_interval_ms: u64,
     pub shared_mempool_batch_size: usize,
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
+    // how long to wait for a peer to ACK a broadcast batch before sending it again
+    pub shared_mempool_ack_timeout_ms: u64,
+    // upper bound of the backoff between resends of a batch that wasn't ACKed
+    pub shared_mempool_max_backoff_ms: u64,
+    // max number of broadcast batches to a single peer that await an ACK
+    pub shared_mempool_max_pending_broadcasts: usize,
+    // max number of transactions accepted from a single peer per quota window
+    pub shared_mempool_peer_quota: usize,
+    pub shared_mempool_peer_quota_window_ms: u64,
+    // how long a peer is banned for once its reputation drops too low
+    pub shared_mempool_peer_ban_secs: u64,
     pub capacity: usize,
     // max number of transactions per user in Mempool
     pub capacity_per_user: usize,
+    // min increase of gas price, in percent, for a transaction to replace a pending one
+    pub gas_price_bump_percent: u64,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
+    // whether to journal transactions on disk, to replay them after a restart
+    pub persist_transactions: bool,
+    // order in which ready transactions are proposed to consensus
+    pub transaction_ordering: TransactionOrdering,
 }
 
 impl Default for MempoolConfig {
@@ -24,10 +41,30 @@ impl Default for MempoolConfig {
             shared_mempool_tick_interval_ms: 50,
             shared_mempool_batch_size: 100,
             shared_mempool_max_concurrent_inbound_syncs: 100,
+            shared_mempool_ack_timeout_ms: 2_000,
+            shared_mempool_max_backoff_ms: 30_000,
+            shared_mempool_max_pending_broadcasts: 20,
+            shared_mempool_peer_quota: 5_000,
+            shared_mempool_peer_quota_window_ms: 1_000,
+            shared_mempool_peer_ban_secs: 600,
             capacity: 1_000_000,
             capacity_per_user: 100,
+            gas_price_bump_percent: 10,
             system_transaction_timeout_secs: 86400,
             system_transaction_gc_interval_ms: 180_000,
+            persist_transactions: false,
+            transaction_ordering: TransactionOrdering::GasPrice,
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionOrdering {
+    // Highest gas price first
+    GasPrice,
+    // First in, first out by arrival in mempool
+    Fifo,
+    // One transaction per sender in turn
+    RoundRobin,
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::utils;
+use libra_types::transaction::MAX_TRANSACTION_SIZE_IN_BYTES;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
@@ -9,9 +10,26 @@ use std::net::SocketAddr;
 #[serde(default, deny_unknown_fields)]
 pub struct RpcConfig {
     pub address: SocketAddr,
+    /// The most requests allowed in one batch.
+    pub batch_size_limit: u16,
+    /// The most items (transactions, events, etc.) one request can ask for.
+    pub page_size_limit: u16,
+    /// The largest request body allowed, in bytes.
+    pub content_length_limit: usize,
+    /// Throttles requests from each IP address. Unlimited if not set.
+    pub ip_rate_limit: Option<RateLimitConfig>,
 }
 
 pub const DEFAULT_JSON_RPC_PORT: u16 = 8080;
+pub const DEFAULT_BATCH_SIZE_LIMIT: u16 = 20;
+pub const DEFAULT_PAGE_SIZE_LIMIT: u16 = 1000;
+/// Room in each request of a batch for what surrounds the hex encoded transaction of a `submit`:
+/// the JSON-RPC envelope, the authenticator and the encoding of the signed transaction.
+const REQUEST_OVERHEAD_IN_BYTES: usize = 1024;
+/// Fits a full batch of `submit` requests, each carrying the largest transaction allowed, hex
+/// encoded.
+pub const DEFAULT_CONTENT_LENGTH_LIMIT: usize = DEFAULT_BATCH_SIZE_LIMIT as usize
+    * (2 * MAX_TRANSACTION_SIZE_IN_BYTES + REQUEST_OVERHEAD_IN_BYTES); // 180kb
 
 impl Default for RpcConfig {
     fn default() -> RpcConfig {
@@ -19,6 +37,10 @@ impl Default for RpcConfig {
             address: format!("0.0.0.0:{}", DEFAULT_JSON_RPC_PORT)
                 .parse()
                 .unwrap(),
+            batch_size_limit: DEFAULT_BATCH_SIZE_LIMIT,
+            page_size_limit: DEFAULT_PAGE_SIZE_LIMIT,
+            content_length_limit: DEFAULT_CONTENT_LENGTH_LIMIT,
+            ip_rate_limit: None,
         }
     }
 }
@@ -28,3 +50,13 @@ impl RpcConfig {
         self.address.set_port(utils::get_available_port());
     }
 }
+
+/// Token bucket parameters. Each request in a batch takes one token.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Tokens refilled per second.
+    pub requests_per_sec: u64,
+    /// Capacity of the bucket, i.e. how many requests can be served at once after idling.
+    pub burst_size: u64,
+}
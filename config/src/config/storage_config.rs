@@ -3,7 +3,8 @@
 
 use crate::utils;
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, path::PathBuf};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use thiserror::Error;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -11,6 +12,9 @@ pub struct StorageConfig {
     pub address: SocketAddr,
     pub dir: PathBuf,
     pub grpc_max_receive_len: Option<i32>,
+    /// How much ledger history (transactions, events and transaction infos) to keep. Everything
+    /// is kept if not set.
+    pub ledger_pruning_window: Option<LedgerPruningWindow>,
     #[serde(skip)]
     data_dir: PathBuf,
 }
@@ -21,11 +25,59 @@ impl Default for StorageConfig {
             address: "127.0.0.1:6184".parse().unwrap(),
             dir: PathBuf::from("libradb/db"),
             grpc_max_receive_len: Some(100_000_000),
+            ledger_pruning_window: None,
             data_dir: PathBuf::from("/opt/libra/data/common"),
         }
     }
 }
 
+/// The retention window of the ledger history pruner.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerPruningWindow {
+    /// Keep the latest version and this many versions before it.
+    Versions(u64),
+    /// Keep the current epoch and this many epochs before it.
+    Epochs(u64),
+}
+
+impl LedgerPruningWindow {
+    /// Returns true if this window keeps all the history `other` keeps. Windows counted in
+    /// different units are incomparable, so neither keeps at least what the other keeps.
+    pub fn keeps_at_least(&self, other: &LedgerPruningWindow) -> bool {
+        match (self, other) {
+            (LedgerPruningWindow::Versions(a), LedgerPruningWindow::Versions(b))
+            | (LedgerPruningWindow::Epochs(a), LedgerPruningWindow::Epochs(b)) => a >= b,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for LedgerPruningWindow {
+    type Err = ParseLedgerPruningWindowError;
+
+    /// Parses "versions:<n>" or "epochs:<n>".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let err = || ParseLedgerPruningWindowError(s.to_string());
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().ok_or_else(err)?;
+        let num = parts
+            .next()
+            .ok_or_else(err)?
+            .parse::<u64>()
+            .map_err(|_| err())?;
+        match kind {
+            "versions" => Ok(LedgerPruningWindow::Versions(num)),
+            "epochs" => Ok(LedgerPruningWindow::Epochs(num)),
+            _ => Err(err()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid ledger pruning window: {0}, expecting versions:<n> or epochs:<n>")]
+pub struct ParseLedgerPruningWindowError(String);
+
 impl StorageConfig {
     pub fn dir(&self) -> PathBuf {
         if self.dir.is_relative() {
@@ -43,3 +95,33 @@ impl StorageConfig {
         self.address.set_port(utils::get_available_port());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_ledger_pruning_window() {
+        assert_eq!(
+            "versions:100".parse::<LedgerPruningWindow>().unwrap(),
+            LedgerPruningWindow::Versions(100)
+        );
+        assert_eq!(
+            "epochs:2".parse::<LedgerPruningWindow>().unwrap(),
+            LedgerPruningWindow::Epochs(2)
+        );
+        assert!("versions".parse::<LedgerPruningWindow>().is_err());
+        assert!("blocks:1".parse::<LedgerPruningWindow>().is_err());
+        assert!("epochs:-1".parse::<LedgerPruningWindow>().is_err());
+    }
+
+    #[test]
+    fn test_ledger_pruning_window_keeps_at_least() {
+        let versions = LedgerPruningWindow::Versions(100);
+        assert!(versions.keeps_at_least(&LedgerPruningWindow::Versions(100)));
+        assert!(versions.keeps_at_least(&LedgerPruningWindow::Versions(10)));
+        assert!(!versions.keeps_at_least(&LedgerPruningWindow::Versions(1000)));
+        assert!(!versions.keeps_at_least(&LedgerPruningWindow::Epochs(1)));
+        assert!(!LedgerPruningWindow::Epochs(1000).keeps_at_least(&versions));
+    }
+}
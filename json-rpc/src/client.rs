@@ -4,11 +4,14 @@
 use crate::{
     errors::JsonRpcError,
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, EventView, StateProofView,
-        TransactionView,
+        AccountAtVersionView, AccountStateChunkView, AccountStateWithProofView, AccountView,
+        BalanceHistoryView, BlockMetadata, EpochInfoView, EventView, MempoolSizeView,
+        OnChainConfigsView, PendingTransactionView, PendingTransactionsView, SimulationView,
+        StateProofView, TransactionStatusView, TransactionView, ValidatorSetView,
     },
 };
 use anyhow::{ensure, format_err, Error, Result};
+use libra_crypto::HashValue;
 use libra_types::{account_address::AccountAddress, transaction::SignedTransaction};
 use reqwest::Client;
 use serde_json::{json, Value};
@@ -46,6 +49,28 @@ impl JsonRpcBatch {
         Ok(())
     }
 
+    pub fn add_submit_and_wait_request(&mut self, transaction: SignedTransaction) -> Result<()> {
+        let txn_payload = hex::encode(lcs::to_bytes(&transaction)?);
+        self.add_request(
+            "submit_and_wait".to_string(),
+            vec![Value::String(txn_payload)],
+        );
+        Ok(())
+    }
+
+    pub fn add_simulate_request(
+        &mut self,
+        transaction: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Result<()> {
+        let txn_payload = hex::encode(lcs::to_bytes(&transaction)?);
+        self.add_request(
+            "simulate".to_string(),
+            vec![Value::String(txn_payload), json!(skip_signature_check)],
+        );
+        Ok(())
+    }
+
     pub fn add_get_account_state_request(&mut self, address: AccountAddress) {
         self.add_request(
             "get_account_state".to_string(),
@@ -85,6 +110,13 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_transaction_by_hash_request(&mut self, hash: HashValue, include_events: bool) {
+        self.add_request(
+            "get_transaction_by_hash".to_string(),
+            vec![json!(hash.to_hex()), json!(include_events)],
+        );
+    }
+
     pub fn add_get_events_request(&mut self, event_key: String, start: u64, limit: u64) {
         self.add_request(
             "get_events".to_string(),
@@ -96,6 +128,18 @@ impl JsonRpcBatch {
         self.add_request("get_state_proof".to_string(), vec![json!(known_version)]);
     }
 
+    pub fn add_get_account_states_in_range_request(
+        &mut self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) {
+        self.add_request(
+            "get_account_states_in_range".to_string(),
+            vec![json!(version), json!(start_key.to_hex()), json!(limit)],
+        );
+    }
+
     pub fn add_get_account_state_with_proof_request(
         &mut self,
         account: AccountAddress,
@@ -111,6 +155,63 @@ impl JsonRpcBatch {
             ],
         );
     }
+
+    pub fn add_get_account_state_at_version_request(
+        &mut self,
+        account: AccountAddress,
+        version: u64,
+    ) {
+        self.add_request(
+            "get_account_state_at_version".to_string(),
+            vec![json!(account.to_string()), json!(version)],
+        );
+    }
+
+    pub fn add_get_balance_history_request(
+        &mut self,
+        account: AccountAddress,
+        from_version: u64,
+        to_version: u64,
+    ) {
+        self.add_request(
+            "get_balance_history".to_string(),
+            vec![
+                json!(account.to_string()),
+                json!(from_version),
+                json!(to_version),
+            ],
+        );
+    }
+
+    pub fn add_get_validator_set_request(&mut self) {
+        self.add_request("get_validator_set".to_string(), vec![]);
+    }
+
+    pub fn add_get_on_chain_configs_request(&mut self) {
+        self.add_request("get_on_chain_configs".to_string(), vec![]);
+    }
+
+    pub fn add_get_epoch_info_request(&mut self) {
+        self.add_request("get_epoch_info".to_string(), vec![]);
+    }
+
+    pub fn add_get_pending_transactions_request(&mut self, account: AccountAddress) {
+        self.add_request(
+            "get_pending_transactions".to_string(),
+            vec![json!(account.to_string())],
+        );
+    }
+
+    pub fn add_get_pending_transaction_request(&mut self, account: AccountAddress, sequence: u64) {
+        self.add_request(
+            "get_pending_transaction".to_string(),
+            vec![json!(account.to_string()), json!(sequence)],
+        );
+    }
+
+    pub fn add_get_mempool_size_request(&mut self) {
+        self.add_request("get_mempool_size".to_string(), vec![]);
+    }
 }
 
 #[derive(Clone)]
@@ -192,6 +293,8 @@ impl fmt::Debug for JsonRpcAsyncClient {
 #[derive(Clone, PartialEq, Debug)]
 pub enum JsonRpcResponse {
     SubmissionResponse,
+    SimulationResponse(SimulationView),
+    TransactionStatusResponse(TransactionStatusView),
     AccountResponse(Option<AccountView>),
     StateProofResponse(StateProofView),
     AccountTransactionResponse(Option<TransactionView>),
@@ -199,6 +302,15 @@ pub enum JsonRpcResponse {
     EventsResponse(Vec<EventView>),
     BlockMetadataResponse(BlockMetadata),
     AccountStateWithProofResponse(AccountStateWithProofView),
+    AccountStateChunkResponse(AccountStateChunkView),
+    AccountAtVersionResponse(AccountAtVersionView),
+    BalanceHistoryResponse(BalanceHistoryView),
+    ValidatorSetResponse(ValidatorSetView),
+    OnChainConfigsResponse(OnChainConfigsView),
+    EpochInfoResponse(EpochInfoView),
+    PendingTransactionsResponse(PendingTransactionsView),
+    PendingTransactionResponse(Option<PendingTransactionView>),
+    MempoolSizeResponse(MempoolSizeView),
     UnknownResponse(Value),
 }
 
@@ -215,6 +327,14 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 );
                 Ok(JsonRpcResponse::SubmissionResponse)
             }
+            "submit_and_wait" => {
+                let status: TransactionStatusView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::TransactionStatusResponse(status))
+            }
+            "simulate" => {
+                let simulation: SimulationView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::SimulationResponse(simulation))
+            }
             "get_account_state" => {
                 let account = match value {
                     Value::Null => None,
@@ -239,11 +359,53 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                     account_with_proof,
                 ))
             }
+            "get_account_states_in_range" => {
+                let chunk: AccountStateChunkView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::AccountStateChunkResponse(chunk))
+            }
+            "get_account_state_at_version" => {
+                let account: AccountAtVersionView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::AccountAtVersionResponse(account))
+            }
+            "get_balance_history" => {
+                let history: BalanceHistoryView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::BalanceHistoryResponse(history))
+            }
             "get_state_proof" => {
                 let state_proof: StateProofView = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::StateProofResponse(state_proof))
             }
-            "get_account_transaction" => {
+            "get_validator_set" => {
+                let validator_set: ValidatorSetView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::ValidatorSetResponse(validator_set))
+            }
+            "get_on_chain_configs" => {
+                let configs: OnChainConfigsView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::OnChainConfigsResponse(configs))
+            }
+            "get_epoch_info" => {
+                let epoch_info: EpochInfoView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::EpochInfoResponse(epoch_info))
+            }
+            "get_pending_transactions" => {
+                let pending: PendingTransactionsView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::PendingTransactionsResponse(pending))
+            }
+            "get_pending_transaction" => {
+                let pending = match value {
+                    Value::Null => None,
+                    _ => {
+                        let pending: PendingTransactionView = serde_json::from_value(value)?;
+                        Some(pending)
+                    }
+                };
+                Ok(JsonRpcResponse::PendingTransactionResponse(pending))
+            }
+            "get_mempool_size" => {
+                let size: MempoolSizeView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::MempoolSizeResponse(size))
+            }
+            "get_account_transaction" | "get_transaction_by_hash" => {
                 let txn = match value {
                     Value::Null => None,
                     _ => {
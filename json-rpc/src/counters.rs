@@ -23,7 +23,7 @@ pub static INVALID_REQUESTS: Lazy<IntGaugeVec> = Lazy::new(|| {
         "libra_client_service_invalid_requests_count",
         "Cumulative number of invalid requests that JSON RPC client service receives",
         &[
-            "type", // categories of invalid requests: "invalid_format", "invalid_params", "invalid_method", "method_not_found"
+            "type", // categories of invalid requests: "invalid_format", "invalid_params", "invalid_method", "method_not_found", "batch_size_too_large", "rate_limited", "content_too_large", "invalid_subscription", "too_many_connections"
         ]
     )
     .unwrap()
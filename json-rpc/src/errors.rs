@@ -29,6 +29,15 @@ pub enum ServerCode {
     MempoolInvalidUpdate = -32010,
     MempoolVmError = -32011,
     MempoolUnknownError = -32012,
+
+    // Request limit errors - see `RpcConfig` for the limits
+    BatchSizeTooLarge = -32013,
+    PageSizeTooLarge = -32014,
+    ContentTooLarge = -32015,
+    RateLimited = -32016,
+    TooManySubscriptions = -32017,
+    TooManyWaiters = -32018,
+    TooManyConnections = -32019,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -83,6 +92,80 @@ impl JsonRpcError {
         }
     }
 
+    pub(crate) fn batch_size_too_large(size: usize, limit: u16) -> Self {
+        Self {
+            code: ServerCode::BatchSizeTooLarge as i16,
+            message: format!(
+                "Server error: batch size {} exceeds the limit {}",
+                size, limit
+            ),
+            data: None,
+        }
+    }
+
+    pub(crate) fn page_size_too_large(size: u64, limit: u16) -> Self {
+        Self {
+            code: ServerCode::PageSizeTooLarge as i16,
+            message: format!(
+                "Server error: page size {} exceeds the limit {}",
+                size, limit
+            ),
+            data: None,
+        }
+    }
+
+    pub(crate) fn content_too_large(limit: usize) -> Self {
+        Self {
+            code: ServerCode::ContentTooLarge as i16,
+            message: format!(
+                "Server error: request body exceeds the limit of {} bytes",
+                limit
+            ),
+            data: None,
+        }
+    }
+
+    pub(crate) fn rate_limited() -> Self {
+        Self {
+            code: ServerCode::RateLimited as i16,
+            message: "Server error: too many requests, retry later".to_string(),
+            data: None,
+        }
+    }
+
+    pub(crate) fn too_many_subscriptions(limit: usize) -> Self {
+        Self {
+            code: ServerCode::TooManySubscriptions as i16,
+            message: format!(
+                "Server error: a connection can have at most {} subscriptions",
+                limit
+            ),
+            data: None,
+        }
+    }
+
+    pub(crate) fn too_many_connections(limit: usize) -> Self {
+        Self {
+            code: ServerCode::TooManyConnections as i16,
+            message: format!(
+                "Server error: at most {} streaming connections can be open at once",
+                limit
+            ),
+            data: None,
+        }
+    }
+
+    pub(crate) fn too_many_waiters(limit: usize) -> Self {
+        Self {
+            code: ServerCode::TooManyWaiters as i16,
+            message: format!(
+                "Server error: at most {} submit_and_wait requests can wait at once, use submit instead",
+                limit
+            ),
+            data: None,
+        }
+    }
+
     pub(crate) fn mempool_error(error: MempoolStatus) -> Result<Self> {
         let code = match error.code {
             MempoolStatusCode::InvalidSeqNumber => ServerCode::MempoolInvalidSeqNumber,
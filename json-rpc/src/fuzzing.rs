@@ -44,7 +44,12 @@ pub fn fuzzer(data: &[u8]) {
 
     let port = utils::get_available_port();
     let address = format!("0.0.0.0:{}", port);
-    let _runtime = bootstrap(address.parse().unwrap(), Arc::new(db), smp.ac_client);
+    let _runtime = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(db),
+        smp.ac_client,
+        smp.inspection_sender,
+    );
 
     let client = Client::new();
     let url = format!("http://{}", address);
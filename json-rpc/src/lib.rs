@@ -11,7 +11,10 @@
 //! Module organization:
 //! ├── methods.rs        # contains all available JSON RPC method handlers
 //! ├── views.rs          # custom JSON serializers for Libra data types
+//! ├── schema.rs         # OpenRPC document generated from the methods and views
 //! ├── runtime.rs        # implementation of JSON RPC protocol over HTTP
+//! ├── rate_limiter.rs   # per-IP rate limiting of requests
+//! ├── stream.rs         # subscriptions to new events and transactions over WebSocket
 //! ├── tests.rs          # tests
 
 #[macro_use]
@@ -24,7 +27,10 @@ mod client;
 mod counters;
 pub mod errors;
 mod methods;
+mod rate_limiter;
 mod runtime;
+pub mod schema;
+mod stream;
 pub mod views;
 
 pub use client::{
@@ -4,40 +4,99 @@
 //! Module contains RPC method handlers for Full Node JSON-RPC interface
 use crate::{
     errors::JsonRpcError,
+    schema::{openrpc_document, DISCOVER_METHOD},
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, EventView, StateProofView,
-        TransactionView,
+        AccountAtVersionView, AccountStateChunkView, AccountStateWithProofView, AccountView,
+        BalanceHistoryView, BlockMetadata, EpochInfoView, EventView, GasScheduleView,
+        MempoolSizeView, OnChainConfigsView, PendingTransactionView, PendingTransactionsView,
+        PublishingOptionView, SimulationView, StateProofView, TransactionStatusDataView,
+        TransactionStatusView, TransactionView, ValidatorInfoView, ValidatorSetView,
     },
 };
-use anyhow::{ensure, format_err, Error, Result};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use core::future::Future;
 use debug_interface::prelude::*;
 use futures::{channel::oneshot, SinkExt};
 use hex;
-use libra_mempool::MempoolClientSender;
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_mempool::{
+    MempoolClientSender, MempoolInspectionRequest, MempoolInspectionResponse,
+    MempoolInspectionSender,
+};
 use libra_types::{
-    account_address::AccountAddress, account_state::AccountState, event::EventKey,
-    ledger_info::LedgerInfoWithSignatures, mempool_status::MempoolStatusCode,
-    transaction::SignedTransaction,
+    account_address::AccountAddress,
+    account_config::{association_address, validator_set_address, AccountResource},
+    account_state::AccountState,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    mempool_status::MempoolStatusCode,
+    on_chain_config::{LibraVersion, VMPublishingOption},
+    transaction::{SignedTransaction, Transaction, TransactionWithProof},
+    validator_set::ValidatorSet,
 };
 use serde_json::Value;
-use std::{collections::HashMap, convert::TryFrom, pin::Pin, str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use storage_interface::DbReader;
+use tokio::{sync::Semaphore, time::delay_for};
+use vm_validator::vm_validator::simulate_transaction;
+
+/// The longest `submit_and_wait` holds a request, however late the transaction expires.
+const SUBMIT_AND_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long `submit_and_wait` waits before checking the DB again for the transaction.
+const SUBMIT_AND_WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long `submit_and_wait` waits before checking again that mempool still has the transaction.
+const SUBMIT_AND_WAIT_MEMPOOL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// The most `submit_and_wait` requests held at once; further ones are rejected before submitting.
+pub(crate) const MAX_SUBMIT_AND_WAIT_WAITERS: usize = 1_000;
 
 #[derive(Clone)]
 pub(crate) struct JsonRpcService {
     db: Arc<dyn DbReader>,
     mempool_sender: MempoolClientSender,
+    mempool_inspector: MempoolInspectionSender,
+    page_size_limit: u16,
+    waiters: Arc<Semaphore>,
 }
 
 impl JsonRpcService {
-    pub fn new(db: Arc<dyn DbReader>, mempool_sender: MempoolClientSender) -> Self {
-        Self { db, mempool_sender }
+    pub fn new(
+        db: Arc<dyn DbReader>,
+        mempool_sender: MempoolClientSender,
+        mempool_inspector: MempoolInspectionSender,
+        page_size_limit: u16,
+    ) -> Self {
+        Self {
+            db,
+            mempool_sender,
+            mempool_inspector,
+            page_size_limit,
+            waiters: Arc::new(Semaphore::new(MAX_SUBMIT_AND_WAIT_WAITERS)),
+        }
     }
 
     pub fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
         self.db.get_latest_ledger_info()
     }
+
+    pub fn page_size_limit(&self) -> u16 {
+        self.page_size_limit
+    }
+
+    /// Fails with a `PageSizeTooLarge` error if `limit` exceeds the configured page size.
+    fn check_page_size(&self, limit: u64) -> Result<()> {
+        ensure!(limit > 0, "limit must be greater than 0");
+        if limit > u64::from(self.page_size_limit) {
+            return Err(JsonRpcError::page_size_too_large(limit, self.page_size_limit).into());
+        }
+        Ok(())
+    }
 }
 
 type RpcHandler =
@@ -64,6 +123,14 @@ impl JsonRpcRequest {
 async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<()> {
     let txn_payload: String = serde_json::from_value(request.get_param(0))?;
     let transaction: SignedTransaction = lcs::from_bytes(&hex::decode(txn_payload)?)?;
+    submit_transaction(&mut service, transaction).await
+}
+
+/// Sends `transaction` to mempool, and fails with the reason if mempool doesn't accept it
+async fn submit_transaction(
+    service: &mut JsonRpcService,
+    transaction: SignedTransaction,
+) -> Result<()> {
     trace_code_block!("json-rpc::submit", {"txn", transaction.sender(), transaction.sequence_number()});
 
     let (req_sender, callback) = oneshot::channel();
@@ -82,6 +149,180 @@ async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<
     }
 }
 
+/// Submits transaction to full node, then holds the request until the transaction is committed,
+/// expires or leaves mempool, for up to `SUBMIT_AND_WAIT_TIMEOUT`. Fails like `submit` if mempool
+/// rejects it, and without submitting it if `MAX_SUBMIT_AND_WAIT_WAITERS` requests already wait
+async fn submit_and_wait(
+    mut service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<TransactionStatusView> {
+    let txn_payload: String = serde_json::from_value(request.get_param(0))?;
+    let transaction: SignedTransaction = lcs::from_bytes(&hex::decode(txn_payload)?)?;
+    let (sender, sequence_number) = (transaction.sender(), transaction.sequence_number());
+    let expiration_time = transaction.expiration_time().as_secs();
+    let hash = Transaction::UserTransaction(transaction.clone()).hash();
+
+    let waiters = service.waiters.clone();
+    let _permit = waiters
+        .try_acquire()
+        .map_err(|_| JsonRpcError::too_many_waiters(MAX_SUBMIT_AND_WAIT_WAITERS))?;
+    submit_transaction(&mut service, transaction).await?;
+
+    let deadline = Instant::now() + SUBMIT_AND_WAIT_TIMEOUT;
+    let mut next_mempool_check = Instant::now() + SUBMIT_AND_WAIT_MEMPOOL_CHECK_INTERVAL;
+    let status = loop {
+        let ledger_info_with_sigs = service.get_latest_ledger_info()?;
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        if let Some(txn) = service
+            .db
+            .get_transaction_by_hash(hash, ledger_info.version(), true)?
+        {
+            break TransactionStatusDataView::Committed {
+                transaction: transaction_view(txn, true)?,
+            };
+        }
+        // The prologue rejects transactions that expire before the timestamp of their block.
+        if ledger_info.timestamp_usecs() / 1_000_000 >= expiration_time {
+            break TransactionStatusDataView::Expired {};
+        }
+        if Instant::now() >= next_mempool_check {
+            next_mempool_check = Instant::now() + SUBMIT_AND_WAIT_MEMPOOL_CHECK_INTERVAL;
+            if !is_in_mempool(&mut service, sender, sequence_number, hash).await? {
+                // Mempool removes transactions once they're committed, which may have happened
+                // since the DB was checked above.
+                let version = service.get_latest_ledger_info()?.ledger_info().version();
+                break match service.db.get_transaction_by_hash(hash, version, true)? {
+                    Some(txn) => TransactionStatusDataView::Committed {
+                        transaction: transaction_view(txn, true)?,
+                    },
+                    None => TransactionStatusDataView::Dropped {},
+                };
+            }
+        }
+        if Instant::now() >= deadline {
+            break TransactionStatusDataView::Pending {};
+        }
+        delay_for(SUBMIT_AND_WAIT_POLL_INTERVAL).await;
+    };
+    Ok(TransactionStatusView {
+        hash: hash.to_hex(),
+        status,
+    })
+}
+
+/// Returns whether mempool holds the transaction with hash `hash` as the transaction of `sender`
+/// with `sequence_number`
+async fn is_in_mempool(
+    service: &mut JsonRpcService,
+    sender: AccountAddress,
+    sequence_number: u64,
+    hash: HashValue,
+) -> Result<bool> {
+    match inspect_mempool(service, |callback| {
+        MempoolInspectionRequest::GetTransaction(sender, sequence_number, callback)
+    })
+    .await?
+    {
+        MempoolInspectionResponse::Transaction(txn) => Ok(txn.map_or(false, |txn| {
+            Transaction::UserTransaction(txn.transaction).hash() == hash
+        })),
+        response => bail!("unexpected mempool response: {:?}", response),
+    }
+}
+
+/// Executes a transaction against the latest state without committing it, and returns the gas it
+/// would use, its status, events and write set. If the second parameter is true, the signature of
+/// the transaction isn't checked, so it can be simulated before being signed.
+async fn simulate(service: JsonRpcService, request: JsonRpcRequest) -> Result<SimulationView> {
+    let txn_payload: String = serde_json::from_value(request.get_param(0))?;
+    let skip_signature_check: bool = serde_json::from_value(request.get_param(1))?;
+    let transaction: SignedTransaction = lcs::from_bytes(&hex::decode(txn_payload)?)?;
+
+    let (version, output) =
+        simulate_transaction(service.db, transaction, skip_signature_check).await?;
+    Ok(SimulationView::new(version, output))
+}
+
+/// Returns the transactions of an account that are waiting in mempool, along with the account's
+/// sequence number on chain, so that gaps keeping transactions parked can be spotted
+async fn get_pending_transactions(
+    mut service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<PendingTransactionsView> {
+    let address: String = serde_json::from_value(request.get_param(0))?;
+    let account_address = AccountAddress::from_str(&address)?;
+
+    let account_sequence_number = match service
+        .db
+        .get_account_state_with_proof_by_version(account_address, request.version())?
+        .0
+    {
+        Some(blob) => AccountState::try_from(&blob)?
+            .get_account_resource()?
+            .map(|account| account.sequence_number()),
+        None => None,
+    };
+    let transactions = match inspect_mempool(&mut service, |callback| {
+        MempoolInspectionRequest::GetAccountTransactions(account_address, callback)
+    })
+    .await?
+    {
+        MempoolInspectionResponse::AccountTransactions(txns) => txns,
+        response => bail!("unexpected mempool response: {:?}", response),
+    };
+    Ok(PendingTransactionsView {
+        account_sequence_number,
+        transactions: transactions.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// Returns the transaction of an account with given sequence number if it's waiting in mempool,
+/// including why it's parked if it can't be included in the next block
+async fn get_pending_transaction(
+    mut service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<PendingTransactionView>> {
+    let address: String = serde_json::from_value(request.get_param(0))?;
+    let sequence_number: u64 = serde_json::from_value(request.get_param(1))?;
+    let account_address = AccountAddress::from_str(&address)?;
+
+    match inspect_mempool(&mut service, |callback| {
+        MempoolInspectionRequest::GetTransaction(account_address, sequence_number, callback)
+    })
+    .await?
+    {
+        MempoolInspectionResponse::Transaction(txn) => Ok(txn.map(Into::into)),
+        response => bail!("unexpected mempool response: {:?}", response),
+    }
+}
+
+/// Returns the number of transactions in mempool
+async fn get_mempool_size(
+    mut service: JsonRpcService,
+    _request: JsonRpcRequest,
+) -> Result<MempoolSizeView> {
+    match inspect_mempool(&mut service, MempoolInspectionRequest::GetSize).await? {
+        MempoolInspectionResponse::Size(size) => Ok(size.into()),
+        response => bail!("unexpected mempool response: {:?}", response),
+    }
+}
+
+/// Sends the request built by `make_request` to mempool and waits for the response
+async fn inspect_mempool<F>(
+    service: &mut JsonRpcService,
+    make_request: F,
+) -> Result<MempoolInspectionResponse>
+where
+    F: FnOnce(oneshot::Sender<Result<MempoolInspectionResponse>>) -> MempoolInspectionRequest,
+{
+    let (req_sender, callback) = oneshot::channel();
+    service
+        .mempool_inspector
+        .send(make_request(req_sender))
+        .await?;
+    callback.await?
+}
+
 /// Returns account state (AccountView) by given address
 async fn get_account_state(
     service: JsonRpcService,
@@ -114,7 +355,7 @@ async fn get_metadata(_service: JsonRpcService, request: JsonRpcRequest) -> Resu
 }
 
 /// Returns transactions by range
-async fn get_transactions(
+pub(crate) async fn get_transactions(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Vec<TransactionView>> {
@@ -122,10 +363,7 @@ async fn get_transactions(
     let limit: u64 = serde_json::from_value(request.get_param(1))?;
     let include_events: bool = serde_json::from_value(request.get_param(2))?;
 
-    ensure!(
-        limit > 0 && limit <= 1000,
-        "limit must be smaller than 1000"
-    );
+    service.check_page_size(limit)?;
 
     let txs =
         service
@@ -181,44 +419,67 @@ async fn get_account_transaction(
 
     let account = AccountAddress::try_from(p_account)?;
 
-    let tx = service
+    service
         .db
-        .get_txn_by_account(account, sequence, request.version(), include_events)?;
-
-    if let Some(tx) = tx {
-        if include_events {
-            ensure!(
-                tx.events.is_some(),
-                "Storage layer didn't return events when requested!"
-            );
-        }
-        let tx_version = tx.version;
-
-        let events = tx
-            .events
-            .unwrap_or_default()
-            .into_iter()
-            .map(|x| ((tx_version, x).into()))
-            .collect();
-
-        Ok(Some(TransactionView {
-            version: tx_version,
-            transaction: tx.transaction.into(),
-            events,
-            vm_status: tx.proof.transaction_info().major_status(),
-            gas_used: tx.proof.transaction_info().gas_used(),
-        }))
-    } else {
-        Ok(None)
+        .get_txn_by_account(account, sequence, request.version(), include_events)?
+        .map(|tx| transaction_view(tx, include_events))
+        .transpose()
+}
+
+/// Returns the committed transaction with the given hash, which is the hash of the LCS bytes of
+/// the `Transaction`, as in its `TransactionInfo`. Transactions committed before the node started
+/// indexing transactions by hash aren't found
+async fn get_transaction_by_hash(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<TransactionView>> {
+    let hash: String = serde_json::from_value(request.get_param(0))?;
+    let include_events: bool = serde_json::from_value(request.get_param(1))?;
+
+    let hash = HashValue::from_slice(&hex::decode(hash)?)?;
+    service
+        .db
+        .get_transaction_by_hash(hash, request.version(), include_events)?
+        .map(|tx| transaction_view(tx, include_events))
+        .transpose()
+}
+
+fn transaction_view(tx: TransactionWithProof, include_events: bool) -> Result<TransactionView> {
+    if include_events {
+        ensure!(
+            tx.events.is_some(),
+            "Storage layer didn't return events when requested!"
+        );
     }
+    let tx_version = tx.version;
+
+    let events = tx
+        .events
+        .unwrap_or_default()
+        .into_iter()
+        .map(|x| ((tx_version, x).into()))
+        .collect();
+
+    Ok(TransactionView {
+        version: tx_version,
+        transaction: tx.transaction.into(),
+        events,
+        vm_status: tx.proof.transaction_info().major_status(),
+        gas_used: tx.proof.transaction_info().gas_used(),
+    })
 }
 
 /// Returns events by given access path
-async fn get_events(service: JsonRpcService, request: JsonRpcRequest) -> Result<Vec<EventView>> {
+pub(crate) async fn get_events(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<EventView>> {
     let raw_event_key: String = serde_json::from_value(request.get_param(0))?;
     let start: u64 = serde_json::from_value(request.get_param(1))?;
     let limit: u64 = serde_json::from_value(request.get_param(2))?;
 
+    service.check_page_size(limit)?;
+
     let event_key = EventKey::try_from(&hex::decode(raw_event_key)?[..])?;
     let events_with_proof = service.db.get_events(&event_key, start, true, limit)?;
 
@@ -261,20 +522,298 @@ async fn get_account_state_with_proof(
     )?)
 }
 
+/// Returns up to `limit` accounts at the given version in the order of hashed account address,
+/// starting from the given hash, with a proof of the range
+async fn get_account_states_in_range(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<AccountStateChunkView> {
+    let version: u64 = serde_json::from_value(request.get_param(0))?;
+    let start_key: String = serde_json::from_value(request.get_param(1))?;
+    let limit: u64 = serde_json::from_value(request.get_param(2))?;
+
+    ensure!(
+        version <= request.version(),
+        "version {} is newer than the latest version {}",
+        version,
+        request.version()
+    );
+    service.check_page_size(limit)?;
+    let start_key = HashValue::from_slice(&hex::decode(start_key)?)?;
+
+    let chunk = service
+        .db
+        .get_account_states_in_range(version, start_key, limit)?;
+    AccountStateChunkView::try_from(chunk)
+}
+
+/// Returns the state of `address` at `version` along with its proof against the ledger version of
+/// `request`, so that values read from it can be verified by the client
+fn get_account_state_and_proof(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+    address: AccountAddress,
+    version: u64,
+) -> Result<(Option<AccountState>, AccountStateWithProofView)> {
+    let account_state_with_proof =
+        service
+            .db
+            .get_account_state_with_proof(address, version, request.version())?;
+    let account_state = match &account_state_with_proof.blob {
+        Some(blob) => Some(AccountState::try_from(blob)?),
+        None => None,
+    };
+    Ok((
+        account_state,
+        AccountStateWithProofView::try_from(account_state_with_proof)?,
+    ))
+}
+
+/// Returns the current validator set, proven by the state of the validator set account
+async fn get_validator_set(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<ValidatorSetView> {
+    let (account_state, account_state_with_proof) = get_account_state_and_proof(
+        &service,
+        &request,
+        validator_set_address(),
+        request.version(),
+    )?;
+    let validator_set = account_state
+        .map(|account_state| account_state.get_config::<ValidatorSet>())
+        .transpose()?
+        .flatten()
+        .ok_or_else(|| format_err!("validator set not found"))?;
+    Ok(ValidatorSetView {
+        version: request.version(),
+        validators: validator_set
+            .payload()
+            .iter()
+            .map(ValidatorInfoView::from)
+            .collect(),
+        account_state_with_proof,
+    })
+}
+
+/// Returns the on-chain configs stored under the association account, proven by its state
+async fn get_on_chain_configs(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<OnChainConfigsView> {
+    let (account_state, account_state_with_proof) =
+        get_account_state_and_proof(&service, &request, association_address(), request.version())?;
+    let account_state =
+        account_state.ok_or_else(|| format_err!("association account not found"))?;
+    Ok(OnChainConfigsView {
+        version: request.version(),
+        libra_version: account_state
+            .get_config::<LibraVersion>()?
+            .map(|libra_version| libra_version.major),
+        publishing_option: account_state
+            .get_config::<VMPublishingOption>()?
+            .as_ref()
+            .map(PublishingOptionView::from),
+        gas_schedule: account_state
+            .get_gas_schedule_resource()?
+            .as_ref()
+            .map(GasScheduleView::from),
+        account_state_with_proof,
+    })
+}
+
+/// Returns the current epoch and when it started, proven by the state of the association account
+async fn get_epoch_info(service: JsonRpcService, request: JsonRpcRequest) -> Result<EpochInfoView> {
+    let (account_state, account_state_with_proof) =
+        get_account_state_and_proof(&service, &request, association_address(), request.version())?;
+    let configuration = account_state
+        .map(|account_state| account_state.get_configuration_resource())
+        .transpose()?
+        .flatten()
+        .ok_or_else(|| format_err!("configuration resource not found"))?;
+    Ok(EpochInfoView {
+        epoch: configuration.epoch(),
+        last_reconfiguration_time: configuration.last_reconfiguration_time(),
+        ledger_version: request.version(),
+        account_state_with_proof,
+    })
+}
+
+/// Returns the account at a past version, proven against the current ledger version
+async fn get_account_state_at_version(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<AccountAtVersionView> {
+    let address: String = serde_json::from_value(request.get_param(0))?;
+    let version: u64 = serde_json::from_value(request.get_param(1))?;
+    let address = AccountAddress::from_str(&address)?;
+
+    ensure!(
+        version <= request.version(),
+        "version {} is greater than the ledger version {}",
+        version,
+        request.version()
+    );
+    Ok(account_at_version(&service, &request, address, version)?.1)
+}
+
+/// Returns the balance of the account at `from_version` and at `to_version`, both proven against
+/// the current ledger version, along with the payments it sent and received in between.
+/// If there are more payments than `page_size_limit`, only those up to `next_from_version` are
+/// returned, and the rest can be fetched by calling again from there
+async fn get_balance_history(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<BalanceHistoryView> {
+    let address: String = serde_json::from_value(request.get_param(0))?;
+    let from_version: u64 = serde_json::from_value(request.get_param(1))?;
+    let to_version: u64 = serde_json::from_value(request.get_param(2))?;
+    let address = AccountAddress::from_str(&address)?;
+
+    ensure!(
+        from_version <= to_version,
+        "from_version {} is greater than to_version {}",
+        from_version,
+        to_version
+    );
+    ensure!(
+        to_version <= request.version(),
+        "to_version {} is greater than the ledger version {}",
+        to_version,
+        request.version()
+    );
+
+    let (start_account, start) = account_at_version(&service, &request, address, from_version)?;
+    let (end_account, end) = account_at_version(&service, &request, address, to_version)?;
+
+    // Payment events are numbered per account, so the ones emitted after `from_version` are
+    // exactly those between the event counts at the two versions.
+    let mut payments = vec![];
+    let mut next_from_version = None;
+    if let Some(end_account) = end_account {
+        let ranges = [
+            (
+                end_account.sent_events(),
+                start_account
+                    .as_ref()
+                    .map_or(0, |account| account.sent_events().count()),
+            ),
+            (
+                end_account.received_events(),
+                start_account
+                    .as_ref()
+                    .map_or(0, |account| account.received_events().count()),
+            ),
+        ];
+        // Each handle's events are in version order, so the first `limit + 1` of each are enough
+        // to find the first `limit + 1` payments overall.
+        let limit = u64::from(service.page_size_limit);
+        for (handle, start) in ranges.iter() {
+            if handle.count() > *start {
+                let count = std::cmp::min(handle.count() - start, limit + 1);
+                let events = service.db.get_events(handle.key(), *start, true, count)?;
+                payments.extend(events.into_iter().map(EventView::from));
+            }
+        }
+        payments.sort_by_key(|event| event.transaction_version);
+
+        if payments.len() as u64 > limit {
+            // A version is returned with all its payments or not at all, so that the next call
+            // can start right before it.
+            let cut_version = payments[limit as usize].transaction_version;
+            payments.retain(|event| event.transaction_version < cut_version);
+            if payments.is_empty() {
+                let count = ranges
+                    .iter()
+                    .map(|(handle, start)| handle.count().saturating_sub(*start))
+                    .sum();
+                return Err(
+                    JsonRpcError::page_size_too_large(count, service.page_size_limit).into(),
+                );
+            }
+            next_from_version = Some(cut_version - 1);
+        }
+    }
+
+    Ok(BalanceHistoryView {
+        start,
+        end,
+        payments,
+        next_from_version,
+    })
+}
+
+/// Returns the account resource at `version` along with its view
+fn account_at_version(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+    address: AccountAddress,
+    version: u64,
+) -> Result<(Option<AccountResource>, AccountAtVersionView)> {
+    let (account_state, account_state_with_proof) =
+        get_account_state_and_proof(service, request, address, version)?;
+    let (account, account_view) = match account_state {
+        Some(account_state) => match (
+            account_state.get_account_resource()?,
+            account_state.get_balance_resource()?,
+        ) {
+            (Some(account), Some(balance)) => {
+                let view = AccountView::new(&account, &balance);
+                (Some(account), Some(view))
+            }
+            _ => (None, None),
+        },
+        None => (None, None),
+    };
+    Ok((
+        account,
+        AccountAtVersionView {
+            version,
+            account: account_view,
+            account_state_with_proof,
+        },
+    ))
+}
+
 /// Builds registry of all available RPC methods
 /// To register new RPC method, add it via `register_rpc_method!` macros call
 /// Note that RPC method name will equal to name of function
 pub(crate) fn build_registry() -> RpcRegistry {
     let mut registry = RpcRegistry::new();
     register_rpc_method!(registry, submit, 1);
+    register_rpc_method!(registry, submit_and_wait, 1);
+    register_rpc_method!(registry, simulate, 2);
     register_rpc_method!(registry, get_metadata, 0);
     register_rpc_method!(registry, get_account_state, 1);
     register_rpc_method!(registry, get_transactions, 3);
     register_rpc_method!(registry, get_account_transaction, 3);
+    register_rpc_method!(registry, get_transaction_by_hash, 2);
     register_rpc_method!(registry, get_events, 3);
 
     register_rpc_method!(registry, get_state_proof, 1);
     register_rpc_method!(registry, get_account_state_with_proof, 3);
+    register_rpc_method!(registry, get_account_states_in_range, 3);
+    register_rpc_method!(registry, get_account_state_at_version, 2);
+    register_rpc_method!(registry, get_balance_history, 3);
+
+    register_rpc_method!(registry, get_validator_set, 0);
+    register_rpc_method!(registry, get_on_chain_configs, 0);
+    register_rpc_method!(registry, get_epoch_info, 0);
+
+    register_rpc_method!(registry, get_pending_transactions, 1);
+    register_rpc_method!(registry, get_pending_transaction, 2);
+    register_rpc_method!(registry, get_mempool_size, 0);
+
+    // OpenRPC requires a name that can't be a function name, so it's registered by hand
+    registry.insert(
+        DISCOVER_METHOD.to_string(),
+        Box::new(|_service, request| {
+            Box::pin(async move {
+                ensure!(request.params.is_empty(), "Invalid number of arguments");
+                Ok(openrpc_document())
+            })
+        }),
+    );
 
     registry
 }
@@ -0,0 +1,73 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-IP token bucket rate limiting of JSON RPC requests
+
+use libra_config::config::RateLimitConfig;
+use lru_cache::LruCache;
+use std::{
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The most IPs tracked at once. Beyond that, the bucket of the least recently seen IP is dropped,
+/// which lets that IP start over with a full bucket.
+pub(crate) const MAX_TRACKED_IPS: usize = 10_000;
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<LruCache<IpAddr, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(LruCache::new(MAX_TRACKED_IPS)),
+        }
+    }
+
+    /// Takes `num_requests` tokens from the bucket of `ip`. Returns false and takes nothing if
+    /// there aren't enough tokens left.
+    pub fn try_acquire(&self, ip: IpAddr, num_requests: usize) -> bool {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if !buckets.contains_key(&ip) {
+            buckets.insert(
+                ip,
+                TokenBucket {
+                    tokens: self.config.burst_size as f64,
+                    last_refill: now,
+                },
+            );
+        }
+        let bucket = buckets.get_mut(&ip).expect("Bucket was just inserted.");
+        Self::refill(&self.config, bucket, now);
+        if bucket.tokens < num_requests as f64 {
+            return false;
+        }
+        bucket.tokens -= num_requests as f64;
+        true
+    }
+
+    /// Returns the number of IPs with a bucket.
+    #[cfg(test)]
+    pub fn num_tracked_ips(&self) -> usize {
+        self.buckets.lock().unwrap().len()
+    }
+
+    fn refill(config: &RateLimitConfig, bucket: &mut TokenBucket, now: Instant) {
+        let elapsed = now
+            .checked_duration_since(bucket.last_refill)
+            .unwrap_or_else(|| Duration::from_secs(0));
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * config.requests_per_sec as f64)
+            .min(config.burst_size as f64);
+        bucket.last_refill = now;
+    }
+}
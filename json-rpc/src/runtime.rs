@@ -5,26 +5,45 @@ use crate::{
     counters,
     errors::JsonRpcError,
     methods::{build_registry, JsonRpcRequest, JsonRpcService, RpcRegistry},
+    rate_limiter::RateLimiter,
+    stream::{handle_connection, Streams, MAX_CONNECTIONS},
 };
 use futures::future::join_all;
-use libra_config::config::NodeConfig;
-use libra_mempool::MempoolClientSender;
+use libra_config::config::{NodeConfig, RpcConfig};
+use libra_mempool::{MempoolClientSender, MempoolInspectionSender};
 use libra_types::ledger_info::LedgerInfoWithSignatures;
 use serde_json::{map::Map, Value};
 use std::{net::SocketAddr, sync::Arc};
 use storage_interface::DbReader;
 use tokio::runtime::{Builder, Runtime};
 use warp::{
-    reject::{self, Reject},
-    Filter,
+    reject::{self, PayloadTooLarge, Reject},
+    ws::Ws,
+    Filter, Rejection, Reply,
 };
 
-/// Creates HTTP server (warp-based) that serves JSON RPC requests
+/// Creates HTTP server (warp-based) that serves JSON RPC requests, and subscriptions over
+/// WebSocket at `/stream`, with the default request limits
 /// Returns handle to corresponding Tokio runtime
 pub fn bootstrap(
     address: SocketAddr,
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
+    mp_inspector: MempoolInspectionSender,
+) -> Runtime {
+    let config = RpcConfig {
+        address,
+        ..RpcConfig::default()
+    };
+    bootstrap_with_config(&config, libra_db, mp_sender, mp_inspector)
+}
+
+/// Same as `bootstrap`, but enforces the request limits in `config`
+pub fn bootstrap_with_config(
+    config: &RpcConfig,
+    libra_db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+    mp_inspector: MempoolInspectionSender,
 ) -> Runtime {
     let runtime = Builder::new()
         .thread_name("rpc-")
@@ -34,16 +53,40 @@ pub fn bootstrap(
         .expect("[rpc] failed to create runtime");
 
     let registry = Arc::new(build_registry());
-    let service = JsonRpcService::new(libra_db, mp_sender);
+    let service = JsonRpcService::new(libra_db, mp_sender, mp_inspector, config.page_size_limit);
+    let content_length_limit = config.content_length_limit;
+    let limits = Arc::new(RequestLimits {
+        batch_size_limit: config.batch_size_limit,
+        content_length_limit,
+        rate_limiter: config.ip_rate_limit.map(RateLimiter::new),
+    });
+
+    let stream_service = service.clone();
+    let stream_limits = Arc::clone(&limits);
+    let streams = Streams::new(service.clone(), runtime.handle());
+    let stream_handler = warp::path("stream")
+        .and(warp::path::end())
+        .and(warp::ws())
+        .and(warp::addr::remote())
+        .and(warp::any().map(move || stream_service.clone()))
+        .and(warp::any().map(move || Arc::clone(&stream_limits)))
+        .and(warp::any().map(move || streams.clone()))
+        .and_then(stream_endpoint);
 
     let handler = warp::any()
         .and(warp::path::end())
         .and(warp::post())
         .and(warp::header::exact("content-type", "application/json"))
+        .and(warp::body::content_length_limit(
+            content_length_limit as u64,
+        ))
         .and(warp::body::json())
+        .and(warp::addr::remote())
         .and(warp::any().map(move || service.clone()))
         .and(warp::any().map(move || Arc::clone(&registry)))
-        .and_then(rpc_endpoint);
+        .and(warp::any().map(move || Arc::clone(&limits)))
+        .and_then(rpc_endpoint)
+        .recover(move |rejection| handle_rejection(rejection, content_length_limit));
 
     // Ensure that we actually bind to the socket first before spawning the
     // server tasks. This helps in tests to prevent races where a client attempts
@@ -52,7 +95,7 @@ pub fn bootstrap(
     //
     // Note: we need to enter the runtime context first to actually bind, since
     //       tokio TcpListener can only be bound inside a tokio context.
-    let server = runtime.enter(move || warp::serve(handler).bind(address));
+    let server = runtime.enter(move || warp::serve(stream_handler.or(handler)).bind(address));
     runtime.handle().spawn(server);
     runtime
 }
@@ -62,8 +105,87 @@ pub fn bootstrap_from_config(
     config: &NodeConfig,
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
+    mp_inspector: MempoolInspectionSender,
 ) -> Runtime {
-    bootstrap(config.rpc.address, libra_db, mp_sender)
+    bootstrap_with_config(&config.rpc, libra_db, mp_sender, mp_inspector)
+}
+
+/// Limits checked before a request (or a batch of them) gets handled, whether it comes over HTTP
+/// or WebSocket
+pub(crate) struct RequestLimits {
+    batch_size_limit: u16,
+    content_length_limit: usize,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl RequestLimits {
+    /// Checks the size of a batch of `num_requests` requests from `remote_address` and takes them
+    /// from its rate limit
+    pub(crate) fn check(
+        &self,
+        remote_address: Option<SocketAddr>,
+        num_requests: usize,
+    ) -> Result<(), JsonRpcError> {
+        if num_requests > self.batch_size_limit as usize {
+            counters::INVALID_REQUESTS
+                .with_label_values(&["batch_size_too_large"])
+                .inc();
+            return Err(JsonRpcError::batch_size_too_large(
+                num_requests,
+                self.batch_size_limit,
+            ));
+        }
+        if let (Some(rate_limiter), Some(remote_address)) = (&self.rate_limiter, remote_address) {
+            if !rate_limiter.try_acquire(remote_address.ip(), num_requests) {
+                counters::INVALID_REQUESTS
+                    .with_label_values(&["rate_limited"])
+                    .inc();
+                return Err(JsonRpcError::rate_limited());
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the size of a request body that wasn't limited while being received, e.g. a
+    /// WebSocket message
+    pub(crate) fn check_content_length(&self, content_length: usize) -> Result<(), JsonRpcError> {
+        if content_length > self.content_length_limit {
+            counters::INVALID_REQUESTS
+                .with_label_values(&["content_too_large"])
+                .inc();
+            return Err(JsonRpcError::content_too_large(self.content_length_limit));
+        }
+        Ok(())
+    }
+}
+
+/// WebSocket entry point
+/// Upgrades the connection if the client is within its rate limit and the server has fewer than
+/// `MAX_CONNECTIONS` connections, and counts every subscription request sent over it against the
+/// same limits as HTTP requests
+async fn stream_endpoint(
+    ws: Ws,
+    remote_address: Option<SocketAddr>,
+    service: JsonRpcService,
+    limits: Arc<RequestLimits>,
+    streams: Streams,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    if let Err(err) = limits.check(remote_address, 1) {
+        return Ok(Box::new(warp::reply::json(&error_response(err))));
+    }
+    let permit = match streams.try_connect() {
+        Some(permit) => permit,
+        None => {
+            counters::INVALID_REQUESTS
+                .with_label_values(&["too_many_connections"])
+                .inc();
+            let err = JsonRpcError::too_many_connections(MAX_CONNECTIONS);
+            return Ok(Box::new(warp::reply::json(&error_response(err))));
+        }
+    };
+    Ok(Box::new(ws.on_upgrade(move |websocket| {
+        handle_connection(websocket, remote_address, service, limits, streams, permit)
+    })))
 }
 
 /// JSON RPC entry point
@@ -71,9 +193,19 @@ pub fn bootstrap_from_config(
 /// Performs routing based on methods defined in `registry`
 async fn rpc_endpoint(
     data: Value,
+    remote_address: Option<SocketAddr>,
     service: JsonRpcService,
     registry: Arc<RpcRegistry>,
+    limits: Arc<RequestLimits>,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let num_requests = match &data {
+        Value::Array(requests) => requests.len(),
+        _ => 1,
+    };
+    if let Err(err) = limits.check(remote_address, num_requests) {
+        return Ok(Box::new(warp::reply::json(&error_response(err))));
+    }
+
     // take snapshot of latest version of DB to be used across all requests, especially for batched requests
     let ledger_info = service
         .get_latest_ledger_info()
@@ -97,6 +229,30 @@ async fn rpc_endpoint(
     }
 }
 
+/// Turns an oversized request body into a JSON RPC error, and passes other rejections through
+async fn handle_rejection(
+    rejection: Rejection,
+    content_length_limit: usize,
+) -> Result<impl Reply, Rejection> {
+    if rejection.find::<PayloadTooLarge>().is_some() {
+        counters::INVALID_REQUESTS
+            .with_label_values(&["content_too_large"])
+            .inc();
+        let err = JsonRpcError::content_too_large(content_length_limit);
+        return Ok(warp::reply::json(&error_response(err)));
+    }
+    Err(rejection)
+}
+
+/// Response to a request that was rejected as a whole, before its id could be read
+fn error_response(err: JsonRpcError) -> Value {
+    let mut response = Map::new();
+    response.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+    response.insert("id".to_string(), Value::Null);
+    response.insert("error".to_string(), err.serialize());
+    Value::Object(response)
+}
+
 /// Handler of single RPC request
 /// Performs validation and executes corresponding rpc handler
 async fn rpc_request_handler(
@@ -220,7 +376,7 @@ async fn rpc_request_handler(
     Value::Object(response)
 }
 
-fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError> {
+pub(crate) fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError> {
     match request.get("id") {
         Some(req_id) => {
             if req_id.is_string() || req_id.is_number() || req_id.is_null() {
@@ -233,7 +389,7 @@ fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError>
     }
 }
 
-fn verify_protocol(request: &Map<String, Value>) -> Result<(), JsonRpcError> {
+pub(crate) fn verify_protocol(request: &Map<String, Value>) -> Result<(), JsonRpcError> {
     if let Some(Value::String(protocol)) = request.get("jsonrpc") {
         if protocol == "2.0" {
             return Ok(());
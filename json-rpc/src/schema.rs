@@ -0,0 +1,904 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenRPC document describing the JSON-RPC API
+//!
+//! Every view in `views.rs` that shows up in a response describes its JSON form via
+//! `ViewSchema`, and every method registered in `methods::build_registry` has an entry in
+//! `method_specs`. The document is served by the `rpc.discover` method, as OpenRPC specifies.
+//! Subscriptions over WebSocket aren't part of it, since they don't go through the registry.
+//! The schemas are written by hand, so the tests check them against a fixture of every variant of
+//! the enum views, as well as against real responses.
+//!
+//! Specification: https://spec.open-rpc.org
+use crate::views::{
+    AccountAtVersionView, AccountStateBlobView, AccountStateChunkView, AccountStateProofView,
+    AccountStateWithProofView, AccountView, BalanceHistoryView, BlockMetadata, BytesView,
+    EpochInfoView, EventDataView, EventView, GasCostView, GasScheduleView, MempoolSizeView,
+    OnChainConfigsView, PendingTransactionStatusView, PendingTransactionView,
+    PendingTransactionsView, PublishingOptionView, ScriptView, SimulationView, StateProofView,
+    TransactionDataView, TransactionStatusDataView, TransactionStatusView, TransactionView,
+    ValidatorInfoView, ValidatorSetMemberView, ValidatorSetView, WriteOpView,
+};
+use libra_types::vm_error::VMStatus;
+use serde_json::{json, Map, Value};
+
+/// Version of the OpenRPC specification the document follows
+pub const OPENRPC_VERSION: &str = "1.2.4";
+
+/// Name of the OpenRPC service discovery method
+pub const DISCOVER_METHOD: &str = "rpc.discover";
+
+/// Implemented by views to describe their serialized form as a JSON Schema
+pub trait ViewSchema {
+    /// Name of the schema under `components/schemas`
+    const NAME: &'static str;
+
+    /// JSON Schema of the view. Nested views are referenced by `reference`
+    fn schema() -> Value;
+}
+
+/// Reference to the schema of `T` in `components/schemas`
+pub fn reference<T: ViewSchema>() -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", T::NAME) })
+}
+
+fn unsigned() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+fn null() -> Value {
+    json!({ "type": "null" })
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "oneOf": [null(), schema] })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+/// Object with exactly the given fields
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    let required: Vec<_> = fields.iter().map(|(name, _)| json!(name)).collect();
+    let properties: Map<String, Value> = fields
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), schema))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Enum serialized with `#[serde(tag = "type")]`: an object per variant, told apart by `type`
+fn tagged(variants: Vec<(&str, Vec<(&str, Value)>)>) -> Value {
+    let variants: Vec<_> = variants
+        .into_iter()
+        .map(|(tag, mut fields)| {
+            fields.insert(0, ("type", json!({ "const": tag })));
+            object(fields)
+        })
+        .collect();
+    json!({ "oneOf": variants })
+}
+
+impl ViewSchema for BytesView {
+    const NAME: &'static str = "Bytes";
+
+    fn schema() -> Value {
+        json!({ "type": "string", "description": "hex-encoded bytes" })
+    }
+}
+
+impl ViewSchema for AccountView {
+    const NAME: &'static str = "Account";
+
+    fn schema() -> Value {
+        object(vec![
+            ("balance", unsigned()),
+            ("sequence_number", unsigned()),
+            ("authentication_key", reference::<BytesView>()),
+            ("sent_events_key", reference::<BytesView>()),
+            ("received_events_key", reference::<BytesView>()),
+            ("delegated_key_rotation_capability", boolean()),
+            ("delegated_withdrawal_capability", boolean()),
+        ])
+    }
+}
+
+impl ViewSchema for EventView {
+    const NAME: &'static str = "Event";
+
+    fn schema() -> Value {
+        object(vec![
+            ("key", reference::<BytesView>()),
+            ("sequence_number", unsigned()),
+            ("transaction_version", unsigned()),
+            ("data", reference::<EventDataView>()),
+        ])
+    }
+}
+
+impl ViewSchema for EventDataView {
+    const NAME: &'static str = "EventData";
+
+    fn schema() -> Value {
+        tagged(vec![
+            (
+                "receivedpayment",
+                vec![
+                    ("amount", unsigned()),
+                    ("sender", reference::<BytesView>()),
+                    ("metadata", reference::<BytesView>()),
+                ],
+            ),
+            (
+                "sentpayment",
+                vec![
+                    ("amount", unsigned()),
+                    ("receiver", reference::<BytesView>()),
+                    ("metadata", reference::<BytesView>()),
+                ],
+            ),
+            (
+                "newblock",
+                vec![
+                    ("round", unsigned()),
+                    ("proposer", reference::<BytesView>()),
+                    ("votes", array(reference::<BytesView>())),
+                    ("timestamp_usecs", unsigned()),
+                ],
+            ),
+            ("newepoch", vec![("epoch", unsigned())]),
+            (
+                "discoverysetchange",
+                vec![("validators", array(reference::<BytesView>()))],
+            ),
+            (
+                "validatorsetchange",
+                vec![("validators", array(reference::<ValidatorSetMemberView>()))],
+            ),
+            (
+                "upgrade",
+                vec![("writeset_payload", reference::<BytesView>())],
+            ),
+            ("unknown", vec![]),
+        ])
+    }
+}
+
+impl ViewSchema for BlockMetadata {
+    const NAME: &'static str = "BlockMetadata";
+
+    fn schema() -> Value {
+        object(vec![("version", unsigned()), ("timestamp", unsigned())])
+    }
+}
+
+impl ViewSchema for TransactionView {
+    const NAME: &'static str = "Transaction";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("transaction", reference::<TransactionDataView>()),
+            ("events", array(reference::<EventView>())),
+            ("vm_status", unsigned()),
+            ("gas_used", unsigned()),
+        ])
+    }
+}
+
+impl ViewSchema for TransactionDataView {
+    const NAME: &'static str = "TransactionData";
+
+    fn schema() -> Value {
+        tagged(vec![
+            ("blockmetadata", vec![("timestamp_usecs", unsigned())]),
+            ("writeset", vec![]),
+            (
+                "user",
+                vec![
+                    ("sender", string()),
+                    ("signature_scheme", string()),
+                    ("signature", string()),
+                    ("public_key", string()),
+                    ("sequence_number", unsigned()),
+                    ("max_gas_amount", unsigned()),
+                    ("gas_unit_price", unsigned()),
+                    ("expiration_time", unsigned()),
+                    ("script_hash", string()),
+                    ("script", reference::<ScriptView>()),
+                ],
+            ),
+            ("unknown", vec![]),
+        ])
+    }
+}
+
+impl ViewSchema for ScriptView {
+    const NAME: &'static str = "Script";
+
+    fn schema() -> Value {
+        tagged(vec![
+            (
+                "peer_to_peer_transaction",
+                vec![
+                    ("currency", string()),
+                    ("receiver", string()),
+                    ("auth_key_prefix", reference::<BytesView>()),
+                    ("amount", unsigned()),
+                    ("metadata", reference::<BytesView>()),
+                ],
+            ),
+            (
+                "mint_transaction",
+                vec![
+                    ("receiver", string()),
+                    ("auth_key_prefix", reference::<BytesView>()),
+                    ("amount", unsigned()),
+                ],
+            ),
+            ("add_validator_transaction", vec![("validator", string())]),
+            (
+                "approved_payment_transaction",
+                vec![
+                    ("currency", string()),
+                    ("payee", string()),
+                    ("amount", unsigned()),
+                    ("metadata", reference::<BytesView>()),
+                    ("signature", reference::<BytesView>()),
+                ],
+            ),
+            (
+                "burn_transaction",
+                vec![("currency", string()), ("preburn_address", string())],
+            ),
+            (
+                "cancel_burn_transaction",
+                vec![("currency", string()), ("preburn_address", string())],
+            ),
+            (
+                "create_account_transaction",
+                vec![
+                    ("address", string()),
+                    ("auth_key_prefix", reference::<BytesView>()),
+                    ("initial_amount", unsigned()),
+                ],
+            ),
+            ("empty_script_transaction", vec![]),
+            (
+                "modify_publishing_option_transaction",
+                vec![("publishing_option", reference::<BytesView>())],
+            ),
+            (
+                "preburn_transaction",
+                vec![("currency", string()), ("amount", unsigned())],
+            ),
+            (
+                "register_approved_payment_transaction",
+                vec![("public_key", reference::<BytesView>())],
+            ),
+            (
+                "register_preburner_transaction",
+                vec![("currency", string())],
+            ),
+            (
+                "register_validator_transaction",
+                vec![
+                    ("consensus_pubkey", reference::<BytesView>()),
+                    ("validator_network_signing_pubkey", reference::<BytesView>()),
+                    (
+                        "validator_network_identity_pubkey",
+                        reference::<BytesView>(),
+                    ),
+                    ("validator_network_address", reference::<BytesView>()),
+                    (
+                        "fullnodes_network_identity_pubkey",
+                        reference::<BytesView>(),
+                    ),
+                    ("fullnodes_network_address", reference::<BytesView>()),
+                ],
+            ),
+            (
+                "remove_validator_transaction",
+                vec![("validator", string())],
+            ),
+            (
+                "rotate_authentication_key_transaction",
+                vec![("new_key", reference::<BytesView>())],
+            ),
+            (
+                "rotate_consensus_pubkey_transaction",
+                vec![("new_key", reference::<BytesView>())],
+            ),
+            (
+                "update_libra_version_transaction",
+                vec![("major", unsigned())],
+            ),
+            ("unknown_transaction", vec![]),
+        ])
+    }
+}
+
+impl ViewSchema for TransactionStatusView {
+    const NAME: &'static str = "TransactionStatus";
+
+    fn schema() -> Value {
+        object(vec![
+            ("hash", string()),
+            ("status", reference::<TransactionStatusDataView>()),
+        ])
+    }
+}
+
+impl ViewSchema for TransactionStatusDataView {
+    const NAME: &'static str = "TransactionStatusData";
+
+    fn schema() -> Value {
+        tagged(vec![
+            (
+                "committed",
+                vec![("transaction", reference::<TransactionView>())],
+            ),
+            ("expired", vec![]),
+            ("dropped", vec![]),
+            ("pending", vec![]),
+        ])
+    }
+}
+
+impl ViewSchema for VMStatus {
+    const NAME: &'static str = "VMStatus";
+
+    fn schema() -> Value {
+        object(vec![
+            ("major_status", unsigned()),
+            ("sub_status", nullable(unsigned())),
+            ("message", nullable(string())),
+        ])
+    }
+}
+
+impl ViewSchema for SimulationView {
+    const NAME: &'static str = "Simulation";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("discarded", boolean()),
+            ("vm_status", reference::<VMStatus>()),
+            ("gas_used", unsigned()),
+            ("events", array(reference::<EventView>())),
+            ("write_set", array(reference::<WriteOpView>())),
+        ])
+    }
+}
+
+impl ViewSchema for WriteOpView {
+    const NAME: &'static str = "WriteOp";
+
+    fn schema() -> Value {
+        object(vec![
+            ("address", string()),
+            ("path", reference::<BytesView>()),
+            ("value", nullable(reference::<BytesView>())),
+        ])
+    }
+}
+
+impl ViewSchema for StateProofView {
+    const NAME: &'static str = "StateProof";
+
+    fn schema() -> Value {
+        object(vec![
+            ("ledger_info_with_signatures", reference::<BytesView>()),
+            ("validator_change_proof", reference::<BytesView>()),
+            ("ledger_consistency_proof", reference::<BytesView>()),
+        ])
+    }
+}
+
+impl ViewSchema for AccountStateWithProofView {
+    const NAME: &'static str = "AccountStateWithProof";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("blob", nullable(reference::<BytesView>())),
+            ("proof", reference::<AccountStateProofView>()),
+        ])
+    }
+}
+
+impl ViewSchema for AccountStateProofView {
+    const NAME: &'static str = "AccountStateProof";
+
+    fn schema() -> Value {
+        object(vec![
+            (
+                "ledger_info_to_transaction_info_proof",
+                reference::<BytesView>(),
+            ),
+            ("transaction_info", reference::<BytesView>()),
+            (
+                "transaction_info_to_account_proof",
+                reference::<BytesView>(),
+            ),
+        ])
+    }
+}
+
+impl ViewSchema for AccountStateChunkView {
+    const NAME: &'static str = "AccountStateChunk";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("accounts", array(reference::<AccountStateBlobView>())),
+            ("proof", reference::<BytesView>()),
+            ("next_key", nullable(reference::<BytesView>())),
+        ])
+    }
+}
+
+impl ViewSchema for AccountStateBlobView {
+    const NAME: &'static str = "AccountStateBlob";
+
+    fn schema() -> Value {
+        object(vec![
+            ("key", reference::<BytesView>()),
+            ("blob", reference::<BytesView>()),
+        ])
+    }
+}
+
+impl ViewSchema for AccountAtVersionView {
+    const NAME: &'static str = "AccountAtVersion";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("account", nullable(reference::<AccountView>())),
+            (
+                "account_state_with_proof",
+                reference::<AccountStateWithProofView>(),
+            ),
+        ])
+    }
+}
+
+impl ViewSchema for BalanceHistoryView {
+    const NAME: &'static str = "BalanceHistory";
+
+    fn schema() -> Value {
+        object(vec![
+            ("start", reference::<AccountAtVersionView>()),
+            ("end", reference::<AccountAtVersionView>()),
+            ("payments", array(reference::<EventView>())),
+            ("next_from_version", nullable(unsigned())),
+        ])
+    }
+}
+
+impl ViewSchema for ValidatorSetView {
+    const NAME: &'static str = "ValidatorSet";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("validators", array(reference::<ValidatorInfoView>())),
+            (
+                "account_state_with_proof",
+                reference::<AccountStateWithProofView>(),
+            ),
+        ])
+    }
+}
+
+impl ViewSchema for ValidatorInfoView {
+    const NAME: &'static str = "ValidatorInfo";
+
+    fn schema() -> Value {
+        object(vec![
+            ("account_address", string()),
+            ("consensus_public_key", reference::<BytesView>()),
+            ("consensus_voting_power", unsigned()),
+            ("network_signing_public_key", reference::<BytesView>()),
+            ("network_identity_public_key", reference::<BytesView>()),
+        ])
+    }
+}
+
+impl ViewSchema for ValidatorSetMemberView {
+    const NAME: &'static str = "ValidatorSetMember";
+
+    fn schema() -> Value {
+        object(vec![
+            ("account_address", string()),
+            ("consensus_public_key", reference::<BytesView>()),
+            ("consensus_voting_power", unsigned()),
+        ])
+    }
+}
+
+impl ViewSchema for OnChainConfigsView {
+    const NAME: &'static str = "OnChainConfigs";
+
+    fn schema() -> Value {
+        object(vec![
+            ("version", unsigned()),
+            ("libra_version", nullable(unsigned())),
+            (
+                "publishing_option",
+                nullable(reference::<PublishingOptionView>()),
+            ),
+            ("gas_schedule", nullable(reference::<GasScheduleView>())),
+            (
+                "account_state_with_proof",
+                reference::<AccountStateWithProofView>(),
+            ),
+        ])
+    }
+}
+
+impl ViewSchema for PublishingOptionView {
+    const NAME: &'static str = "PublishingOption";
+
+    fn schema() -> Value {
+        tagged(vec![
+            (
+                "locked",
+                vec![("whitelist", array(reference::<BytesView>()))],
+            ),
+            ("custom_scripts", vec![]),
+            ("open", vec![]),
+        ])
+    }
+}
+
+impl ViewSchema for GasScheduleView {
+    const NAME: &'static str = "GasSchedule";
+
+    fn schema() -> Value {
+        object(vec![
+            ("instruction_schedule", array(reference::<GasCostView>())),
+            ("native_schedule", array(reference::<GasCostView>())),
+        ])
+    }
+}
+
+impl ViewSchema for GasCostView {
+    const NAME: &'static str = "GasCost";
+
+    fn schema() -> Value {
+        object(vec![("cpu", unsigned()), ("storage", unsigned())])
+    }
+}
+
+impl ViewSchema for EpochInfoView {
+    const NAME: &'static str = "EpochInfo";
+
+    fn schema() -> Value {
+        object(vec![
+            ("epoch", unsigned()),
+            ("last_reconfiguration_time", unsigned()),
+            ("ledger_version", unsigned()),
+            (
+                "account_state_with_proof",
+                reference::<AccountStateWithProofView>(),
+            ),
+        ])
+    }
+}
+
+impl ViewSchema for PendingTransactionsView {
+    const NAME: &'static str = "PendingTransactions";
+
+    fn schema() -> Value {
+        object(vec![
+            ("account_sequence_number", nullable(unsigned())),
+            ("transactions", array(reference::<PendingTransactionView>())),
+        ])
+    }
+}
+
+impl ViewSchema for PendingTransactionView {
+    const NAME: &'static str = "PendingTransaction";
+
+    fn schema() -> Value {
+        object(vec![
+            ("sequence_number", unsigned()),
+            ("hash", string()),
+            ("transaction", reference::<TransactionDataView>()),
+            ("status", reference::<PendingTransactionStatusView>()),
+            ("system_expiration_time", unsigned()),
+        ])
+    }
+}
+
+impl ViewSchema for PendingTransactionStatusView {
+    const NAME: &'static str = "PendingTransactionStatus";
+
+    fn schema() -> Value {
+        tagged(vec![
+            ("ready", vec![]),
+            ("parked", vec![("missing_sequence_number", unsigned())]),
+        ])
+    }
+}
+
+impl ViewSchema for MempoolSizeView {
+    const NAME: &'static str = "MempoolSize";
+
+    fn schema() -> Value {
+        object(vec![
+            ("total", unsigned()),
+            ("ready", unsigned()),
+            ("parked", unsigned()),
+        ])
+    }
+}
+
+fn add<T: ViewSchema>(schemas: &mut Map<String, Value>) {
+    schemas.insert(T::NAME.to_string(), T::schema());
+}
+
+/// Schemas of all views, keyed by `ViewSchema::NAME`
+pub fn component_schemas() -> Map<String, Value> {
+    let mut schemas = Map::new();
+    add::<BytesView>(&mut schemas);
+    add::<AccountView>(&mut schemas);
+    add::<EventView>(&mut schemas);
+    add::<EventDataView>(&mut schemas);
+    add::<BlockMetadata>(&mut schemas);
+    add::<TransactionView>(&mut schemas);
+    add::<TransactionDataView>(&mut schemas);
+    add::<ScriptView>(&mut schemas);
+    add::<TransactionStatusView>(&mut schemas);
+    add::<TransactionStatusDataView>(&mut schemas);
+    add::<VMStatus>(&mut schemas);
+    add::<SimulationView>(&mut schemas);
+    add::<WriteOpView>(&mut schemas);
+    add::<StateProofView>(&mut schemas);
+    add::<AccountStateWithProofView>(&mut schemas);
+    add::<AccountStateProofView>(&mut schemas);
+    add::<AccountStateChunkView>(&mut schemas);
+    add::<AccountStateBlobView>(&mut schemas);
+    add::<AccountAtVersionView>(&mut schemas);
+    add::<BalanceHistoryView>(&mut schemas);
+    add::<ValidatorSetView>(&mut schemas);
+    add::<ValidatorInfoView>(&mut schemas);
+    add::<ValidatorSetMemberView>(&mut schemas);
+    add::<OnChainConfigsView>(&mut schemas);
+    add::<PublishingOptionView>(&mut schemas);
+    add::<GasScheduleView>(&mut schemas);
+    add::<GasCostView>(&mut schemas);
+    add::<EpochInfoView>(&mut schemas);
+    add::<PendingTransactionsView>(&mut schemas);
+    add::<PendingTransactionView>(&mut schemas);
+    add::<PendingTransactionStatusView>(&mut schemas);
+    add::<MempoolSizeView>(&mut schemas);
+    schemas
+}
+
+/// OpenRPC description of a single method: its positional parameters and its result
+pub struct MethodSpec {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub params: Vec<(&'static str, Value)>,
+    pub result: Value,
+}
+
+impl MethodSpec {
+    fn new(
+        name: &'static str,
+        summary: &'static str,
+        params: Vec<(&'static str, Value)>,
+        result: Value,
+    ) -> Self {
+        Self {
+            name,
+            summary,
+            params,
+            result,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let params: Vec<_> = self
+            .params
+            .iter()
+            .map(|(name, schema)| json!({ "name": name, "required": true, "schema": schema }))
+            .collect();
+        json!({
+            "name": self.name,
+            "summary": self.summary,
+            "paramStructure": "by-position",
+            "params": params,
+            "result": { "name": format!("{}_result", self.name), "schema": self.result },
+        })
+    }
+}
+
+/// Specs of all methods in the registry, plus `rpc.discover`
+pub fn method_specs() -> Vec<MethodSpec> {
+    let account = || ("account", string());
+    let data = || ("data", reference::<BytesView>());
+    let include_events = || ("include_events", boolean());
+    vec![
+        MethodSpec::new(
+            "submit",
+            "Submits a signed transaction to mempool",
+            vec![data()],
+            null(),
+        ),
+        MethodSpec::new(
+            "submit_and_wait",
+            "Submits a signed transaction and waits until it's committed or expires",
+            vec![data()],
+            reference::<TransactionStatusView>(),
+        ),
+        MethodSpec::new(
+            "simulate",
+            "Executes a transaction against the latest state without committing it",
+            vec![data(), ("skip_signature_check", boolean())],
+            reference::<SimulationView>(),
+        ),
+        MethodSpec::new(
+            "get_metadata",
+            "Returns the version and timestamp of the latest ledger info",
+            vec![],
+            reference::<BlockMetadata>(),
+        ),
+        MethodSpec::new(
+            "get_account_state",
+            "Returns the account resource of an account",
+            vec![account()],
+            nullable(reference::<AccountView>()),
+        ),
+        MethodSpec::new(
+            "get_transactions",
+            "Returns a range of committed transactions",
+            vec![
+                ("start_version", unsigned()),
+                ("limit", unsigned()),
+                include_events(),
+            ],
+            array(reference::<TransactionView>()),
+        ),
+        MethodSpec::new(
+            "get_account_transaction",
+            "Returns the committed transaction of an account with the given sequence number",
+            vec![account(), ("sequence_number", unsigned()), include_events()],
+            nullable(reference::<TransactionView>()),
+        ),
+        MethodSpec::new(
+            "get_transaction_by_hash",
+            "Returns the committed transaction with the given hash",
+            vec![("hash", string()), include_events()],
+            nullable(reference::<TransactionView>()),
+        ),
+        MethodSpec::new(
+            "get_events",
+            "Returns a range of events emitted under an event key",
+            vec![
+                ("key", string()),
+                ("start", unsigned()),
+                ("limit", unsigned()),
+            ],
+            array(reference::<EventView>()),
+        ),
+        MethodSpec::new(
+            "get_state_proof",
+            "Returns the proofs that the latest ledger info extends the known version",
+            vec![("known_version", unsigned())],
+            reference::<StateProofView>(),
+        ),
+        MethodSpec::new(
+            "get_account_state_with_proof",
+            "Returns the state of an account at a version, with its proof",
+            vec![
+                account(),
+                ("version", unsigned()),
+                ("ledger_version", unsigned()),
+            ],
+            reference::<AccountStateWithProofView>(),
+        ),
+        MethodSpec::new(
+            "get_account_states_in_range",
+            "Returns a page of account states at a version, with a range proof",
+            vec![
+                ("version", unsigned()),
+                ("start_key", string()),
+                ("limit", unsigned()),
+            ],
+            reference::<AccountStateChunkView>(),
+        ),
+        MethodSpec::new(
+            "get_account_state_at_version",
+            "Returns the account resource of an account at a version, with its proof",
+            vec![account(), ("version", unsigned())],
+            reference::<AccountAtVersionView>(),
+        ),
+        MethodSpec::new(
+            "get_balance_history",
+            "Returns the balances of an account at two versions and the payments in between, \
+             up to the page size limit",
+            vec![
+                account(),
+                ("from_version", unsigned()),
+                ("to_version", unsigned()),
+            ],
+            reference::<BalanceHistoryView>(),
+        ),
+        MethodSpec::new(
+            "get_validator_set",
+            "Returns the current validator set, with the proof of its account state",
+            vec![],
+            reference::<ValidatorSetView>(),
+        ),
+        MethodSpec::new(
+            "get_on_chain_configs",
+            "Returns the current on-chain configs, with the proof of their account state",
+            vec![],
+            reference::<OnChainConfigsView>(),
+        ),
+        MethodSpec::new(
+            "get_epoch_info",
+            "Returns the current epoch, with the proof of its account state",
+            vec![],
+            reference::<EpochInfoView>(),
+        ),
+        MethodSpec::new(
+            "get_pending_transactions",
+            "Returns the transactions of an account waiting in mempool",
+            vec![account()],
+            reference::<PendingTransactionsView>(),
+        ),
+        MethodSpec::new(
+            "get_pending_transaction",
+            "Returns a transaction waiting in mempool, with the reason it's parked",
+            vec![account(), ("sequence_number", unsigned())],
+            nullable(reference::<PendingTransactionView>()),
+        ),
+        MethodSpec::new(
+            "get_mempool_size",
+            "Returns the number of transactions in mempool",
+            vec![],
+            reference::<MempoolSizeView>(),
+        ),
+        MethodSpec::new(
+            DISCOVER_METHOD,
+            "Returns this OpenRPC document",
+            vec![],
+            json!({ "type": "object" }),
+        ),
+    ]
+}
+
+/// OpenRPC document of the JSON-RPC API
+pub fn openrpc_document() -> Value {
+    let methods: Vec<_> = method_specs().iter().map(MethodSpec::to_json).collect();
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "Libra JSON-RPC",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "methods": methods,
+        "components": { "schemas": component_schemas() },
+    })
+}
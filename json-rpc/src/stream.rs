@@ -0,0 +1,400 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebSocket endpoint that pushes new events and transactions to subscribers as they get committed
+//!
+//! Every text message a client sends is a JSON RPC request to one of the subscription methods:
+//!   * `subscribe_to_events(event_key, from_seq)`
+//!   * `subscribe_to_transactions(from_version)`
+//!
+//! The server acknowledges the request with a `null` result, then keeps sending responses with the
+//! same request id, each carrying one `EventView` or `TransactionView` in order, until the
+//! connection closes or an error response is sent. Since each of them carries its sequence number
+//! or version, a client that reconnects resumes by subscribing again from the one after the last
+//! it has received.
+//!
+//! The server accepts up to `MAX_CONNECTIONS` connections, each with up to
+//! `MAX_SUBSCRIPTIONS_PER_CONNECTION` subscriptions. Responses wait in a bounded queue, so a client
+//! that doesn't read them slows down its own subscriptions.
+//!
+//! A single task polls the DB for the latest ledger info and notifies all connections when it
+//! changes. Connections only fetch new items for their subscriptions then, or while catching up.
+
+use crate::{
+    counters,
+    errors::JsonRpcError,
+    methods::{get_events, get_transactions, JsonRpcRequest, JsonRpcService},
+    runtime::{parse_request_id, verify_protocol, RequestLimits},
+};
+use anyhow::{format_err, Result};
+use futures::{
+    channel::mpsc::{channel, Receiver, Sender},
+    future::{self, Either},
+    pin_mut, SinkExt, StreamExt,
+};
+use futures_semaphore::{Permit, Semaphore};
+use libra_types::ledger_info::LedgerInfoWithSignatures;
+use serde_json::{json, map::Map, Value};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{runtime::Handle, sync::watch, time::delay_for};
+use warp::ws::{Message, WebSocket};
+
+/// How often the DB is checked for a new ledger info.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The most WebSocket connections served at once.
+pub(crate) const MAX_CONNECTIONS: usize = 1_000;
+/// The most subscriptions a single connection can have.
+pub(crate) const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 16;
+/// The most responses queued for a connection. Once they pile up, subscriptions stop polling until
+/// the client reads them.
+const MAX_PENDING_MESSAGES: usize = 1_000;
+/// The most items fetched from the DB at once for one subscription, unless the page size limit is
+/// lower.
+const BATCH_SIZE: u64 = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Subscription {
+    Events { event_key: String, from_seq: u64 },
+    Transactions { from_version: u64 },
+}
+
+impl Subscription {
+    fn name(&self) -> &'static str {
+        match self {
+            Subscription::Events { .. } => "subscribe_to_events",
+            Subscription::Transactions { .. } => "subscribe_to_transactions",
+        }
+    }
+
+    /// The sequence number or version of the first item to push.
+    fn cursor(&self) -> u64 {
+        match self {
+            Subscription::Events { from_seq, .. } => *from_seq,
+            Subscription::Transactions { from_version } => *from_version,
+        }
+    }
+
+    /// Fetches the next items committed up to `ledger_info` starting from `cursor`, each paired
+    /// with the cursor right after it. Returns an empty list if there's nothing new.
+    async fn poll(
+        &self,
+        service: &JsonRpcService,
+        ledger_info: LedgerInfoWithSignatures,
+        cursor: u64,
+    ) -> Result<Vec<(u64, Value)>> {
+        let batch_size = BATCH_SIZE.min(u64::from(service.page_size_limit()));
+        match self {
+            Subscription::Events { event_key, .. } => {
+                let request = JsonRpcRequest {
+                    params: vec![json!(event_key), json!(cursor), json!(batch_size)],
+                    ledger_info,
+                };
+                get_events(service.clone(), request)
+                    .await?
+                    .into_iter()
+                    .map(|event| Ok((event.sequence_number + 1, serde_json::to_value(event)?)))
+                    .collect()
+            }
+            Subscription::Transactions { .. } => {
+                let request = JsonRpcRequest {
+                    params: vec![json!(cursor), json!(batch_size), json!(true)],
+                    ledger_info,
+                };
+                get_transactions(service.clone(), request)
+                    .await?
+                    .into_iter()
+                    .map(|txn| Ok((txn.version + 1, serde_json::to_value(txn)?)))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// What all WebSocket connections share: the slots that cap their number, and the latest ledger
+/// info.
+#[derive(Clone)]
+pub(crate) struct Streams {
+    connections: Semaphore,
+    ledger_infos: watch::Receiver<Option<LedgerInfoWithSignatures>>,
+}
+
+impl Streams {
+    /// Starts watching the latest ledger info of `service` on `handle`.
+    pub(crate) fn new(service: JsonRpcService, handle: &Handle) -> Self {
+        let (sender, ledger_infos) = watch::channel(None);
+        handle.spawn(watch_ledger_info(service, sender));
+        Self {
+            connections: Semaphore::new(MAX_CONNECTIONS),
+            ledger_infos,
+        }
+    }
+
+    /// Takes a connection slot, which is given back once the permit is dropped. Returns `None` if
+    /// there are already `MAX_CONNECTIONS` connections.
+    pub(crate) fn try_connect(&self) -> Option<Permit> {
+        self.connections.try_acquire()
+    }
+}
+
+/// Polls the DB for the latest ledger info and notifies all connections whenever its version
+/// changes, so the DB is polled once per `POLL_INTERVAL` however many connections there are.
+async fn watch_ledger_info(
+    service: JsonRpcService,
+    sender: watch::Sender<Option<LedgerInfoWithSignatures>>,
+) {
+    let mut latest_version = None;
+    loop {
+        if let Ok(ledger_info) = service.get_latest_ledger_info() {
+            let version = ledger_info.ledger_info().version();
+            if latest_version != Some(version) {
+                latest_version = Some(version);
+                if sender.broadcast(Some(ledger_info)).is_err() {
+                    return;
+                }
+            }
+        }
+        delay_for(POLL_INTERVAL).await;
+    }
+}
+
+/// Serves one WebSocket connection until the client closes it, holding its slot in `_permit` until
+/// then. Every message is a request subject to `limits`.
+pub(crate) async fn handle_connection(
+    websocket: WebSocket,
+    remote_address: Option<SocketAddr>,
+    service: JsonRpcService,
+    limits: Arc<RequestLimits>,
+    streams: Streams,
+    _permit: Permit,
+) {
+    let (mut ws_sender, mut ws_receiver) = websocket.split();
+    let (mut sender, mut receiver) = channel::<Value>(MAX_PENDING_MESSAGES);
+
+    // Everything sent on this connection goes through the channel, so a client that doesn't keep
+    // up holds back its subscriptions rather than growing the queue.
+    tokio::spawn(async move {
+        while let Some(response) = receiver.next().await {
+            if ws_sender
+                .send(Message::text(response.to_string()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let (mut subscription_sender, subscription_receiver) =
+        channel::<(Value, Subscription)>(MAX_SUBSCRIPTIONS_PER_CONNECTION);
+    tokio::spawn(run_subscriptions(
+        subscription_receiver,
+        service,
+        streams.ledger_infos,
+        sender.clone(),
+    ));
+
+    while let Some(Ok(message)) = ws_receiver.next().await {
+        if message.is_close() {
+            break;
+        }
+        // Pings and binary messages are not requests.
+        let text = match message.to_str() {
+            Ok(text) => text,
+            Err(()) => continue,
+        };
+
+        if let Err(err) = limits
+            .check_content_length(text.len())
+            .and_then(|()| limits.check(remote_address, 1))
+        {
+            if sender.send(response(&Value::Null, Err(err))).await.is_err() {
+                break;
+            }
+            continue;
+        }
+
+        match parse_subscription(text) {
+            Ok((id, subscription)) => {
+                counters::REQUESTS
+                    .with_label_values(&[subscription.name(), "success"])
+                    .inc();
+                if subscription_sender.send((id, subscription)).await.is_err() {
+                    break;
+                }
+            }
+            Err((id, err)) => {
+                counters::INVALID_REQUESTS
+                    .with_label_values(&["invalid_subscription"])
+                    .inc();
+                if sender.send(response(&id, Err(err))).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Stops the subscriptions on this connection.
+    subscription_sender.close_channel();
+}
+
+/// A subscription being served, with the sequence number or version of the next item to push.
+struct ActiveSubscription {
+    id: Value,
+    subscription: Subscription,
+    cursor: u64,
+}
+
+/// Serves all subscriptions of one connection until it closes. A single task fetches new items for
+/// all of them against the same ledger info, and once they have all caught up, it waits for either
+/// a new subscription or a new ledger info.
+async fn run_subscriptions(
+    mut requests: Receiver<(Value, Subscription)>,
+    service: JsonRpcService,
+    mut ledger_infos: watch::Receiver<Option<LedgerInfoWithSignatures>>,
+    mut sender: Sender<Value>,
+) {
+    let mut subscriptions: Vec<ActiveSubscription> = vec![];
+    let mut ledger_info = ledger_infos.borrow().clone();
+    let mut caught_up = true;
+    loop {
+        if caught_up {
+            let new_ledger_info = ledger_infos.recv();
+            pin_mut!(new_ledger_info);
+            match future::select(requests.next(), new_ledger_info).await {
+                Either::Left((Some(request), _)) => {
+                    if !add_subscription(request, &mut subscriptions, &mut sender).await {
+                        return;
+                    }
+                }
+                Either::Right((Some(new_ledger_info), _)) => ledger_info = new_ledger_info,
+                // The connection or the server is closed.
+                Either::Left((None, _)) | Either::Right((None, _)) => return,
+            }
+        }
+        loop {
+            match requests.try_next() {
+                Ok(Some(request)) => {
+                    if !add_subscription(request, &mut subscriptions, &mut sender).await {
+                        return;
+                    }
+                }
+                // The connection is closed.
+                Ok(None) => return,
+                // No new subscriptions.
+                Err(_) => break,
+            }
+        }
+
+        caught_up = true;
+        let ledger_info = match &ledger_info {
+            Some(ledger_info) => ledger_info,
+            // Nothing can be fetched before the first ledger info.
+            None => continue,
+        };
+        let mut index = 0;
+        while index < subscriptions.len() {
+            let active = &mut subscriptions[index];
+            let items = active
+                .subscription
+                .poll(&service, ledger_info.clone(), active.cursor)
+                .await;
+            match items {
+                Ok(items) => {
+                    caught_up &= items.is_empty();
+                    for (next_cursor, item) in items {
+                        if sender.send(response(&active.id, Ok(item))).await.is_err() {
+                            return;
+                        }
+                        active.cursor = next_cursor;
+                    }
+                    index += 1;
+                }
+                Err(err) => {
+                    let err = match err.downcast_ref::<JsonRpcError>() {
+                        Some(custom_error) => custom_error.clone(),
+                        None => JsonRpcError::internal_error(err.to_string()),
+                    };
+                    if sender.send(response(&active.id, Err(err))).await.is_err() {
+                        return;
+                    }
+                    subscriptions.remove(index);
+                }
+            }
+        }
+    }
+}
+
+/// Acknowledges a new subscription and starts serving it, or rejects it if the connection already
+/// has too many. Returns false if the connection is closed.
+async fn add_subscription(
+    (id, subscription): (Value, Subscription),
+    subscriptions: &mut Vec<ActiveSubscription>,
+    sender: &mut Sender<Value>,
+) -> bool {
+    if subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+        let err = JsonRpcError::too_many_subscriptions(MAX_SUBSCRIPTIONS_PER_CONNECTION);
+        return sender.send(response(&id, Err(err))).await.is_ok();
+    }
+    if sender.send(response(&id, Ok(Value::Null))).await.is_err() {
+        return false;
+    }
+    subscriptions.push(ActiveSubscription {
+        cursor: subscription.cursor(),
+        id,
+        subscription,
+    });
+    true
+}
+
+/// Parses a subscription request. On failure, returns the error with the request id if known.
+fn parse_subscription(text: &str) -> Result<(Value, Subscription), (Value, JsonRpcError)> {
+    let request: Map<String, Value> =
+        serde_json::from_str(text).map_err(|_| (Value::Null, JsonRpcError::invalid_request()))?;
+    let id = parse_request_id(&request).map_err(|err| (Value::Null, err))?;
+    verify_protocol(&request).map_err(|err| (id.clone(), err))?;
+
+    let params = match request.get("params") {
+        Some(Value::Array(params)) => params,
+        _ => return Err((id, JsonRpcError::invalid_params())),
+    };
+    let subscription = match request.get("method") {
+        Some(Value::String(method)) => parse_params(method, params)
+            .map_err(|_| (id.clone(), JsonRpcError::invalid_params()))?,
+        _ => return Err((id, JsonRpcError::invalid_request())),
+    };
+    match subscription {
+        Some(subscription) => Ok((id, subscription)),
+        None => Err((id, JsonRpcError::method_not_found())),
+    }
+}
+
+/// Returns `None` if `method` is not a subscription method.
+fn parse_params(method: &str, params: &[Value]) -> Result<Option<Subscription>> {
+    let param = |index: usize| {
+        params
+            .get(index)
+            .cloned()
+            .ok_or_else(|| format_err!("Invalid number of arguments"))
+    };
+    Ok(match method {
+        "subscribe_to_events" => Some(Subscription::Events {
+            event_key: serde_json::from_value(param(0)?)?,
+            from_seq: serde_json::from_value(param(1)?)?,
+        }),
+        "subscribe_to_transactions" => Some(Subscription::Transactions {
+            from_version: serde_json::from_value(param(0)?)?,
+        }),
+        _ => None,
+    })
+}
+
+fn response(id: &Value, result: std::result::Result<Value, JsonRpcError>) -> Value {
+    let mut response = Map::new();
+    response.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+    response.insert("id".to_string(), id.clone());
+    match result {
+        Ok(result) => response.insert("result".to_string(), result),
+        Err(err) => response.insert("error".to_string(), err.serialize()),
+    };
+    Value::Object(response)
+}
@@ -2,17 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{Error, Result};
-use libra_crypto::HashValue;
+use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_types::{
     account_address::AccountAddress,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof, AccountStateWithProof},
     block_info::BlockInfo,
     contract_event::ContractEvent,
     event::EventKey,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proof::{
         AccumulatorConsistencyProof, AccumulatorRangeProof, SparseMerkleProof,
-        TransactionAccumulatorProof, TransactionListProof, TransactionProof,
+        SparseMerkleRangeProof, TransactionAccumulatorProof, TransactionListProof,
+        TransactionProof,
     },
     transaction::{
         Transaction, TransactionInfo, TransactionListWithProof, TransactionWithProof, Version,
@@ -35,6 +36,32 @@ pub(crate) struct MockLibraDB {
     pub account_state_with_proof: Vec<AccountStateWithProof>,
 }
 
+impl MockLibraDB {
+    fn get_transaction_with_proof(&self, v: usize, fetch_events: bool) -> TransactionWithProof {
+        let (x, status) = &self.all_txns[v];
+        TransactionWithProof {
+            version: v as u64,
+            transaction: x.clone(),
+            events: if fetch_events {
+                Some(
+                    self.events
+                        .iter()
+                        .filter(|(ev, _)| *ev == v as u64)
+                        .map(|(_, e)| e)
+                        .cloned()
+                        .collect(),
+                )
+            } else {
+                None
+            },
+            proof: TransactionProof::new(
+                TransactionAccumulatorProof::new(vec![]),
+                TransactionInfo::new(x.hash(), Default::default(), Default::default(), 0, *status),
+            ),
+        }
+    }
+}
+
 impl DbReader for MockLibraDB {
     fn get_latest_account_state(
         &self,
@@ -75,40 +102,27 @@ impl DbReader for MockLibraDB {
         Ok(self
             .all_txns
             .iter()
-            .enumerate()
-            .find(|(_, (x, _))| {
+            .position(|(x, _)| {
                 if let Ok(t) = x.as_signed_user_txn() {
                     t.sender() == address && t.sequence_number() == seq_num
                 } else {
                     false
                 }
             })
-            .map(|(v, (x, status))| TransactionWithProof {
-                version: v as u64,
-                transaction: x.clone(),
-                events: if fetch_events {
-                    Some(
-                        self.events
-                            .iter()
-                            .filter(|(ev, _)| *ev == v as u64)
-                            .map(|(_, e)| e)
-                            .cloned()
-                            .collect(),
-                    )
-                } else {
-                    None
-                },
-                proof: TransactionProof::new(
-                    TransactionAccumulatorProof::new(vec![]),
-                    TransactionInfo::new(
-                        Default::default(),
-                        Default::default(),
-                        Default::default(),
-                        0,
-                        *status,
-                    ),
-                ),
-            }))
+            .map(|v| self.get_transaction_with_proof(v, fetch_events)))
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        _ledger_version: u64,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>, Error> {
+        Ok(self
+            .all_txns
+            .iter()
+            .position(|(x, _)| x.hash() == hash)
+            .map(|v| self.get_transaction_with_proof(v, fetch_events)))
     }
 
     fn get_transactions(
@@ -211,10 +225,17 @@ impl DbReader for MockLibraDB {
     fn get_account_state_with_proof(
         &self,
         _address: AccountAddress,
-        _version: Version,
+        version: Version,
         _ledger_version: Version,
     ) -> Result<AccountStateWithProof> {
-        Ok(self.account_state_with_proof[0].clone())
+        // the states are ordered by version, the latest one at or before `version` is returned
+        Ok(self
+            .account_state_with_proof
+            .iter()
+            .rev()
+            .find(|state| state.version <= version)
+            .unwrap_or(&self.account_state_with_proof[0])
+            .clone())
     }
 
     fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
@@ -235,4 +256,35 @@ impl DbReader for MockLibraDB {
     fn get_latest_state_root(&self) -> Result<(u64, HashValue)> {
         unimplemented!()
     }
+
+    fn get_account_states_in_range(
+        &self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        let mut account_blobs: Vec<_> = self
+            .all_accounts
+            .iter()
+            .map(|(address, blob)| (address.hash(), blob.clone()))
+            .filter(|(key, _)| *key >= start_key)
+            .collect();
+        account_blobs.sort_by_key(|(key, _)| *key);
+        // Only whether there is a non-empty subtree on the right matters to the callers.
+        let right_siblings = if account_blobs.len() > limit as usize {
+            vec![HashValue::random()]
+        } else {
+            vec![]
+        };
+        account_blobs.truncate(limit as usize);
+        Ok(AccountStateChunkWithProof::new(
+            version,
+            account_blobs,
+            SparseMerkleRangeProof::new(right_siblings),
+        ))
+    }
+
+    fn get_least_readable_version(&self) -> Result<u64> {
+        Ok(0)
+    }
 }
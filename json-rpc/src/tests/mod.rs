@@ -2,4 +2,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod mock_db;
+mod schema_tests;
 mod unit_tests;
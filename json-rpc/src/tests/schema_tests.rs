@@ -0,0 +1,546 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    methods::build_registry,
+    runtime::bootstrap,
+    schema::{method_specs, openrpc_document, reference, ViewSchema},
+    views::{
+        BytesView, EventDataView, PendingTransactionStatusView, PublishingOptionView, ScriptView,
+        TransactionDataView, TransactionStatusDataView, TransactionView, ValidatorSetMemberView,
+    },
+};
+use anyhow::{bail, ensure, format_err, Result};
+use executor::db_bootstrapper::maybe_bootstrap_db;
+use futures::{channel::mpsc::channel, StreamExt};
+use hex;
+use libra_config::utils;
+use libra_crypto::{hash::CryptoHash, HashValue, PrivateKey};
+use libra_mempool::{
+    MempoolInspectionRequest, MempoolInspectionResponse, MempoolSize, PendingTransaction,
+    PendingTransactionStatus,
+};
+use libra_types::{
+    account_config::{association_address, lbr_type_tag},
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    on_chain_config::new_epoch_event_key,
+    test_helpers::transaction_test_helpers::{get_test_signed_transaction, get_test_signed_txn},
+    vm_error::StatusCode,
+};
+use libra_vm::LibraVM;
+use libradb::LibraDB;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+use std::{collections::HashSet, time::Duration};
+use storage_interface::{DbReader, DbReaderWriter};
+use transaction_builder::encode_transfer_script;
+
+/// Checks `value` against `schema`, for the subset of JSON Schema that `schema.rs` generates
+fn validate(
+    value: &Value,
+    schema: &Value,
+    components: &Map<String, Value>,
+    path: &str,
+) -> Result<()> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        let schema = components
+            .get(name)
+            .ok_or_else(|| format_err!("{}: unknown schema {}", path, name))?;
+        return validate(value, schema, components, path);
+    }
+    if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+        let matching = variants
+            .iter()
+            .filter(|variant| validate(value, variant, components, path).is_ok())
+            .count();
+        ensure!(
+            matching == 1,
+            "{}: {} matches {} variants",
+            path,
+            value,
+            matching
+        );
+        return Ok(());
+    }
+    if let Some(expected) = schema.get("const") {
+        ensure!(value == expected, "{}: {} isn't {}", path, value, expected);
+    }
+    if let Some(minimum) = schema.get("minimum").and_then(Value::as_u64) {
+        ensure!(
+            value.as_u64().map_or(false, |v| v >= minimum),
+            "{}: {} is less than {}",
+            path,
+            value,
+            minimum
+        );
+    }
+    let is_type = match schema.get("type").and_then(Value::as_str) {
+        None => true,
+        Some("null") => value.is_null(),
+        Some("boolean") => value.is_boolean(),
+        Some("string") => value.is_string(),
+        Some("integer") => value.is_u64() || value.is_i64(),
+        Some("array") => {
+            if let Some(items) = value.as_array() {
+                for (i, item) in items.iter().enumerate() {
+                    validate(
+                        item,
+                        &schema["items"],
+                        components,
+                        &format!("{}[{}]", path, i),
+                    )?;
+                }
+                true
+            } else {
+                false
+            }
+        }
+        Some("object") => {
+            if let Some(object) = value.as_object() {
+                validate_object(object, schema, components, path)?;
+                true
+            } else {
+                false
+            }
+        }
+        Some(other) => bail!("{}: unsupported type {}", path, other),
+    };
+    ensure!(is_type, "{}: {} doesn't match {}", path, value, schema);
+    Ok(())
+}
+
+fn validate_object(
+    object: &Map<String, Value>,
+    schema: &Value,
+    components: &Map<String, Value>,
+    path: &str,
+) -> Result<()> {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for field in required.iter().filter_map(Value::as_str) {
+            ensure!(
+                object.contains_key(field),
+                "{}: missing field {}",
+                path,
+                field
+            );
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (field, value) in object {
+        match properties.and_then(|properties| properties.get(field)) {
+            Some(field_schema) => validate(
+                value,
+                field_schema,
+                components,
+                &format!("{}.{}", path, field),
+            )?,
+            None => ensure!(
+                schema.get("additionalProperties") != Some(&Value::Bool(false)),
+                "{}: unexpected field {}",
+                path,
+                field
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Collects the targets of all `$ref`s in `value`
+fn references(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                refs.push(reference.clone());
+            }
+            object.values().for_each(|value| references(value, refs));
+        }
+        Value::Array(values) => values.iter().for_each(|value| references(value, refs)),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_openrpc_document() {
+    let document = openrpc_document();
+    let components = document["components"]["schemas"].as_object().unwrap();
+
+    // the document describes exactly the registered methods
+    let documented: HashSet<_> = document["methods"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|method| method["name"].as_str().unwrap().to_string())
+        .collect();
+    let registered: HashSet<_> = build_registry().keys().cloned().collect();
+    assert_eq!(documented, registered);
+
+    // and every reference resolves
+    let mut refs = vec![];
+    references(&document, &mut refs);
+    for reference in refs {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        assert!(components.contains_key(name), "unknown schema {}", name);
+    }
+}
+
+#[test]
+fn test_responses_conform_to_openrpc_document() {
+    // A genesis DB, so that every method has real state to return.
+    let (config, key) = config_builder::test_config();
+    let (db, db_reader_writer) =
+        DbReaderWriter::wrap(LibraDB::open(&config.storage.dir(), false).unwrap());
+    maybe_bootstrap_db::<LibraVM>(db_reader_writer, &config).unwrap();
+    let genesis_version = db.get_latest_state_root().unwrap().0;
+    let genesis_hash = db
+        .get_transactions(0, 1, genesis_version, false)
+        .unwrap()
+        .transactions[0]
+        .hash();
+
+    let (mp_sender, mut mp_events) = channel(1);
+    let (mp_inspector, mut inspection_events) = channel(1);
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(address.parse().unwrap(), db, mp_sender, mp_inspector);
+
+    let sender = association_address();
+    let program = encode_transfer_script(lbr_type_tag(), &sender, vec![], 100);
+    let txn = get_test_signed_txn(sender, 0, &key, key.public_key(), Some(program));
+    // the ledger timestamp of genesis is 0, so `submit_and_wait` sees it expired right away
+    let expired_txn = get_test_signed_transaction(
+        sender,
+        1,
+        &key,
+        key.public_key(),
+        None,
+        0,
+        0,
+        lbr_type_tag(),
+        None,
+    );
+
+    // futures that mock a mempool accepting every transaction, with one ready and one parked
+    rt.spawn(async move {
+        while let Some((_txn, cb)) = mp_events.next().await {
+            cb.send(Ok((MempoolStatus::new(MempoolStatusCode::Accepted), None)))
+                .unwrap();
+        }
+    });
+    let pending_txns = vec![
+        PendingTransaction {
+            transaction: txn.clone(),
+            status: PendingTransactionStatus::Ready,
+            system_expiration_time: Duration::from_secs(100),
+        },
+        PendingTransaction {
+            transaction: get_test_signed_txn(sender, 2, &key, key.public_key(), None),
+            status: PendingTransactionStatus::Parked {
+                missing_sequence_number: 1,
+            },
+            system_expiration_time: Duration::from_secs(100),
+        },
+    ];
+    rt.spawn(async move {
+        while let Some(request) = inspection_events.next().await {
+            let (response, cb) = match request {
+                MempoolInspectionRequest::GetAccountTransactions(_, cb) => (
+                    MempoolInspectionResponse::AccountTransactions(pending_txns.clone()),
+                    cb,
+                ),
+                MempoolInspectionRequest::GetTransaction(_, seq, cb) => (
+                    MempoolInspectionResponse::Transaction(
+                        pending_txns
+                            .iter()
+                            .find(|txn| txn.transaction.sequence_number() == seq)
+                            .cloned(),
+                    ),
+                    cb,
+                ),
+                MempoolInspectionRequest::GetSize(cb) => (
+                    MempoolInspectionResponse::Size(MempoolSize {
+                        total: 2,
+                        ready: 1,
+                        parked: 1,
+                    }),
+                    cb,
+                ),
+            };
+            cb.send(Ok(response)).unwrap();
+        }
+    });
+
+    let txn_hex = hex::encode(lcs::to_bytes(&txn).unwrap());
+    let account = sender.to_string();
+    let calls = vec![
+        ("submit", json!([txn_hex])),
+        (
+            "submit_and_wait",
+            json!([hex::encode(lcs::to_bytes(&expired_txn).unwrap())]),
+        ),
+        ("simulate", json!([txn_hex, false])),
+        ("get_metadata", json!([])),
+        ("get_account_state", json!([account])),
+        ("get_transactions", json!([0, 1, true])),
+        ("get_account_transaction", json!([account, 0, true])),
+        (
+            "get_transaction_by_hash",
+            json!([genesis_hash.to_hex(), true]),
+        ),
+        (
+            "get_events",
+            json!([hex::encode(new_epoch_event_key().as_bytes()), 0, 10]),
+        ),
+        ("get_state_proof", json!([0])),
+        ("get_account_state_with_proof", json!([account, 0, 0])),
+        (
+            "get_account_states_in_range",
+            json!([0, HashValue::zero().to_hex(), 10]),
+        ),
+        ("get_account_state_at_version", json!([account, 0])),
+        ("get_balance_history", json!([account, 0, genesis_version])),
+        ("get_validator_set", json!([])),
+        ("get_on_chain_configs", json!([])),
+        ("get_epoch_info", json!([])),
+        ("get_pending_transactions", json!([account])),
+        ("get_pending_transaction", json!([account, 2])),
+        ("get_mempool_size", json!([])),
+        ("rpc.discover", json!([])),
+    ];
+
+    let document = openrpc_document();
+    let components = document["components"]["schemas"].as_object().unwrap();
+    let specs = method_specs();
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    for (method, params) in &calls {
+        let spec = specs
+            .iter()
+            .find(|spec| spec.name == *method)
+            .unwrap_or_else(|| panic!("{} isn't documented", method));
+        assert_eq!(params.as_array().unwrap().len(), spec.params.len());
+        for ((name, schema), param) in spec.params.iter().zip(params.as_array().unwrap()) {
+            validate(param, schema, components, &format!("{}({})", method, name)).unwrap();
+        }
+
+        let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+        let response: Value = client
+            .post(&url)
+            .json(&request)
+            .send()
+            .unwrap()
+            .json()
+            .unwrap();
+        let result = response
+            .get("result")
+            .unwrap_or_else(|| panic!("{} failed: {}", method, response));
+        validate(result, &spec.result, components, method).unwrap();
+    }
+
+    // every documented method got exercised
+    let called: HashSet<_> = calls.iter().map(|(method, _)| *method).collect();
+    let documented: HashSet<_> = specs.iter().map(|spec| spec.name).collect();
+    assert_eq!(called, documented);
+}
+
+/// Names of the variants of an enum serialized with `#[serde(tag = "type")]`, as its derived
+/// `Deserialize` lists them when it meets an unknown one
+fn serde_variants<T: DeserializeOwned>() -> HashSet<String> {
+    let error = serde_json::from_value::<T>(json!({ "type": "" }))
+        .err()
+        .expect("empty tag accepted")
+        .to_string();
+    let expected = &error[error
+        .find("expected")
+        .expect("not an unknown variant error")..];
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Checks that `fixtures` cover every variant of `T` and of its schema, and conform to the schema
+fn check_variants<T: ViewSchema + Serialize + DeserializeOwned>(fixtures: Vec<T>) {
+    let document = openrpc_document();
+    let components = document["components"]["schemas"].as_object().unwrap();
+    let schema_variants: HashSet<_> = components[T::NAME]["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|variant| {
+            variant["properties"]["type"]["const"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(schema_variants, serde_variants::<T>(), "{}", T::NAME);
+
+    let mut covered = HashSet::new();
+    for fixture in fixtures {
+        let value = serde_json::to_value(&fixture).unwrap();
+        validate(&value, &reference::<T>(), components, T::NAME).unwrap();
+        covered.insert(value["type"].as_str().unwrap().to_string());
+    }
+    assert_eq!(covered, schema_variants, "{}", T::NAME);
+}
+
+#[test]
+fn test_every_variant_conforms_to_openrpc_document() {
+    let bytes = || BytesView::from(&vec![0u8, 1]);
+    let address = || association_address().to_string();
+
+    check_variants(vec![
+        EventDataView::ReceivedPayment {
+            amount: 1,
+            sender: bytes(),
+            metadata: bytes(),
+        },
+        EventDataView::SentPayment {
+            amount: 1,
+            receiver: bytes(),
+            metadata: bytes(),
+        },
+        EventDataView::NewBlock {
+            round: 1,
+            proposer: bytes(),
+            votes: vec![bytes()],
+            timestamp_usecs: 1,
+        },
+        EventDataView::NewEpoch { epoch: 1 },
+        EventDataView::DiscoverySetChange {
+            validators: vec![bytes()],
+        },
+        EventDataView::ValidatorSetChange {
+            validators: vec![ValidatorSetMemberView {
+                account_address: address(),
+                consensus_public_key: bytes(),
+                consensus_voting_power: 1,
+            }],
+        },
+        EventDataView::Upgrade {
+            writeset_payload: bytes(),
+        },
+        EventDataView::Unknown {},
+    ]);
+
+    let scripts = vec![
+        ScriptView::PeerToPeer {
+            currency: "LBR".to_string(),
+            receiver: address(),
+            auth_key_prefix: bytes(),
+            amount: 1,
+            metadata: bytes(),
+        },
+        ScriptView::Mint {
+            receiver: address(),
+            auth_key_prefix: bytes(),
+            amount: 1,
+        },
+        ScriptView::AddValidator {
+            validator: address(),
+        },
+        ScriptView::ApprovedPayment {
+            currency: "LBR".to_string(),
+            payee: address(),
+            amount: 1,
+            metadata: bytes(),
+            signature: bytes(),
+        },
+        ScriptView::Burn {
+            currency: "LBR".to_string(),
+            preburn_address: address(),
+        },
+        ScriptView::CancelBurn {
+            currency: "LBR".to_string(),
+            preburn_address: address(),
+        },
+        ScriptView::CreateAccount {
+            address: address(),
+            auth_key_prefix: bytes(),
+            initial_amount: 1,
+        },
+        ScriptView::EmptyScript {},
+        ScriptView::ModifyPublishingOption {
+            publishing_option: bytes(),
+        },
+        ScriptView::Preburn {
+            currency: "LBR".to_string(),
+            amount: 1,
+        },
+        ScriptView::RegisterApprovedPayment {
+            public_key: bytes(),
+        },
+        ScriptView::RegisterPreburner {
+            currency: "LBR".to_string(),
+        },
+        ScriptView::RegisterValidator {
+            consensus_pubkey: bytes(),
+            validator_network_signing_pubkey: bytes(),
+            validator_network_identity_pubkey: bytes(),
+            validator_network_address: bytes(),
+            fullnodes_network_identity_pubkey: bytes(),
+            fullnodes_network_address: bytes(),
+        },
+        ScriptView::RemoveValidator {
+            validator: address(),
+        },
+        ScriptView::RotateAuthenticationKey { new_key: bytes() },
+        ScriptView::RotateConsensusPubkey { new_key: bytes() },
+        ScriptView::UpdateLibraVersion { major: 1 },
+        ScriptView::Unknown {},
+    ];
+    check_variants(scripts);
+
+    let user_transaction = TransactionDataView::UserTransaction {
+        sender: address(),
+        signature_scheme: "Scheme::Ed25519".to_string(),
+        signature: "00".to_string(),
+        public_key: "00".to_string(),
+        sequence_number: 1,
+        max_gas_amount: 1,
+        gas_unit_price: 1,
+        expiration_time: 1,
+        script_hash: "00".to_string(),
+        script: ScriptView::EmptyScript {},
+    };
+
+    check_variants(vec![
+        TransactionDataView::BlockMetadata { timestamp_usecs: 1 },
+        TransactionDataView::WriteSet {},
+        user_transaction.clone(),
+        TransactionDataView::UnknownTransaction {},
+    ]);
+
+    check_variants(vec![
+        TransactionStatusDataView::Committed {
+            transaction: TransactionView {
+                version: 1,
+                transaction: user_transaction,
+                events: vec![],
+                vm_status: StatusCode::EXECUTED,
+                gas_used: 1,
+            },
+        },
+        TransactionStatusDataView::Expired {},
+        TransactionStatusDataView::Dropped {},
+        TransactionStatusDataView::Pending {},
+    ]);
+
+    check_variants(vec![
+        PublishingOptionView::Locked {
+            whitelist: vec![bytes()],
+        },
+        PublishingOptionView::CustomScripts {},
+        PublishingOptionView::Open {},
+    ]);
+
+    check_variants(vec![
+        PendingTransactionStatusView::Ready {},
+        PendingTransactionStatusView::Parked {
+            missing_sequence_number: 1,
+        },
+    ]);
+}
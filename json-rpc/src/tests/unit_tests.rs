@@ -4,34 +4,64 @@
 use crate::{
     client::{JsonRpcAsyncClient, JsonRpcBatch, JsonRpcResponse},
     errors::{JsonRpcError, ServerCode},
-    runtime::bootstrap,
+    rate_limiter::{RateLimiter, MAX_TRACKED_IPS},
+    runtime::{bootstrap, bootstrap_with_config},
+    stream::MAX_SUBSCRIPTIONS_PER_CONNECTION,
     tests::mock_db::MockLibraDB,
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, BytesView, EventView,
-        ResponseAsView, StateProofView, TransactionDataView, TransactionView,
+        AccountAtVersionView, AccountStateChunkView, AccountStateWithProofView, AccountView,
+        BalanceHistoryView, BlockMetadata, BytesView, EpochInfoView, EventDataView, EventView,
+        GasCostView, MempoolSizeView, OnChainConfigsView, PendingTransactionStatusView,
+        PendingTransactionView, PendingTransactionsView, PublishingOptionView, ResponseAsView,
+        ScriptView, SimulationView, StateProofView, TransactionDataView, TransactionStatusDataView,
+        TransactionStatusView, TransactionView, ValidatorSetMemberView, ValidatorSetView,
     },
 };
-use futures::{channel::mpsc::channel, StreamExt};
+use executor::db_bootstrapper::maybe_bootstrap_db;
+use futures::{channel::mpsc::channel, SinkExt, Stream, StreamExt};
 use hex;
-use libra_config::utils;
-use libra_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+use libra_config::{
+    config::{RateLimitConfig, RpcConfig},
+    utils,
+};
+use libra_crypto::{
+    ed25519::Ed25519PrivateKey, hash::CryptoHash, x25519::X25519StaticPrivateKey, HashValue,
+    PrivateKey, SigningKey, Uniform, ValidKey,
+};
+use libra_mempool::{
+    MempoolInspectionRequest, MempoolInspectionResponse, MempoolSize, PendingTransaction,
+    PendingTransactionStatus,
+};
 use libra_proptest_helpers::ValueGenerator;
 use libra_types::{
     account_address::AccountAddress,
-    account_config::AccountResource,
+    account_config::{
+        association_address, lbr_type_tag, received_payment_tag, sent_payment_tag, AccountResource,
+        BalanceResource, ReceivedPaymentEvent, SentPaymentEvent, ACCOUNT_RESOURCE_PATH,
+        BALANCE_RESOURCE_PATH, CORE_CODE_ADDRESS,
+    },
     account_state::AccountState,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     contract_event::ContractEvent,
-    event::EventKey,
-    language_storage::TypeTag,
+    event::{EventHandle, EventKey},
+    language_storage::{StructTag, TypeTag},
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::{MempoolStatus, MempoolStatusCode},
+    on_chain_config::{
+        new_epoch_event_key, ConfigurationResource, GasScheduleCost, GasScheduleResource,
+        LibraVersion, OnChainConfig, VMPublishingOption, CONFIGURATION_RESOURCE_PATH,
+        GAS_SCHEDULE_RESOURCE_PATH,
+    },
     proof::{SparseMerkleProof, TransactionAccumulatorProof},
-    test_helpers::transaction_test_helpers::get_test_signed_txn,
-    transaction::{Transaction, TransactionInfo, TransactionPayload},
+    test_helpers::transaction_test_helpers::{get_test_signed_transaction, get_test_signed_txn},
+    transaction::{SignedTransaction, Transaction, TransactionInfo, TransactionPayload},
+    validator_info::ValidatorInfo,
+    validator_set::ValidatorSet,
     vm_error::{StatusCode, VMStatus},
 };
-use libradb::test_helper::arb_blocks_to_commit;
+use libra_vm::LibraVM;
+use libradb::{test_helper::arb_blocks_to_commit, LibraDB};
+use move_core_types::identifier::Identifier;
 use proptest::prelude::*;
 use reqwest;
 use serde_json;
@@ -39,9 +69,14 @@ use std::{
     collections::{BTreeMap, HashMap},
     convert::TryFrom,
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use storage_interface::{DbReader, DbReaderWriter};
+use tokio_tungstenite::{connect_async, tungstenite};
+use transaction_builder::{
+    encode_burn_script, encode_create_account_script, encode_register_validator_script,
+    encode_remove_validator_script, encode_transfer_script,
 };
-use storage_interface::DbReader;
 use vm_validator::{
     mocks::mock_vm_validator::MockVMValidator, vm_validator::TransactionValidation,
 };
@@ -53,7 +88,12 @@ fn test_json_rpc_protocol() {
     let address = format!("0.0.0.0:{}", utils::get_available_port());
     let mock_db = mock_db();
     let mp_sender = channel(1024).0;
-    let _runtime = bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let _runtime = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db),
+        mp_sender,
+        channel(1).0,
+    );
     let client = reqwest::blocking::Client::new();
 
     // check that only root path is accessible
@@ -100,6 +140,167 @@ fn test_json_rpc_protocol() {
     assert_eq!(fetch_error(resp), -32000);
 }
 
+#[test]
+fn test_request_limits() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        batch_size_limit: 2,
+        page_size_limit: 10,
+        content_length_limit: 1024,
+        ip_rate_limit: None,
+    };
+    let _runtime = bootstrap_with_config(
+        &config,
+        Arc::new(mock_db()),
+        channel(1024).0,
+        channel(1024).0,
+    );
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    let metadata_request =
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1});
+
+    // batch within the limit
+    let request = serde_json::json!([metadata_request, metadata_request]);
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(resp.status(), 200);
+    let responses: Vec<JsonMap> = resp.json().unwrap();
+    assert_eq!(responses.len(), 2);
+
+    // batch too large
+    let request = serde_json::json!([metadata_request, metadata_request, metadata_request]);
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(fetch_error(resp), ServerCode::BatchSizeTooLarge as i16);
+
+    // page too large
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, 11, false], "id": 1});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(fetch_error(resp), ServerCode::PageSizeTooLarge as i16);
+
+    // body too large
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": ["0".repeat(1024)], "id": 1});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(fetch_error(resp), ServerCode::ContentTooLarge as i16);
+
+    // rate limited once the bucket is empty
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        ip_rate_limit: Some(RateLimitConfig {
+            requests_per_sec: 1,
+            burst_size: 2,
+        }),
+        ..RpcConfig::default()
+    };
+    let _runtime = bootstrap_with_config(
+        &config,
+        Arc::new(mock_db()),
+        channel(1024).0,
+        channel(1024).0,
+    );
+    let url = format!("http://{}", address);
+    let request = serde_json::json!([metadata_request, metadata_request]);
+    let resp = client.post(&url).json(&request).send().unwrap();
+    let responses: Vec<JsonMap> = resp.json().unwrap();
+    assert_eq!(responses.len(), 2);
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(fetch_error(resp), ServerCode::RateLimited as i16);
+}
+
+#[test]
+fn test_stream_request_limits() {
+    let mock_db = mock_db();
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        content_length_limit: 1024,
+        ip_rate_limit: Some(RateLimitConfig {
+            requests_per_sec: 1,
+            burst_size: 3,
+        }),
+        ..RpcConfig::default()
+    };
+    let mut rt = bootstrap_with_config(
+        &config,
+        Arc::new(mock_db.clone()),
+        channel(1024).0,
+        channel(1024).0,
+    );
+
+    rt.block_on(async {
+        let url = format!("ws://{}/stream", address);
+        // The upgrade takes the first request from the bucket.
+        let (mut socket, _) = connect_async(url.as_str()).await.unwrap();
+        let from_version = mock_db.all_txns.len() as u64 + 100;
+        let subscribe = |params: serde_json::Value| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "subscribe_to_transactions", "params": params,
+            });
+            tungstenite::Message::text(request.to_string())
+        };
+
+        // message too large
+        socket
+            .send(subscribe(serde_json::json!([
+                from_version,
+                "0".repeat(1024)
+            ])))
+            .await
+            .unwrap();
+        let response = next_ws_response(&mut socket).await;
+        assert_eq!(
+            response["error"]["code"],
+            serde_json::json!(ServerCode::ContentTooLarge as i16)
+        );
+
+        // every message is a request counted against the rate limit
+        for _ in 0..2 {
+            socket
+                .send(subscribe(serde_json::json!([from_version])))
+                .await
+                .unwrap();
+            let response = next_ws_response(&mut socket).await;
+            assert_eq!(response["result"], serde_json::Value::Null);
+        }
+        socket
+            .send(subscribe(serde_json::json!([from_version])))
+            .await
+            .unwrap();
+        let response = next_ws_response(&mut socket).await;
+        assert_eq!(
+            response["error"]["code"],
+            serde_json::json!(ServerCode::RateLimited as i16)
+        );
+
+        // and so is every upgrade
+        assert!(connect_async(url.as_str()).await.is_err());
+    });
+}
+
+#[test]
+fn test_rate_limiter_tracks_bounded_ips() {
+    let rate_limiter = RateLimiter::new(RateLimitConfig {
+        requests_per_sec: 1,
+        burst_size: 1,
+    });
+    let ip =
+        |index: usize| -> std::net::IpAddr { std::net::Ipv4Addr::from(index as u32 + 1).into() };
+    assert!(rate_limiter.try_acquire(ip(0), 1));
+    assert!(!rate_limiter.try_acquire(ip(0), 1));
+
+    for index in 1..=MAX_TRACKED_IPS {
+        assert!(rate_limiter.try_acquire(ip(index), 1));
+        assert!(rate_limiter.num_tracked_ips() <= MAX_TRACKED_IPS);
+    }
+    // the least recently seen IP was dropped and starts over with a full bucket
+    assert!(rate_limiter.try_acquire(ip(0), 1));
+    assert!(!rate_limiter.try_acquire(ip(MAX_TRACKED_IPS), 1));
+}
+
 // returns MockLibraDB for unit-testing
 fn mock_db() -> MockLibraDB {
     let mut gen = ValueGenerator::new();
@@ -182,7 +383,12 @@ fn test_transaction_submission() {
     let mock_db = mock_db();
     let port = utils::get_available_port();
     let address = format!("0.0.0.0:{}", port);
-    let mut runtime = bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let mut runtime = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db),
+        mp_sender,
+        channel(1).0,
+    );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
     // future that mocks shared mempool execution
@@ -246,6 +452,7 @@ fn test_get_account_state() {
         address.parse().unwrap(),
         Arc::new(mock_db.clone()),
         mp_sender,
+        channel(1).0,
     );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
@@ -326,6 +533,7 @@ fn test_get_metadata() {
         address.parse().unwrap(),
         Arc::new(mock_db.clone()),
         channel(1).0,
+        channel(1).0,
     );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
@@ -356,6 +564,7 @@ fn test_get_events() {
         address.parse().unwrap(),
         Arc::new(mock_db.clone()),
         channel(1).0,
+        channel(1).0,
     );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
@@ -401,6 +610,7 @@ fn test_get_transactions() {
         address.parse().unwrap(),
         Arc::new(mock_db.clone()),
         channel(1).0,
+        channel(1).0,
     );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
@@ -491,6 +701,7 @@ fn test_get_account_transaction() {
         address.parse().unwrap(),
         Arc::new(mock_db.clone()),
         channel(1).0,
+        channel(1).0,
     );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
@@ -569,6 +780,287 @@ fn test_get_account_transaction() {
     }
 }
 
+#[test]
+fn test_get_transaction_by_hash() {
+    let mock_db = mock_db();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        channel(1).0,
+    );
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    for (version, (txn, status)) in mock_db.all_txns.iter().enumerate() {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_transaction_by_hash_request(txn.hash(), true);
+        let result = rt
+            .block_on(client.execute(batch))
+            .unwrap()
+            .remove(0)
+            .unwrap();
+        let tx_view = TransactionView::optional_from_response(result)
+            .unwrap()
+            .expect("Transaction didn't exist!");
+        assert_eq!(tx_view.version, version as u64);
+        assert_eq!(&tx_view.vm_status, status);
+        assert_eq!(tx_view.transaction, TransactionDataView::from(txn.clone()));
+        let expected_num_events = mock_db
+            .events
+            .iter()
+            .filter(|(ev, _)| *ev == version as u64)
+            .count();
+        assert_eq!(tx_view.events.len(), expected_num_events);
+    }
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_transaction_by_hash_request(HashValue::random(), false);
+    let result = rt
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap();
+    assert!(TransactionView::optional_from_response(result)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_submit_and_wait() {
+    let (mp_sender, mut mp_events) = channel(1);
+    let (mp_inspector, mut inspection_events) = channel(1);
+    let mock_db = mock_db();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        mp_sender,
+        mp_inspector,
+    );
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    // future that mocks a mempool accepting every transaction
+    rt.spawn(async move {
+        while let Some((_txn, cb)) = mp_events.next().await {
+            cb.send(Ok((MempoolStatus::new(MempoolStatusCode::Accepted), None)))
+                .unwrap();
+        }
+    });
+
+    // future that mocks a mempool which has already dropped every transaction
+    rt.spawn(async move {
+        while let Some(request) = inspection_events.next().await {
+            match request {
+                MempoolInspectionRequest::GetTransaction(_, _, cb) => cb
+                    .send(Ok(MempoolInspectionResponse::Transaction(None)))
+                    .unwrap(),
+                _ => panic!("unexpected mempool inspection request"),
+            }
+        }
+    });
+
+    // A transaction that's already committed is returned right away.
+    let (version, txn) = mock_db
+        .all_txns
+        .iter()
+        .enumerate()
+        .find_map(|(version, (txn, _))| {
+            txn.as_signed_user_txn()
+                .ok()
+                .map(|txn| (version as u64, txn.clone()))
+        })
+        .expect("mock DB missing user transaction");
+    let mut batch = JsonRpcBatch::default();
+    batch.add_submit_and_wait_request(txn.clone()).unwrap();
+    let result = rt
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap();
+    let status_view = TransactionStatusView::from_response(result).unwrap();
+    assert_eq!(
+        status_view.hash,
+        Transaction::UserTransaction(txn).hash().to_hex()
+    );
+    match status_view.status {
+        TransactionStatusDataView::Committed { transaction } => {
+            assert_eq!(transaction.version, version)
+        }
+        status => panic!("unexpected status {:?}", status),
+    }
+
+    // A transaction that expired before the latest block never commits.
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let expired_txn = get_test_signed_transaction(
+        AccountAddress::random(),
+        0,
+        &privkey,
+        privkey.public_key(),
+        None,
+        mock_db.timestamp / 1_000_000 - 1,
+        0,
+        lbr_type_tag(),
+        None,
+    );
+    let mut batch = JsonRpcBatch::default();
+    batch.add_submit_and_wait_request(expired_txn).unwrap();
+    let result = rt
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap();
+    assert_eq!(
+        TransactionStatusView::from_response(result).unwrap().status,
+        TransactionStatusDataView::Expired {}
+    );
+
+    // A transaction that left mempool without being committed is reported as dropped.
+    let dropped_txn = get_test_signed_transaction(
+        AccountAddress::random(),
+        0,
+        &privkey,
+        privkey.public_key(),
+        None,
+        mock_db.timestamp / 1_000_000 + 100,
+        0,
+        lbr_type_tag(),
+        None,
+    );
+    let mut batch = JsonRpcBatch::default();
+    batch.add_submit_and_wait_request(dropped_txn).unwrap();
+    let result = rt
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap();
+    assert_eq!(
+        TransactionStatusView::from_response(result).unwrap().status,
+        TransactionStatusDataView::Dropped {}
+    );
+}
+
+#[test]
+fn test_mempool_inspection() {
+    let (mp_inspector, mut inspection_events) = channel(1);
+    let mock_db = mock_db();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        mp_inspector,
+    );
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    // an account with transaction 1 ready and transaction 3 waiting for 2
+    let (account, blob) = mock_db.all_accounts.iter().next().unwrap();
+    let account = *account;
+    let sequence_number = AccountState::try_from(blob)
+        .unwrap()
+        .get_account_resource()
+        .unwrap()
+        .unwrap()
+        .sequence_number();
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let pending = |seq, status| PendingTransaction {
+        transaction: get_test_signed_txn(account, seq, &privkey, privkey.public_key(), None),
+        status,
+        system_expiration_time: Duration::from_secs(100),
+    };
+    let parked = PendingTransactionStatus::Parked {
+        missing_sequence_number: sequence_number + 2,
+    };
+    let pending_txns = vec![
+        pending(sequence_number + 1, PendingTransactionStatus::Ready),
+        pending(sequence_number + 3, parked),
+    ];
+
+    // future that mocks mempool inspection
+    let txns = pending_txns.clone();
+    rt.spawn(async move {
+        while let Some(request) = inspection_events.next().await {
+            let (response, cb) = match request {
+                MempoolInspectionRequest::GetAccountTransactions(address, cb) => {
+                    assert_eq!(address, account);
+                    (
+                        MempoolInspectionResponse::AccountTransactions(txns.clone()),
+                        cb,
+                    )
+                }
+                MempoolInspectionRequest::GetTransaction(address, seq, cb) => (
+                    MempoolInspectionResponse::Transaction(
+                        txns.iter()
+                            .find(|txn| {
+                                txn.transaction.sender() == address
+                                    && txn.transaction.sequence_number() == seq
+                            })
+                            .cloned(),
+                    ),
+                    cb,
+                ),
+                MempoolInspectionRequest::GetSize(cb) => (
+                    MempoolInspectionResponse::Size(MempoolSize {
+                        total: 2,
+                        ready: 1,
+                        parked: 1,
+                    }),
+                    cb,
+                ),
+            };
+            cb.send(Ok(response)).unwrap();
+        }
+    });
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_pending_transactions_request(account);
+    batch.add_get_pending_transaction_request(account, sequence_number + 3);
+    batch.add_get_pending_transaction_request(account, sequence_number + 2);
+    batch.add_get_mempool_size_request();
+    let mut result = rt.block_on(client.execute(batch)).unwrap();
+
+    let view = PendingTransactionsView::from_response(result.remove(0).unwrap()).unwrap();
+    assert_eq!(view.account_sequence_number, Some(sequence_number));
+    let parked_txn = pending_txns[1].clone();
+    let expected: Vec<PendingTransactionView> = pending_txns.into_iter().map(Into::into).collect();
+    assert_eq!(view.transactions, expected);
+    assert_eq!(
+        view.transactions[1].hash,
+        Transaction::UserTransaction(parked_txn.transaction)
+            .hash()
+            .to_hex()
+    );
+
+    let parked_txn = PendingTransactionView::optional_from_response(result.remove(0).unwrap())
+        .unwrap()
+        .expect("parked transaction is missing");
+    assert_eq!(parked_txn.sequence_number, sequence_number + 3);
+    assert_eq!(
+        parked_txn.status,
+        PendingTransactionStatusView::Parked {
+            missing_sequence_number: sequence_number + 2,
+        }
+    );
+    assert!(
+        PendingTransactionView::optional_from_response(result.remove(0).unwrap())
+            .unwrap()
+            .is_none()
+    );
+
+    let size = MempoolSizeView::from_response(result.remove(0).unwrap()).unwrap();
+    assert_eq!(
+        size,
+        MempoolSizeView {
+            total: 2,
+            ready: 1,
+            parked: 1,
+        }
+    );
+}
+
 #[test]
 fn test_get_account_state_with_proof() {
     // set up MockLibraDB
@@ -581,6 +1073,7 @@ fn test_get_account_state_with_proof() {
         address.parse().unwrap(),
         Arc::new(mock_db.clone()),
         channel(1).0,
+        channel(1).0,
     );
     let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
 
@@ -640,6 +1133,61 @@ fn test_get_account_state_with_proof() {
     assert_eq!(li_proof, *expected_li_proof);
 }
 
+#[test]
+fn test_get_account_states_in_range() {
+    let mock_db = mock_db();
+
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        channel(1).0,
+    );
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    let mut expected: Vec<_> = mock_db
+        .all_accounts
+        .iter()
+        .map(|(address, blob)| (address.hash(), blob.clone()))
+        .collect();
+    expected.sort_by_key(|(key, _)| *key);
+
+    // Page through all accounts two at a time.
+    let mut received = vec![];
+    let mut start_key = HashValue::zero();
+    loop {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_account_states_in_range_request(mock_db.version, start_key, 2);
+        let result = rt
+            .block_on(client.execute(batch))
+            .unwrap()
+            .remove(0)
+            .unwrap();
+        let chunk = AccountStateChunkView::from_response(result).unwrap();
+        assert_eq!(chunk.version, mock_db.version);
+        assert!(chunk.accounts.len() <= 2);
+
+        let num_accounts = chunk.accounts.len();
+        let next_key = chunk.next_key;
+        for account in chunk.accounts {
+            let key = HashValue::from_slice(&account.key.into_bytes().unwrap()).unwrap();
+            let blob: AccountStateBlob =
+                lcs::from_bytes(&account.blob.into_bytes().unwrap()).unwrap();
+            received.push((key, blob));
+        }
+        match next_key {
+            Some(next_key) => {
+                assert_eq!(num_accounts, 2);
+                start_key = HashValue::from_slice(&next_key.into_bytes().unwrap()).unwrap();
+            }
+            None => break,
+        }
+    }
+    assert_eq!(received, expected);
+}
+
 #[test]
 fn test_get_state_proof() {
     let port = utils::get_available_port();
@@ -666,6 +1214,418 @@ fn test_get_state_proof() {
     assert_eq!(li.ledger_info().version(), version);
 }
 
+#[test]
+fn test_get_balance_history() {
+    let account = AccountAddress::random();
+    let sent_events = EventKey::new_from_address(&account, 0);
+    let received_events = EventKey::new_from_address(&account, 1);
+    let account_state = |sent: u64, received: u64, balance: u64| {
+        let account_resource = AccountResource::new(
+            sent,
+            vec![],
+            false,
+            false,
+            EventHandle::new(sent_events, sent),
+            EventHandle::new(received_events, received),
+            2,
+        );
+        let mut account_state = AccountState::default();
+        account_state.insert(
+            ACCOUNT_RESOURCE_PATH.clone(),
+            lcs::to_bytes(&account_resource).unwrap(),
+        );
+        account_state.insert(
+            BALANCE_RESOURCE_PATH.clone(),
+            lcs::to_bytes(&BalanceResource::new(balance)).unwrap(),
+        );
+        AccountStateBlob::try_from(&account_state).unwrap()
+    };
+    let sent_payment = |seq: u64, amount: u64| {
+        ContractEvent::new(
+            sent_events,
+            seq,
+            TypeTag::Struct(sent_payment_tag()),
+            lcs::to_bytes(&SentPaymentEvent::new(
+                amount,
+                AccountAddress::random(),
+                vec![],
+            ))
+            .unwrap(),
+        )
+    };
+    let received_payment = |seq: u64, amount: u64| {
+        ContractEvent::new(
+            received_events,
+            seq,
+            TypeTag::Struct(received_payment_tag()),
+            lcs::to_bytes(&ReceivedPaymentEvent::new(
+                amount,
+                AccountAddress::random(),
+                vec![],
+            ))
+            .unwrap(),
+        )
+    };
+
+    // One payment is sent before version 10, then two are sent and one received until version 20.
+    let mut mock_db = mock_db();
+    let mut start = mock_db.account_state_with_proof[0].clone();
+    start.version = 10;
+    start.blob = Some(account_state(1, 0, 90));
+    let mut middle = start.clone();
+    middle.version = 15;
+    middle.blob = Some(account_state(2, 1, 100));
+    let mut end = start.clone();
+    end.version = 20;
+    end.blob = Some(account_state(3, 1, 75));
+    mock_db.account_state_with_proof = vec![start.clone(), middle, end.clone()];
+    mock_db.version = 30;
+    mock_db.events = vec![
+        (5, sent_payment(0, 10)),
+        (12, sent_payment(1, 20)),
+        (15, received_payment(0, 30)),
+        (18, sent_payment(2, 25)),
+        (25, sent_payment(3, 5)),
+    ];
+
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        channel(1).0,
+    );
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_state_at_version_request(account, 10);
+    batch.add_get_balance_history_request(account, 10, 20);
+    batch.add_get_balance_history_request(account, 20, 10);
+    batch.add_get_account_state_at_version_request(account, 31);
+    let mut responses = rt.block_on(client.execute(batch)).unwrap();
+    assert_eq!(responses.len(), 4);
+
+    let account_view = AccountAtVersionView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(account_view.version, 10);
+    assert_eq!(account_view.account.unwrap().balance, 90);
+    assert_eq!(
+        account_view.account_state_with_proof,
+        AccountStateWithProofView::try_from(start).unwrap()
+    );
+
+    let history = BalanceHistoryView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(history.start.account.unwrap().balance, 90);
+    assert_eq!(history.end.account.unwrap().balance, 75);
+    assert_eq!(
+        history.end.account_state_with_proof,
+        AccountStateWithProofView::try_from(end).unwrap()
+    );
+    let payments: Vec<_> = history
+        .payments
+        .iter()
+        .map(|event| match event.data {
+            EventDataView::SentPayment { amount, .. } => (event.transaction_version, amount, true),
+            EventDataView::ReceivedPayment { amount, .. } => {
+                (event.transaction_version, amount, false)
+            }
+            _ => panic!("unexpected event {:?}", event),
+        })
+        .collect();
+    assert_eq!(
+        payments,
+        vec![(12, 20, true), (15, 30, false), (18, 25, true)]
+    );
+    assert_eq!(history.next_from_version, None);
+
+    // the range is reversed, and the version is past the ledger version
+    assert!(responses.remove(0).is_err());
+    assert!(responses.remove(0).is_err());
+
+    // with a smaller page size limit, the payments come in two pages
+    let port = utils::get_available_port();
+    let config = RpcConfig {
+        address: format!("0.0.0.0:{}", port).parse().unwrap(),
+        page_size_limit: 2,
+        ..RpcConfig::default()
+    };
+    let mut rt = bootstrap_with_config(&config, Arc::new(mock_db), channel(1).0, channel(1).0);
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_balance_history_request(account, 10, 20);
+    batch.add_get_balance_history_request(account, 17, 20);
+    let mut responses = rt.block_on(client.execute(batch)).unwrap();
+
+    let history = BalanceHistoryView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(history.start.account.unwrap().balance, 90);
+    let versions: Vec<_> = history
+        .payments
+        .iter()
+        .map(|event| event.transaction_version)
+        .collect();
+    assert_eq!(versions, vec![12, 15]);
+    assert_eq!(history.next_from_version, Some(17));
+
+    let history = BalanceHistoryView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(history.start.account.unwrap().balance, 100);
+    assert_eq!(history.end.account.unwrap().balance, 75);
+    let versions: Vec<_> = history
+        .payments
+        .iter()
+        .map(|event| event.transaction_version)
+        .collect();
+    assert_eq!(versions, vec![18]);
+    assert_eq!(history.next_from_version, None);
+}
+
+#[test]
+fn test_get_validator_set_configs_and_epoch_info() {
+    let validator_info = ValidatorInfo::new(
+        AccountAddress::random(),
+        Ed25519PrivateKey::generate_for_testing().public_key(),
+        7,
+        Ed25519PrivateKey::generate_for_testing().public_key(),
+        X25519StaticPrivateKey::generate_for_testing().public_key(),
+    );
+    let validator_set = ValidatorSet::new(vec![validator_info.clone()]);
+    let gas_schedule = GasScheduleResource {
+        instruction_schedule: vec![GasScheduleCost { cpu: 1, storage: 2 }],
+        native_schedule: vec![],
+    };
+    let configuration =
+        ConfigurationResource::new(3, 1_000_000, EventHandle::new(new_epoch_event_key(), 3));
+
+    // The mock DB returns the same account for every address, so one account holds all configs.
+    let mut account_state = AccountState::default();
+    account_state.insert(
+        ValidatorSet::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&validator_set).unwrap(),
+    );
+    account_state.insert(
+        LibraVersion::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&LibraVersion { major: 2 }).unwrap(),
+    );
+    // The publishing option is stored as LCS bytes of its LCS encoding.
+    account_state.insert(
+        VMPublishingOption::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&lcs::to_bytes(&VMPublishingOption::Locked(vec![[4; 32]])).unwrap()).unwrap(),
+    );
+    account_state.insert(
+        GAS_SCHEDULE_RESOURCE_PATH.clone(),
+        lcs::to_bytes(&gas_schedule).unwrap(),
+    );
+    account_state.insert(
+        CONFIGURATION_RESOURCE_PATH.clone(),
+        lcs::to_bytes(&configuration).unwrap(),
+    );
+
+    let mut mock_db = mock_db();
+    let blob = AccountStateBlob::try_from(&account_state).unwrap();
+    mock_db.account_state_with_proof[0].blob = Some(blob);
+    let expected_proof =
+        AccountStateWithProofView::try_from(mock_db.account_state_with_proof[0].clone()).unwrap();
+
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        channel(1).0,
+    );
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_validator_set_request();
+    batch.add_get_on_chain_configs_request();
+    batch.add_get_epoch_info_request();
+    let mut responses = rt.block_on(client.execute(batch)).unwrap();
+    assert_eq!(responses.len(), 3);
+
+    let validator_set_view = ValidatorSetView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(validator_set_view.version, mock_db.version);
+    assert_eq!(validator_set_view.validators.len(), 1);
+    let validator_view = &validator_set_view.validators[0];
+    assert_eq!(
+        validator_view.account_address,
+        validator_info.account_address().to_string()
+    );
+    assert_eq!(validator_view.consensus_voting_power, 7);
+    assert_eq!(
+        validator_view.network_identity_public_key,
+        BytesView::from(&validator_info.network_identity_public_key().to_bytes())
+    );
+    assert_eq!(validator_set_view.account_state_with_proof, expected_proof);
+
+    let configs_view = OnChainConfigsView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(configs_view.libra_version, Some(2));
+    assert_eq!(
+        configs_view.publishing_option,
+        Some(PublishingOptionView::Locked {
+            whitelist: vec![BytesView::from(&[4; 32][..])],
+        })
+    );
+    let gas_schedule_view = configs_view.gas_schedule.unwrap();
+    assert_eq!(
+        gas_schedule_view.instruction_schedule,
+        vec![GasCostView { cpu: 1, storage: 2 }]
+    );
+    assert!(gas_schedule_view.native_schedule.is_empty());
+    assert_eq!(configs_view.account_state_with_proof, expected_proof);
+
+    let epoch_info_view = EpochInfoView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(epoch_info_view.epoch, 3);
+    assert_eq!(epoch_info_view.last_reconfiguration_time, 1_000_000);
+    assert_eq!(epoch_info_view.ledger_version, mock_db.version);
+    assert_eq!(epoch_info_view.account_state_with_proof, expected_proof);
+}
+
+#[test]
+fn test_simulate() {
+    // Simulation runs the VM, so it needs a DB with a genesis state rather than the mock DB.
+    let (config, key) = config_builder::test_config();
+    let (db, db_reader_writer) =
+        DbReaderWriter::wrap(LibraDB::open(&config.storage.dir(), false).unwrap());
+    maybe_bootstrap_db::<LibraVM>(db_reader_writer, &config).unwrap();
+    let (version, _) = db.get_latest_state_root().unwrap();
+
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(address.parse().unwrap(), db, channel(1).0, channel(1).0);
+    let client = JsonRpcAsyncClient::new(reqwest::Client::new(), "0.0.0.0", port);
+
+    let sender = association_address();
+    let program = encode_transfer_script(lbr_type_tag(), &sender, vec![], 100);
+    // Sign something other than the transaction, so that only its signature is invalid.
+    let raw_txn = get_test_signed_txn(sender, 0, &key, key.public_key(), Some(program))
+        .into_raw_transaction();
+    let unsigned_txn = SignedTransaction::new(
+        raw_txn,
+        key.public_key(),
+        key.sign_message(&HashValue::zero()),
+    );
+
+    let mut batch = JsonRpcBatch::default();
+    batch
+        .add_simulate_request(unsigned_txn.clone(), false)
+        .unwrap();
+    batch.add_simulate_request(unsigned_txn, true).unwrap();
+    let mut responses = rt.block_on(client.execute(batch)).unwrap();
+    assert_eq!(responses.len(), 2);
+
+    let rejected = SimulationView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert!(rejected.discarded);
+    assert_eq!(
+        rejected.vm_status.major_status,
+        StatusCode::INVALID_SIGNATURE
+    );
+    assert_eq!(rejected.gas_used, 0);
+    assert!(rejected.write_set.is_empty());
+
+    let simulation = SimulationView::from_response(responses.remove(0).unwrap()).unwrap();
+    assert_eq!(simulation.version, version);
+    assert!(!simulation.discarded);
+    assert_eq!(simulation.vm_status.major_status, StatusCode::EXECUTED);
+    assert!(simulation.gas_used > 0);
+    assert!(!simulation.write_set.is_empty());
+    assert!(simulation
+        .events
+        .iter()
+        .all(|event| event.transaction_version == version + 1));
+    assert!(simulation.events.iter().any(|event| match event.data {
+        EventDataView::SentPayment { .. } => true,
+        _ => false,
+    }));
+    assert!(simulation.events.iter().any(|event| match event.data {
+        EventDataView::ReceivedPayment { .. } => true,
+        _ => false,
+    }));
+}
+
+#[test]
+fn test_script_and_event_views() {
+    let address = AccountAddress::new([1; AccountAddress::LENGTH]);
+
+    let script = encode_create_account_script(&address, vec![2; 16], 100);
+    assert_eq!(
+        ScriptView::from(TransactionPayload::Script(script)),
+        ScriptView::CreateAccount {
+            address: address.to_string(),
+            auth_key_prefix: BytesView::from(&vec![2; 16]),
+            initial_amount: 100,
+        }
+    );
+
+    let script = encode_burn_script(lbr_type_tag(), address);
+    assert_eq!(
+        ScriptView::from(TransactionPayload::Script(script)),
+        ScriptView::Burn {
+            currency: "LBR".to_string(),
+            preburn_address: address.to_string(),
+        }
+    );
+
+    let script = encode_remove_validator_script(&address);
+    let view = ScriptView::from(TransactionPayload::Script(script));
+    assert_eq!(view.get_name(), "remove validator transaction");
+
+    let script =
+        encode_register_validator_script(vec![1], vec![2], vec![3], vec![4], vec![5], vec![6]);
+    match ScriptView::from(TransactionPayload::Script(script)) {
+        ScriptView::RegisterValidator {
+            consensus_pubkey,
+            fullnodes_network_address,
+            ..
+        } => {
+            assert_eq!(consensus_pubkey, BytesView::from(&vec![1]));
+            assert_eq!(fullnodes_network_address, BytesView::from(&vec![6]));
+        }
+        view => panic!("unexpected script view {:?}", view),
+    }
+
+    let new_epoch_tag = TypeTag::Struct(StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("LibraConfig").unwrap(),
+        name: Identifier::new("NewEpochEvent").unwrap(),
+        type_params: vec![],
+    });
+    let event = ContractEvent::new(
+        new_epoch_event_key(),
+        0,
+        new_epoch_tag,
+        lcs::to_bytes(&7u64).unwrap(),
+    );
+    assert_eq!(
+        EventView::from((0, event)).data,
+        EventDataView::NewEpoch { epoch: 7 }
+    );
+
+    let validator_set_change_tag = TypeTag::Struct(StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("LibraSystem2").unwrap(),
+        name: Identifier::new("ValidatorSetChangeEvent").unwrap(),
+        type_params: vec![],
+    });
+    // (addr, consensus_voting_power, config: (consensus_pubkey), last_force_update_time)
+    let new_validator_set = vec![(address, 10u64, (vec![3u8; 32],), 0u64)];
+    let event = ContractEvent::new(
+        new_epoch_event_key(),
+        1,
+        validator_set_change_tag,
+        lcs::to_bytes(&new_validator_set).unwrap(),
+    );
+    assert_eq!(
+        EventView::from((0, event)).data,
+        EventDataView::ValidatorSetChange {
+            validators: vec![ValidatorSetMemberView {
+                account_address: address.to_string(),
+                consensus_public_key: BytesView::from(&vec![3u8; 32]),
+                consensus_voting_power: 10,
+            }],
+        }
+    );
+}
+
 fn fetch_error(resp: reqwest::blocking::Response) -> i16 {
     let data: JsonMap = resp.json().unwrap();
     let error: JsonMap = serde_json::from_value(data.get("error").unwrap().clone()).unwrap();
@@ -675,3 +1635,108 @@ fn fetch_error(resp: reqwest::blocking::Response) -> i16 {
     );
     serde_json::from_value(error.get("code").unwrap().clone()).unwrap()
 }
+
+/// Reads the next JSON RPC response from a WebSocket.
+async fn next_ws_response<S>(socket: &mut S) -> serde_json::Value
+where
+    S: Stream<Item = Result<tungstenite::Message, tungstenite::Error>> + Unpin,
+{
+    let message = socket.next().await.unwrap().unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+#[test]
+fn test_subscribe_to_transactions() {
+    let mock_db = mock_db();
+
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        channel(1).0,
+    );
+
+    rt.block_on(async {
+        let url = format!("ws://0.0.0.0:{}/stream", port);
+        let (mut socket, _) = connect_async(url.as_str()).await.unwrap();
+
+        // An unknown method is rejected.
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "subscribe_to_blocks", "params": [0],
+        });
+        socket
+            .send(tungstenite::Message::text(request.to_string()))
+            .await
+            .unwrap();
+        let response = next_ws_response(&mut socket).await;
+        assert_eq!(response["id"], serde_json::json!(1));
+        assert_eq!(
+            response["error"]["code"],
+            JsonRpcError::method_not_found().serialize()["code"]
+        );
+
+        // Subscribing from version 1 acknowledges and then pushes all transactions since.
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 2, "method": "subscribe_to_transactions", "params": [1],
+        });
+        socket
+            .send(tungstenite::Message::text(request.to_string()))
+            .await
+            .unwrap();
+        let response = next_ws_response(&mut socket).await;
+        assert_eq!(response["id"], serde_json::json!(2));
+        assert_eq!(response["result"], serde_json::Value::Null);
+
+        for (version, (txn, _)) in mock_db.all_txns.iter().enumerate().skip(1) {
+            let response = next_ws_response(&mut socket).await;
+            assert_eq!(response["id"], serde_json::json!(2));
+            let view: TransactionView = serde_json::from_value(response["result"].clone()).unwrap();
+            assert_eq!(view.version, version as u64);
+            assert_eq!(view.transaction, TransactionDataView::from(txn.clone()));
+        }
+    });
+}
+
+#[test]
+fn test_subscription_limit() {
+    let mock_db = mock_db();
+
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let mut rt = bootstrap(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        channel(1).0,
+        channel(1).0,
+    );
+
+    rt.block_on(async {
+        let url = format!("ws://0.0.0.0:{}/stream", port);
+        let (mut socket, _) = connect_async(url.as_str()).await.unwrap();
+
+        // Subscriptions from a future version have nothing to push yet.
+        let from_version = mock_db.all_txns.len() as u64 + 100;
+        for id in 0..=MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": id, "method": "subscribe_to_transactions",
+                "params": [from_version],
+            });
+            socket
+                .send(tungstenite::Message::text(request.to_string()))
+                .await
+                .unwrap();
+            let response = next_ws_response(&mut socket).await;
+            assert_eq!(response["id"], serde_json::json!(id));
+            if id < MAX_SUBSCRIPTIONS_PER_CONNECTION {
+                assert_eq!(response["result"], serde_json::Value::Null);
+            } else {
+                assert_eq!(
+                    response["error"]["code"],
+                    serde_json::json!(ServerCode::TooManySubscriptions as i16)
+                );
+            }
+        }
+    });
+}
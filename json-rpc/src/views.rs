@@ -4,20 +4,27 @@
 use crate::JsonRpcResponse;
 use anyhow::{format_err, Error, Result};
 use hex;
-use libra_crypto::HashValue;
+use libra_crypto::{hash::CryptoHash, HashValue, ValidKey};
+use libra_mempool::{MempoolSize, PendingTransaction, PendingTransactionStatus};
 use libra_types::{
+    account_address::AccountAddress,
     account_config::{
         received_payment_tag, sent_payment_tag, AccountResource, BalanceResource,
-        ReceivedPaymentEvent, SentPaymentEvent,
+        ReceivedPaymentEvent, SentPaymentEvent, CORE_CODE_ADDRESS,
     },
-    account_state_blob::AccountStateWithProof,
+    account_state_blob::{AccountStateChunkWithProof, AccountStateWithProof},
+    block_metadata::NewBlockEvent,
     contract_event::ContractEvent,
+    discovery_set::DiscoverySet,
     language_storage::TypeTag,
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{GasScheduleCost, GasScheduleResource, VMPublishingOption},
     proof::{AccountStateProof, AccumulatorConsistencyProof},
-    transaction::{Transaction, TransactionArgument, TransactionPayload},
+    transaction::{Transaction, TransactionArgument, TransactionOutput, TransactionPayload},
     validator_change::ValidatorChangeProof,
-    vm_error::StatusCode,
+    validator_info::ValidatorInfo,
+    vm_error::{StatusCode, VMStatus},
+    write_set::WriteOp,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -111,36 +118,130 @@ pub enum EventDataView {
         receiver: BytesView,
         metadata: BytesView,
     },
+    #[serde(rename = "newblock")]
+    NewBlock {
+        round: u64,
+        proposer: BytesView,
+        votes: Vec<BytesView>,
+        timestamp_usecs: u64,
+    },
+    #[serde(rename = "newepoch")]
+    NewEpoch { epoch: u64 },
+    #[serde(rename = "discoverysetchange")]
+    DiscoverySetChange { validators: Vec<BytesView> },
+    #[serde(rename = "validatorsetchange")]
+    ValidatorSetChange {
+        validators: Vec<ValidatorSetMemberView>,
+    },
+    #[serde(rename = "upgrade")]
+    Upgrade { writeset_payload: BytesView },
     #[serde(rename = "unknown")]
     Unknown {},
 }
 
+impl EventDataView {
+    /// Decodes the payload of the events emitted by the standard library modules, by the name of
+    /// their Move struct.
+    fn decode(event: &ContractEvent) -> Result<Self> {
+        let tag = match event.type_tag() {
+            TypeTag::Struct(tag) if tag.address == CORE_CODE_ADDRESS => tag,
+            _ => return Err(format_err!("Unknown events")),
+        };
+        if tag == &received_payment_tag() {
+            let received_event = ReceivedPaymentEvent::try_from(event)
+                .map_err(|_| format_err!("Unable to parse ReceivedPaymentEvent"))?;
+            return Ok(EventDataView::ReceivedPayment {
+                amount: received_event.amount(),
+                sender: BytesView::from(received_event.sender().as_ref()),
+                metadata: BytesView::from(received_event.metadata()),
+            });
+        }
+        if tag == &sent_payment_tag() {
+            let sent_event = SentPaymentEvent::try_from(event)
+                .map_err(|_| format_err!("Unable to parse SentPaymentEvent"))?;
+            return Ok(EventDataView::SentPayment {
+                amount: sent_event.amount(),
+                receiver: BytesView::from(sent_event.receiver().as_ref()),
+                metadata: BytesView::from(sent_event.metadata()),
+            });
+        }
+
+        let data = event.event_data();
+        match (tag.module.as_str(), tag.name.as_str()) {
+            ("LibraBlock", "NewBlockEvent") => {
+                let new_block_event: NewBlockEvent = lcs::from_bytes(data)?;
+                Ok(EventDataView::NewBlock {
+                    round: new_block_event.round(),
+                    proposer: BytesView::from(new_block_event.proposer().as_ref()),
+                    votes: new_block_event
+                        .votes()
+                        .iter()
+                        .map(|voter| BytesView::from(voter.as_ref()))
+                        .collect(),
+                    timestamp_usecs: new_block_event.timestamp_usecs(),
+                })
+            }
+            ("LibraConfig", "NewEpochEvent") => Ok(EventDataView::NewEpoch {
+                epoch: lcs::from_bytes(data)?,
+            }),
+            ("LibraSystem", "DiscoverySetChangeEvent") => Ok(EventDataView::DiscoverySetChange {
+                validators: DiscoverySet::from_bytes(data)?
+                    .iter()
+                    .map(|info| BytesView::from(info.account_address.as_ref()))
+                    .collect(),
+            }),
+            ("LibraSystem2", "ValidatorSetChangeEvent") => Ok(EventDataView::ValidatorSetChange {
+                validators: lcs::from_bytes::<Vec<MoveValidatorInfo>>(data)?
+                    .into_iter()
+                    .map(ValidatorSetMemberView::from)
+                    .collect(),
+            }),
+            ("LibraWriteSetManager", "UpgradeEvent") => Ok(EventDataView::Upgrade {
+                writeset_payload: BytesView::from(&lcs::from_bytes::<Vec<u8>>(data)?),
+            }),
+            _ => Err(format_err!("Unknown events")),
+        }
+    }
+}
+
+/// A validator in the new validator set of a `ValidatorSetChangeEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ValidatorSetMemberView {
+    pub account_address: String,
+    pub consensus_public_key: BytesView,
+    pub consensus_voting_power: u64,
+}
+
+/// Layout of `LibraSystem2::ValidatorInfo`, the payload of a `ValidatorSetChangeEvent` being a
+/// vector of them.
+#[derive(Deserialize)]
+struct MoveValidatorInfo {
+    addr: AccountAddress,
+    consensus_voting_power: u64,
+    config: MoveValidatorConfig,
+    _last_force_update_time: u64,
+}
+
+/// Layout of `ValidatorConfig2::Config`.
+#[derive(Deserialize)]
+struct MoveValidatorConfig {
+    consensus_pubkey: Vec<u8>,
+}
+
+impl From<MoveValidatorInfo> for ValidatorSetMemberView {
+    fn from(info: MoveValidatorInfo) -> Self {
+        Self {
+            account_address: info.addr.to_string(),
+            consensus_public_key: BytesView::from(&info.config.consensus_pubkey),
+            consensus_voting_power: info.consensus_voting_power,
+        }
+    }
+}
+
 impl From<(u64, ContractEvent)> for EventView {
     /// Tries to convert the provided byte array into Event Key.
     fn from((txn_version, event): (u64, ContractEvent)) -> EventView {
-        let event_data = if event.type_tag() == &TypeTag::Struct(received_payment_tag()) {
-            if let Ok(received_event) = ReceivedPaymentEvent::try_from(&event) {
-                Ok(EventDataView::ReceivedPayment {
-                    amount: received_event.amount(),
-                    sender: BytesView::from(received_event.sender().as_ref()),
-                    metadata: BytesView::from(received_event.metadata()),
-                })
-            } else {
-                Err(format_err!("Unable to parse ReceivedPaymentEvent"))
-            }
-        } else if event.type_tag() == &TypeTag::Struct(sent_payment_tag()) {
-            if let Ok(sent_event) = SentPaymentEvent::try_from(&event) {
-                Ok(EventDataView::SentPayment {
-                    amount: sent_event.amount(),
-                    receiver: BytesView::from(sent_event.receiver().as_ref()),
-                    metadata: BytesView::from(sent_event.metadata()),
-                })
-            } else {
-                Err(format_err!("Unable to parse ReceivedPaymentEvent"))
-            }
-        } else {
-            Err(format_err!("Unknown events"))
-        };
+        let event_data = EventDataView::decode(&event);
 
         EventView {
             key: BytesView::from(event.key().as_bytes()),
@@ -252,10 +353,10 @@ impl ResponseAsView for TransactionView {
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(tag = "type")]
-// TODO cover all script types
 pub enum ScriptView {
     #[serde(rename = "peer_to_peer_transaction")]
     PeerToPeer {
+        currency: String,
         receiver: String,
         auth_key_prefix: BytesView,
         amount: u64,
@@ -267,18 +368,86 @@ pub enum ScriptView {
         auth_key_prefix: BytesView,
         amount: u64,
     },
+    #[serde(rename = "add_validator_transaction")]
+    AddValidator { validator: String },
+    #[serde(rename = "approved_payment_transaction")]
+    ApprovedPayment {
+        currency: String,
+        payee: String,
+        amount: u64,
+        metadata: BytesView,
+        signature: BytesView,
+    },
+    #[serde(rename = "burn_transaction")]
+    Burn {
+        currency: String,
+        preburn_address: String,
+    },
+    #[serde(rename = "cancel_burn_transaction")]
+    CancelBurn {
+        currency: String,
+        preburn_address: String,
+    },
+    #[serde(rename = "create_account_transaction")]
+    CreateAccount {
+        address: String,
+        auth_key_prefix: BytesView,
+        initial_amount: u64,
+    },
+    #[serde(rename = "empty_script_transaction")]
+    EmptyScript {},
+    #[serde(rename = "modify_publishing_option_transaction")]
+    ModifyPublishingOption { publishing_option: BytesView },
+    #[serde(rename = "preburn_transaction")]
+    Preburn { currency: String, amount: u64 },
+    #[serde(rename = "register_approved_payment_transaction")]
+    RegisterApprovedPayment { public_key: BytesView },
+    #[serde(rename = "register_preburner_transaction")]
+    RegisterPreburner { currency: String },
+    #[serde(rename = "register_validator_transaction")]
+    RegisterValidator {
+        consensus_pubkey: BytesView,
+        validator_network_signing_pubkey: BytesView,
+        validator_network_identity_pubkey: BytesView,
+        validator_network_address: BytesView,
+        fullnodes_network_identity_pubkey: BytesView,
+        fullnodes_network_address: BytesView,
+    },
+    #[serde(rename = "remove_validator_transaction")]
+    RemoveValidator { validator: String },
+    #[serde(rename = "rotate_authentication_key_transaction")]
+    RotateAuthenticationKey { new_key: BytesView },
+    #[serde(rename = "rotate_consensus_pubkey_transaction")]
+    RotateConsensusPubkey { new_key: BytesView },
+    #[serde(rename = "update_libra_version_transaction")]
+    UpdateLibraVersion { major: u64 },
     #[serde(rename = "unknown_transaction")]
     Unknown {},
 }
 
 impl ScriptView {
-    // TODO cover all script types
     pub fn get_name(&self) -> String {
         match self {
-            ScriptView::PeerToPeer { .. } => "peer to peer transaction".to_string(),
-            ScriptView::Mint { .. } => "mint transaction".to_string(),
-            ScriptView::Unknown { .. } => "unknown transaction".to_string(),
+            ScriptView::PeerToPeer { .. } => "peer to peer transaction",
+            ScriptView::Mint { .. } => "mint transaction",
+            ScriptView::AddValidator { .. } => "add validator transaction",
+            ScriptView::ApprovedPayment { .. } => "approved payment transaction",
+            ScriptView::Burn { .. } => "burn transaction",
+            ScriptView::CancelBurn { .. } => "cancel burn transaction",
+            ScriptView::CreateAccount { .. } => "create account transaction",
+            ScriptView::EmptyScript { .. } => "empty script transaction",
+            ScriptView::ModifyPublishingOption { .. } => "modify publishing option transaction",
+            ScriptView::Preburn { .. } => "preburn transaction",
+            ScriptView::RegisterApprovedPayment { .. } => "register approved payment transaction",
+            ScriptView::RegisterPreburner { .. } => "register preburner transaction",
+            ScriptView::RegisterValidator { .. } => "register validator transaction",
+            ScriptView::RemoveValidator { .. } => "remove validator transaction",
+            ScriptView::RotateAuthenticationKey { .. } => "rotate authentication key transaction",
+            ScriptView::RotateConsensusPubkey { .. } => "rotate consensus pubkey transaction",
+            ScriptView::UpdateLibraVersion { .. } => "update libra version transaction",
+            ScriptView::Unknown { .. } => "unknown transaction",
         }
+        .to_string()
     }
 }
 
@@ -318,66 +487,252 @@ impl From<Transaction> for TransactionDataView {
 }
 
 impl From<TransactionPayload> for ScriptView {
+    /// Decodes the arguments of the standard library scripts, and the currency of those generic
+    /// over one. Scripts that are not in the standard library, or whose arguments don't match the
+    /// script signature, are `Unknown`.
     fn from(value: TransactionPayload) -> Self {
-        let empty_vec: Vec<TransactionArgument> = vec![];
-
-        let (code, args) = match value {
-            TransactionPayload::Program => ("deprecated".to_string(), empty_vec),
-            TransactionPayload::WriteSet(_) => ("genesis".to_string(), empty_vec),
-            TransactionPayload::Script(script) => {
-                (get_transaction_name(script.code()), script.args().to_vec())
-            }
-            TransactionPayload::Module(_) => ("module publishing".to_string(), empty_vec),
+        let (code, ty_args, args) = match value {
+            TransactionPayload::Program => ("deprecated".to_string(), vec![], vec![]),
+            TransactionPayload::WriteSet(_) => ("genesis".to_string(), vec![], vec![]),
+            TransactionPayload::Script(script) => (
+                get_transaction_name(script.code()),
+                script.ty_args().to_vec(),
+                script.args().to_vec(),
+            ),
+            TransactionPayload::Module(_) => ("module publishing".to_string(), vec![], vec![]),
         };
 
-        let res = match code.as_str() {
-            "peer_to_peer_transaction" => {
-                if let [TransactionArgument::Address(receiver), TransactionArgument::U8Vector(auth_key_prefix), TransactionArgument::U64(amount)] =
-                    &args[..]
-                {
-                    Ok(ScriptView::PeerToPeer {
-                        receiver: receiver.to_string(),
-                        auth_key_prefix: BytesView::from(auth_key_prefix),
-                        amount: *amount,
-                        metadata: BytesView::from(&[0u8; 0][..]),
-                    })
-                } else {
-                    Err(format_err!("Unable to parse PeerToPeer arguments"))
+        use TransactionArgument::{Address, U8Vector, U64};
+        let currency = currency_code(&ty_args);
+        match (code.as_str(), currency.as_deref(), &args[..]) {
+            (
+                "peer_to_peer_transaction",
+                Some(currency),
+                [Address(receiver), U8Vector(auth_key_prefix), U64(amount)],
+            ) => ScriptView::PeerToPeer {
+                currency: currency.to_string(),
+                receiver: receiver.to_string(),
+                auth_key_prefix: BytesView::from(auth_key_prefix),
+                amount: *amount,
+                metadata: BytesView::from(&[0u8; 0][..]),
+            },
+            (
+                "peer_to_peer_with_metadata_transaction",
+                Some(currency),
+                [Address(receiver), U8Vector(auth_key_prefix), U64(amount), U8Vector(metadata)],
+            ) => ScriptView::PeerToPeer {
+                currency: currency.to_string(),
+                receiver: receiver.to_string(),
+                auth_key_prefix: BytesView::from(auth_key_prefix),
+                amount: *amount,
+                metadata: BytesView::from(metadata),
+            },
+            (
+                "mint_transaction",
+                None,
+                [Address(receiver), U8Vector(auth_key_prefix), U64(amount)],
+            ) => ScriptView::Mint {
+                receiver: receiver.to_string(),
+                auth_key_prefix: BytesView::from(auth_key_prefix),
+                amount: *amount,
+            },
+            ("add_validator_transaction", None, [Address(validator)]) => ScriptView::AddValidator {
+                validator: validator.to_string(),
+            },
+            (
+                "approved_payment_transaction",
+                Some(currency),
+                [Address(payee), U64(amount), U8Vector(metadata), U8Vector(signature)],
+            ) => ScriptView::ApprovedPayment {
+                currency: currency.to_string(),
+                payee: payee.to_string(),
+                amount: *amount,
+                metadata: BytesView::from(metadata),
+                signature: BytesView::from(signature),
+            },
+            ("burn_transaction", Some(currency), [Address(preburn_address)]) => ScriptView::Burn {
+                currency: currency.to_string(),
+                preburn_address: preburn_address.to_string(),
+            },
+            ("cancel_burn_transaction", Some(currency), [Address(preburn_address)]) => {
+                ScriptView::CancelBurn {
+                    currency: currency.to_string(),
+                    preburn_address: preburn_address.to_string(),
                 }
             }
-            "peer_to_peer_with_metadata_transaction" => {
-                if let [TransactionArgument::Address(receiver), TransactionArgument::U8Vector(auth_key_prefix), TransactionArgument::U64(amount), TransactionArgument::U8Vector(metadata)] =
-                    &args[..]
-                {
-                    Ok(ScriptView::PeerToPeer {
-                        receiver: receiver.to_string(),
-                        auth_key_prefix: BytesView::from(auth_key_prefix),
-                        amount: *amount,
-                        metadata: BytesView::from(metadata),
-                    })
-                } else {
-                    Err(format_err!("Unable to parse PeerToPeer arguments"))
+            (
+                "create_account_transaction",
+                None,
+                [Address(address), U8Vector(auth_key_prefix), U64(initial_amount)],
+            ) => ScriptView::CreateAccount {
+                address: address.to_string(),
+                auth_key_prefix: BytesView::from(auth_key_prefix),
+                initial_amount: *initial_amount,
+            },
+            ("empty_script_transaction", None, []) => ScriptView::EmptyScript {},
+            ("modify_publishing_option_transaction", None, [U8Vector(publishing_option)]) => {
+                ScriptView::ModifyPublishingOption {
+                    publishing_option: BytesView::from(publishing_option),
                 }
             }
-            "mint_transaction" => {
-                if let [TransactionArgument::Address(receiver), TransactionArgument::U8Vector(auth_key_prefix), TransactionArgument::U64(amount)] =
-                    &args[..]
-                {
-                    Ok(ScriptView::Mint {
-                        receiver: receiver.to_string(),
-                        auth_key_prefix: BytesView::from(auth_key_prefix),
-                        amount: *amount,
-                    })
-                } else {
-                    Err(format_err!("Unable to parse PeerToPeer arguments"))
+            ("preburn_transaction", Some(currency), [U64(amount)]) => ScriptView::Preburn {
+                currency: currency.to_string(),
+                amount: *amount,
+            },
+            ("register_approved_payment_transaction", None, [U8Vector(public_key)]) => {
+                ScriptView::RegisterApprovedPayment {
+                    public_key: BytesView::from(public_key),
                 }
             }
-            _ => Err(format_err!("Unknown scripts")),
-        };
-        res.unwrap_or(ScriptView::Unknown {})
+            ("register_preburner_transaction", Some(currency), []) => {
+                ScriptView::RegisterPreburner {
+                    currency: currency.to_string(),
+                }
+            }
+            (
+                "register_validator_transaction",
+                None,
+                [U8Vector(consensus_pubkey), U8Vector(validator_network_signing_pubkey), U8Vector(validator_network_identity_pubkey), U8Vector(validator_network_address), U8Vector(fullnodes_network_identity_pubkey), U8Vector(fullnodes_network_address)],
+            ) => ScriptView::RegisterValidator {
+                consensus_pubkey: BytesView::from(consensus_pubkey),
+                validator_network_signing_pubkey: BytesView::from(validator_network_signing_pubkey),
+                validator_network_identity_pubkey: BytesView::from(
+                    validator_network_identity_pubkey,
+                ),
+                validator_network_address: BytesView::from(validator_network_address),
+                fullnodes_network_identity_pubkey: BytesView::from(
+                    fullnodes_network_identity_pubkey,
+                ),
+                fullnodes_network_address: BytesView::from(fullnodes_network_address),
+            },
+            ("remove_validator_transaction", None, [Address(validator)]) => {
+                ScriptView::RemoveValidator {
+                    validator: validator.to_string(),
+                }
+            }
+            ("rotate_authentication_key_transaction", None, [U8Vector(new_key)]) => {
+                ScriptView::RotateAuthenticationKey {
+                    new_key: BytesView::from(new_key),
+                }
+            }
+            ("rotate_consensus_pubkey_transaction", None, [U8Vector(new_key)]) => {
+                ScriptView::RotateConsensusPubkey {
+                    new_key: BytesView::from(new_key),
+                }
+            }
+            ("update_libra_version_transaction", None, [U64(major)]) => {
+                ScriptView::UpdateLibraVersion { major: *major }
+            }
+            _ => ScriptView::Unknown {},
+        }
+    }
+}
+
+/// Returns the currency code of the single currency type argument of a script, e.g. "LBR" for
+/// `0x0::LBR::T`.
+fn currency_code(ty_args: &[TypeTag]) -> Option<String> {
+    match ty_args {
+        [TypeTag::Struct(tag)] => Some(tag.module.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TransactionStatusView {
+    /// Hash of the submitted transaction, which `get_transaction_by_hash` looks up.
+    pub hash: String,
+    pub status: TransactionStatusDataView,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum TransactionStatusDataView {
+    #[serde(rename = "committed")]
+    Committed { transaction: TransactionView },
+    /// The transaction can no longer be committed.
+    #[serde(rename = "expired")]
+    Expired {},
+    /// The transaction left mempool without being committed, e.g. because it was evicted, garbage
+    /// collected or replaced by another transaction with the same sequence number.
+    #[serde(rename = "dropped")]
+    Dropped {},
+    /// The transaction is neither committed nor expired yet, but the server stopped waiting.
+    #[serde(rename = "pending")]
+    Pending {},
+}
+
+impl ResponseAsView for TransactionStatusView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::TransactionStatusResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SimulationView {
+    /// Version of the state the transaction was executed against.
+    pub version: u64,
+    /// Whether the transaction would be discarded rather than committed.
+    pub discarded: bool,
+    pub vm_status: VMStatus,
+    pub gas_used: u64,
+    /// Events the transaction would emit, as if it was committed at `version + 1`.
+    pub events: Vec<EventView>,
+    pub write_set: Vec<WriteOpView>,
+}
+
+impl SimulationView {
+    pub fn new(version: u64, output: TransactionOutput) -> Self {
+        let events = output
+            .events()
+            .iter()
+            .map(|event| EventView::from((version + 1, event.clone())))
+            .collect();
+        let write_set = output
+            .write_set()
+            .iter()
+            .map(|(access_path, write_op)| WriteOpView {
+                address: access_path.address.to_string(),
+                path: BytesView::from(&access_path.path),
+                value: match write_op {
+                    WriteOp::Value(value) => Some(BytesView::from(value)),
+                    WriteOp::Deletion => None,
+                },
+            })
+            .collect();
+        SimulationView {
+            version,
+            discarded: output.status().is_discarded(),
+            vm_status: output.status().vm_status(),
+            gas_used: output.gas_used(),
+            events,
+            write_set,
+        }
+    }
+}
+
+impl ResponseAsView for SimulationView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::SimulationResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct WriteOpView {
+    pub address: String,
+    pub path: BytesView,
+    /// The new value at `path`, absent if the value is deleted.
+    pub value: Option<BytesView>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct StateProofView {
     pub ledger_info_with_signatures: BytesView,
@@ -457,6 +812,99 @@ impl TryFrom<AccountStateWithProof> for AccountStateWithProofView {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountAtVersionView {
+    pub version: u64,
+    /// The account at `version`, absent if it didn't exist then.
+    pub account: Option<AccountView>,
+    /// State of the account at `version`, proven against the ledger version of the request.
+    pub account_state_with_proof: AccountStateWithProofView,
+}
+
+impl ResponseAsView for AccountAtVersionView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::AccountAtVersionResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BalanceHistoryView {
+    /// The account at `from_version`.
+    pub start: AccountAtVersionView,
+    /// The account at `to_version`.
+    pub end: AccountAtVersionView,
+    /// Payments sent and received after `from_version` up to `to_version`, ordered by version.
+    /// Gas fees are charged without an event, so they only show in the balances.
+    pub payments: Vec<EventView>,
+    /// Set if there are more payments than the page size limit. `payments` then only holds those
+    /// up to this version, and the rest can be fetched with this as the next `from_version`.
+    pub next_from_version: Option<u64>,
+}
+
+impl ResponseAsView for BalanceHistoryView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::BalanceHistoryResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountStateChunkView {
+    pub version: u64,
+    pub accounts: Vec<AccountStateBlobView>,
+    pub proof: BytesView,
+    /// Where to start the next page, absent if there are no more accounts.
+    pub next_key: Option<BytesView>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountStateBlobView {
+    /// Hash of the account address, which is the key in the state tree.
+    pub key: BytesView,
+    pub blob: BytesView,
+}
+
+impl ResponseAsView for AccountStateChunkView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::AccountStateChunkResponse(resp) = response {
+            Ok(resp)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+impl TryFrom<AccountStateChunkWithProof> for AccountStateChunkView {
+    type Error = Error;
+
+    fn try_from(chunk: AccountStateChunkWithProof) -> Result<AccountStateChunkView, Error> {
+        let next_key = chunk.next_key().map(|key| BytesView::from(&key.to_vec()));
+        let accounts = chunk
+            .account_blobs
+            .iter()
+            .map(|(key, blob)| {
+                Ok(AccountStateBlobView {
+                    key: BytesView::from(&key.to_vec()),
+                    blob: BytesView::from(&lcs::to_bytes(blob)?),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(AccountStateChunkView {
+            version: chunk.version,
+            accounts,
+            proof: BytesView::from(&lcs::to_bytes(&chunk.proof)?),
+            next_key,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AccountStateProofView {
     pub ledger_info_to_transaction_info_proof: BytesView,
@@ -481,3 +929,254 @@ impl TryFrom<AccountStateProof> for AccountStateProofView {
         })
     }
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorSetView {
+    pub version: u64,
+    pub validators: Vec<ValidatorInfoView>,
+    /// State of the validator set account, which proves `validators`.
+    pub account_state_with_proof: AccountStateWithProofView,
+}
+
+impl ResponseAsView for ValidatorSetView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::ValidatorSetResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorInfoView {
+    pub account_address: String,
+    pub consensus_public_key: BytesView,
+    pub consensus_voting_power: u64,
+    pub network_signing_public_key: BytesView,
+    pub network_identity_public_key: BytesView,
+}
+
+impl From<&ValidatorInfo> for ValidatorInfoView {
+    fn from(info: &ValidatorInfo) -> ValidatorInfoView {
+        ValidatorInfoView {
+            account_address: info.account_address().to_string(),
+            consensus_public_key: BytesView::from(&info.consensus_public_key().to_bytes()[..]),
+            consensus_voting_power: info.consensus_voting_power(),
+            network_signing_public_key: BytesView::from(
+                &info.network_signing_public_key().to_bytes()[..],
+            ),
+            network_identity_public_key: BytesView::from(
+                &info.network_identity_public_key().to_bytes(),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OnChainConfigsView {
+    pub version: u64,
+    pub libra_version: Option<u64>,
+    pub publishing_option: Option<PublishingOptionView>,
+    pub gas_schedule: Option<GasScheduleView>,
+    /// State of the association account, which proves the configs above.
+    pub account_state_with_proof: AccountStateWithProofView,
+}
+
+impl ResponseAsView for OnChainConfigsView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::OnChainConfigsResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum PublishingOptionView {
+    /// Only the scripts whose hashes are in `whitelist` may run.
+    #[serde(rename = "locked")]
+    Locked { whitelist: Vec<BytesView> },
+    #[serde(rename = "custom_scripts")]
+    CustomScripts {},
+    #[serde(rename = "open")]
+    Open {},
+}
+
+impl From<&VMPublishingOption> for PublishingOptionView {
+    fn from(option: &VMPublishingOption) -> PublishingOptionView {
+        match option {
+            VMPublishingOption::Locked(whitelist) => PublishingOptionView::Locked {
+                whitelist: whitelist
+                    .iter()
+                    .map(|hash| BytesView::from(&hash[..]))
+                    .collect(),
+            },
+            VMPublishingOption::CustomScripts => PublishingOptionView::CustomScripts {},
+            VMPublishingOption::Open => PublishingOptionView::Open {},
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GasScheduleView {
+    pub instruction_schedule: Vec<GasCostView>,
+    pub native_schedule: Vec<GasCostView>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GasCostView {
+    pub cpu: u64,
+    pub storage: u64,
+}
+
+impl From<&GasScheduleCost> for GasCostView {
+    fn from(cost: &GasScheduleCost) -> GasCostView {
+        GasCostView {
+            cpu: cost.cpu,
+            storage: cost.storage,
+        }
+    }
+}
+
+impl From<&GasScheduleResource> for GasScheduleView {
+    fn from(schedule: &GasScheduleResource) -> GasScheduleView {
+        GasScheduleView {
+            instruction_schedule: schedule
+                .instruction_schedule
+                .iter()
+                .map(GasCostView::from)
+                .collect(),
+            native_schedule: schedule
+                .native_schedule
+                .iter()
+                .map(GasCostView::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochInfoView {
+    pub epoch: u64,
+    pub last_reconfiguration_time: u64,
+    pub ledger_version: u64,
+    /// State of the association account, which proves the epoch info above.
+    pub account_state_with_proof: AccountStateWithProofView,
+}
+
+impl ResponseAsView for EpochInfoView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::EpochInfoResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PendingTransactionsView {
+    /// Sequence number of the account on chain, absent if the account doesn't exist.
+    pub account_sequence_number: Option<u64>,
+    /// Transactions of the account in mempool, ordered by sequence number.
+    pub transactions: Vec<PendingTransactionView>,
+}
+
+impl ResponseAsView for PendingTransactionsView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::PendingTransactionsResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PendingTransactionView {
+    pub sequence_number: u64,
+    pub hash: String,
+    pub transaction: TransactionDataView,
+    pub status: PendingTransactionStatusView,
+    /// Time in seconds at which mempool drops the transaction, even if it hasn't expired yet.
+    pub system_expiration_time: u64,
+}
+
+impl From<PendingTransaction> for PendingTransactionView {
+    fn from(pending: PendingTransaction) -> PendingTransactionView {
+        let sequence_number = pending.transaction.sequence_number();
+        let transaction = Transaction::UserTransaction(pending.transaction);
+        PendingTransactionView {
+            sequence_number,
+            hash: transaction.hash().to_hex(),
+            transaction: transaction.into(),
+            status: pending.status.into(),
+            system_expiration_time: pending.system_expiration_time.as_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum PendingTransactionStatusView {
+    /// The transaction can be included in the next block.
+    #[serde(rename = "ready")]
+    Ready {},
+    /// The transaction waits for the sender's transaction with `missing_sequence_number`, which
+    /// mempool doesn't have.
+    #[serde(rename = "parked")]
+    Parked { missing_sequence_number: u64 },
+}
+
+impl From<PendingTransactionStatus> for PendingTransactionStatusView {
+    fn from(status: PendingTransactionStatus) -> PendingTransactionStatusView {
+        match status {
+            PendingTransactionStatus::Ready => PendingTransactionStatusView::Ready {},
+            PendingTransactionStatus::Parked {
+                missing_sequence_number,
+            } => PendingTransactionStatusView::Parked {
+                missing_sequence_number,
+            },
+        }
+    }
+}
+
+impl ResponseAsView for PendingTransactionView {
+    fn optional_from_response(response: JsonRpcResponse) -> Result<Option<Self>> {
+        if let JsonRpcResponse::PendingTransactionResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Option<Self>>(response)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MempoolSizeView {
+    pub total: u64,
+    pub ready: u64,
+    pub parked: u64,
+}
+
+impl From<MempoolSize> for MempoolSizeView {
+    fn from(size: MempoolSize) -> MempoolSizeView {
+        MempoolSizeView {
+            total: size.total as u64,
+            ready: size.ready as u64,
+            parked: size.parked as u64,
+        }
+    }
+}
+
+impl ResponseAsView for MempoolSizeView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::MempoolSizeResponse(view) = response {
+            Ok(view)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
@@ -148,3 +148,16 @@ pub trait VMExecutor {
         state_view: &dyn StateView,
     ) -> Result<Vec<TransactionOutput>, VMStatus>;
 }
+
+/// This trait describes the VM's simulation interface.
+pub trait VMSimulator {
+    /// Executes a single user transaction against `state_view` and returns its output, which is
+    /// never applied. If `skip_signature_check` is set, the signature of the transaction is not
+    /// verified, but the prologue still checks its public key against the sender's
+    /// authentication key.
+    fn simulate_transaction(
+        transaction: SignedTransaction,
+        skip_signature_check: bool,
+        state_view: &dyn StateView,
+    ) -> TransactionOutput;
+}
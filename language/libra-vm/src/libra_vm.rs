@@ -3,7 +3,7 @@
 
 use crate::{
     counters::*, on_chain_configs::VMConfig as OnlineConfig, system_module_names::*, VMExecutor,
-    VMVerifier,
+    VMSimulator, VMVerifier,
 };
 use debug_interface::prelude::*;
 use libra_crypto::HashValue;
@@ -900,6 +900,41 @@ impl VMExecutor for LibraVM {
     }
 }
 
+// Simulator external API
+impl VMSimulator for LibraVM {
+    /// Runs the transaction through the same verification and execution path as `execute_block`
+    /// does for user transactions, so the output carries the gas used, status, events and write
+    /// set the transaction would have if it was committed next.
+    fn simulate_transaction(
+        transaction: SignedTransaction,
+        skip_signature_check: bool,
+        state_view: &dyn StateView,
+    ) -> TransactionOutput {
+        let mut vm = LibraVM::new();
+        let mut data_cache = BlockDataCache::new(state_view);
+        vm.load_configs_impl(&data_cache);
+        let transaction = if skip_signature_check {
+            skip_signature_check_for_simulation(transaction)
+        } else {
+            match transaction.check_signature() {
+                Ok(txn) => txn,
+                Err(_) => {
+                    return discard_error_output(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+                }
+            }
+        };
+        vm.execute_user_transaction(state_view, &mut data_cache, &transaction)
+    }
+}
+
+/// Treats `transaction` as signature checked without checking it. Only `simulate_transaction` may
+/// call this, since its output is never committed.
+fn skip_signature_check_for_simulation(
+    transaction: SignedTransaction,
+) -> SignatureCheckedTransaction {
+    transaction.into_simulated()
+}
+
 /// Internal APIs for the Libra VM, primarily used for testing.
 #[derive(Clone, Copy)]
 pub struct LibraVMInternals<'a>(&'a LibraVM);
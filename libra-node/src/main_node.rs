@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use admission_control_service::admission_control_service::AdmissionControlService;
+use anyhow::ensure;
 use consensus::consensus_provider::{make_consensus_provider, ConsensusProvider};
 use debug_interface::{
     node_debug_service::NodeDebugService,
@@ -9,7 +10,7 @@ use debug_interface::{
 };
 use executor::{db_bootstrapper::maybe_bootstrap_db, Executor};
 use futures::{channel::mpsc::channel, executor::block_on};
-use libra_config::config::{NetworkConfig, NodeConfig, RoleType};
+use libra_config::config::{LedgerPruningWindow, NetworkConfig, NodeConfig, RoleType};
 use libra_json_rpc::bootstrap_from_config as bootstrap_rpc;
 use libra_logger::prelude::*;
 use libra_mempool::MEMPOOL_SUBSCRIBED_CONFIGS;
@@ -57,7 +58,7 @@ fn setup_executor(config: &NodeConfig) -> Arc<Mutex<Executor<LibraVM>>> {
     )))
 }
 
-fn setup_debug_interface(config: &NodeConfig) -> Runtime {
+fn setup_debug_interface(config: &NodeConfig, service: NodeDebugService) -> Runtime {
     let rt = tokio::runtime::Runtime::new().unwrap();
     let addr = format!(
         "{}:{}",
@@ -69,7 +70,7 @@ fn setup_debug_interface(config: &NodeConfig) -> Runtime {
     .unwrap();
     rt.spawn(
         tonic::transport::Server::builder()
-            .add_service(NodeDebugInterfaceServer::new(NodeDebugService::new()))
+            .add_service(NodeDebugInterfaceServer::new(service))
             .serve(addr),
     );
     rt
@@ -169,6 +170,32 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
     let mut instant = Instant::now();
     let (libra_db, db_reader_writer) = init_libra_db(&node_config);
     let storage = start_storage_service_with_db(&node_config, Arc::clone(&libra_db));
+    let db_for_debug = Arc::clone(&libra_db);
+    let configured_window = node_config.storage.ledger_pruning_window;
+    let debug_service = NodeDebugService::new().with_config_handler(
+        "storage.ledger_pruning_window",
+        Arc::new(move |value: &str| {
+            let window = match value {
+                "none" => None,
+                _ => Some(value.parse::<LedgerPruningWindow>()?),
+            };
+            // The debug interface is unauthenticated and pruning can't be undone, so it may only
+            // keep more history than the config file asks for. Shrinking the window requires
+            // changing the config and restarting the node.
+            let keeps_configured = match (window, configured_window) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(window), Some(configured)) => window.keeps_at_least(&configured),
+            };
+            ensure!(
+                keeps_configured,
+                "Ledger pruning window {} is smaller than the configured {:?}.",
+                value,
+                configured_window,
+            );
+            db_for_debug.set_ledger_pruning_window(window)
+        }),
+    );
     maybe_bootstrap_db::<LibraVM>(db_reader_writer, node_config)
         .expect("Db-bootstrapper should not fail.");
 
@@ -233,7 +260,13 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
 
     let admission_control_runtime =
         AdmissionControlService::bootstrap(&node_config, mp_client_sender.clone());
-    let rpc_runtime = bootstrap_rpc(&node_config, libra_db.clone(), mp_client_sender);
+    let (mp_inspection_sender, mp_inspection_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
+    let rpc_runtime = bootstrap_rpc(
+        &node_config,
+        libra_db.clone(),
+        mp_client_sender,
+        mp_inspection_sender,
+    );
 
     let mut consensus = None;
     let (consensus_to_mempool_sender, consensus_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
@@ -243,6 +276,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         node_config,
         mempool_network_handles,
         mp_client_events,
+        mp_inspection_requests,
         consensus_requests,
         state_sync_requests,
         mempool_reconfig_events,
@@ -292,7 +326,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     }
 
-    let debug_if = setup_debug_interface(&node_config);
+    let debug_if = setup_debug_interface(&node_config, debug_service);
 
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
@@ -6,7 +6,7 @@ use crate::core_mempool::transaction::{MempoolTransaction, TimelineState};
 use libra_types::account_address::AccountAddress;
 use std::{
     cmp::Ordering,
-    collections::{btree_set::Iter, BTreeMap, BTreeSet},
+    collections::{btree_map, btree_set, BTreeMap, BTreeSet, HashMap, VecDeque},
     iter::Rev,
     ops::Bound,
     time::Duration,
@@ -17,30 +17,75 @@ pub type AccountTransactions = BTreeMap<u64, MempoolTransaction>;
 /// PriorityIndex represents main Priority Queue in Mempool
 /// It's used to form transaction block for Consensus
 /// Transactions are ordered by gas price. Second level ordering is done by expiration time
+/// The same transactions are also indexed by arrival and by sender, for the other orderings
+/// `TransactionOrdering` offers. Arrival follows system expiration time, since system TTL is fixed
 ///
 /// We don't store full content of transaction in index
 /// Instead we use `OrderedQueueKey` - logical reference to transaction in main store
 pub struct PriorityIndex {
     data: BTreeSet<OrderedQueueKey>,
+    arrival: BTreeSet<(Duration, AccountAddress, u64)>,
+    // system expiration time of transactions of every sender, by sequence number
+    by_sender: HashMap<AccountAddress, BTreeMap<u64, Duration>>,
+    // senders by system expiration time of their oldest transaction
+    senders: BTreeSet<(Duration, AccountAddress)>,
 }
 
-pub type PriorityQueueIter<'a> = Rev<Iter<'a, OrderedQueueKey>>;
+pub type PriorityQueueIter<'a> = Rev<btree_set::Iter<'a, OrderedQueueKey>>;
 
 impl PriorityIndex {
     pub(crate) fn new() -> Self {
         Self {
             data: BTreeSet::new(),
+            arrival: BTreeSet::new(),
+            by_sender: HashMap::new(),
+            senders: BTreeSet::new(),
         }
     }
 
     /// add transaction to index
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(self.make_key(&txn));
+        let key = self.make_key(&txn);
+        self.arrival
+            .insert((key.expiration_time, key.address, key.sequence_number));
+        let txns = self.by_sender.entry(key.address).or_default();
+        let oldest = txns.values().min().cloned();
+        txns.insert(key.sequence_number, key.expiration_time);
+        self.update_sender(key.address, oldest);
+        self.data.insert(key);
     }
 
     /// remove transaction from index
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&self.make_key(&txn));
+        let key = self.make_key(&txn);
+        self.arrival
+            .remove(&(key.expiration_time, key.address, key.sequence_number));
+        if let Some(txns) = self.by_sender.get_mut(&key.address) {
+            let oldest = txns.values().min().cloned();
+            txns.remove(&key.sequence_number);
+            self.update_sender(key.address, oldest);
+        }
+        self.data.remove(&key);
+    }
+
+    /// moves `address` to its place in `senders` after its transactions changed, given the
+    /// expiration time of its oldest transaction before
+    fn update_sender(&mut self, address: AccountAddress, previous_oldest: Option<Duration>) {
+        if let Some(oldest) = previous_oldest {
+            self.senders.remove(&(oldest, address));
+        }
+        let oldest = self
+            .by_sender
+            .get(&address)
+            .and_then(|txns| txns.values().min().cloned());
+        match oldest {
+            Some(oldest) => {
+                self.senders.insert((oldest, address));
+            }
+            None => {
+                self.by_sender.remove(&address);
+            }
+        }
     }
 
     pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
@@ -61,11 +106,60 @@ impl PriorityIndex {
         self.data.iter().rev()
     }
 
+    /// returns iterator over transactions in order of arrival
+    pub(crate) fn iter_by_arrival(&self) -> impl Iterator<Item = TxnPointer> + '_ {
+        self.arrival
+            .iter()
+            .map(|(_, address, sequence_number)| (*address, *sequence_number))
+    }
+
+    /// returns iterator over transactions taking one of every sender in turn
+    pub(crate) fn iter_round_robin(&self) -> RoundRobinIter {
+        RoundRobinIter {
+            senders: self.senders.iter(),
+            by_sender: &self.by_sender,
+            rounds: VecDeque::new(),
+        }
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.data.len()
     }
 }
 
+/// Iterates over transactions in rounds: every round includes the next transaction of each sender,
+/// in sequence number order. Within a round, senders go in order of arrival of their oldest
+/// transaction. Senders are only looked up once the first round reaches them
+pub struct RoundRobinIter<'a> {
+    senders: btree_set::Iter<'a, (Duration, AccountAddress)>,
+    by_sender: &'a HashMap<AccountAddress, BTreeMap<u64, Duration>>,
+    // senders that have been reached, with their transactions left for the next rounds
+    rounds: VecDeque<(AccountAddress, btree_map::Keys<'a, u64, Duration>)>,
+}
+
+impl<'a> Iterator for RoundRobinIter<'a> {
+    type Item = TxnPointer;
+
+    fn next(&mut self) -> Option<TxnPointer> {
+        for (_, address) in &mut self.senders {
+            if let Some(txns) = self.by_sender.get(address) {
+                let mut sequence_numbers = txns.keys();
+                if let Some(sequence_number) = sequence_numbers.next() {
+                    self.rounds.push_back((*address, sequence_numbers));
+                    return Some((*address, *sequence_number));
+                }
+            }
+        }
+        while let Some((address, mut sequence_numbers)) = self.rounds.pop_front() {
+            if let Some(sequence_number) = sequence_numbers.next() {
+                self.rounds.push_back((address, sequence_numbers));
+                return Some((address, *sequence_number));
+            }
+        }
+        None
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct OrderedQueueKey {
     pub gas_ranking_score: u64,
@@ -252,8 +346,9 @@ impl TimelineIndex {
 /// e.g. transactions that can't be included in next block
 /// (because their sequence number is too high)
 /// we keep separate index to be able to efficiently evict them when Mempool is full
+/// Transactions are ordered by gas ranking score, so the cheapest ones are evicted first
 pub struct ParkingLotIndex {
-    data: BTreeSet<TxnPointer>,
+    data: BTreeSet<ParkingLotKey>,
 }
 
 impl ParkingLotIndex {
@@ -265,17 +360,18 @@ impl ParkingLotIndex {
 
     /// add transaction to index
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(TxnPointer::from(txn));
+        self.data.insert(ParkingLotKey::from(txn));
     }
 
     /// remove transaction from index
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&TxnPointer::from(txn));
+        self.data.remove(&ParkingLotKey::from(txn));
     }
 
-    /// returns random "non-ready" transaction (with highest sequence number for that account)
-    pub(crate) fn pop(&mut self) -> Option<TxnPointer> {
-        self.data.iter().rev().next().cloned()
+    /// returns "non-ready" transaction with lowest gas ranking score
+    /// (with highest sequence number for that account, among equally ranked ones)
+    pub(crate) fn lowest(&self) -> Option<ParkingLotKey> {
+        self.data.iter().next().cloned()
     }
 
     pub(crate) fn size(&self) -> usize {
@@ -283,6 +379,44 @@ impl ParkingLotIndex {
     }
 }
 
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct ParkingLotKey {
+    pub gas_ranking_score: u64,
+    pub address: AccountAddress,
+    pub sequence_number: u64,
+}
+
+impl PartialOrd for ParkingLotKey {
+    fn partial_cmp(&self, other: &ParkingLotKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ParkingLotKey {
+    fn cmp(&self, other: &ParkingLotKey) -> Ordering {
+        match self.gas_ranking_score.cmp(&other.gas_ranking_score) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+        // evicting the last transaction of an account doesn't park any other one
+        match self.sequence_number.cmp(&other.sequence_number).reverse() {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+        self.address.cmp(&other.address)
+    }
+}
+
+impl From<&MempoolTransaction> for ParkingLotKey {
+    fn from(txn: &MempoolTransaction) -> Self {
+        ParkingLotKey {
+            gas_ranking_score: txn.ranking_score,
+            address: txn.get_sender(),
+            sequence_number: txn.get_sequence_number(),
+        }
+    }
+}
+
 /// Logical pointer to `MempoolTransaction`
 /// Includes Account's address and transaction sequence number
 pub type TxnPointer = (AccountAddress, u64);
@@ -8,14 +8,16 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{MempoolSize, MempoolTransaction, PendingTransaction, TimelineState},
         transaction_store::TransactionStore,
     },
+    mempooldb::MempoolDB,
     OP_COUNTERS,
 };
+use anyhow::Result;
 use chrono::Utc;
 use debug_interface::prelude::*;
-use libra_config::config::NodeConfig;
+use libra_config::config::{NodeConfig, TransactionOrdering};
 use libra_logger::prelude::*;
 use libra_types::{
     account_address::AccountAddress,
@@ -29,6 +31,8 @@ use ttl_cache::TtlCache;
 pub struct Mempool {
     // stores metadata of all transactions in mempool (of all states)
     transactions: TransactionStore,
+    // decides in which order ready transactions are proposed to consensus
+    transaction_ordering: TransactionOrdering,
 
     sequence_number_cache: LruCache<AccountAddress, u64>,
     // temporary DS. TODO: eventually retire it
@@ -41,8 +45,14 @@ pub struct Mempool {
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        let journal = if config.mempool.persist_transactions {
+            Some(MempoolDB::new(config.storage.dir()))
+        } else {
+            None
+        };
         Mempool {
-            transactions: TransactionStore::new(&config.mempool),
+            transactions: TransactionStore::new(&config.mempool, journal),
+            transaction_ordering: config.mempool.transaction_ordering,
             sequence_number_cache: LruCache::new(config.mempool.capacity),
             metrics_cache: TtlCache::new(config.mempool.capacity),
             system_transaction_timeout: Duration::from_secs(
@@ -179,21 +189,23 @@ impl Mempool {
         let mut skipped = HashSet::new();
         let seen_size = seen.len();
         let mut txn_walked = 0usize;
-        // iterate over the queue of transactions based on gas price
-        'main: for txn in self.transactions.iter_queue() {
+        // iterate over the queue of transactions in order of the configured ordering
+        'main: for (address, sequence_number) in
+            self.transactions.iter_queue(self.transaction_ordering)
+        {
             txn_walked += 1;
-            if seen.contains(&TxnPointer::from(txn)) {
+            if seen.contains(&(address, sequence_number)) {
                 continue;
             }
-            let mut seq = txn.sequence_number;
-            let account_sequence_number = self.sequence_number_cache.get_mut(&txn.address);
-            let seen_previous = seq > 0 && seen.contains(&(txn.address, seq - 1));
+            let mut seq = sequence_number;
+            let account_sequence_number = self.sequence_number_cache.get_mut(&address);
+            let seen_previous = seq > 0 && seen.contains(&(address, seq - 1));
             // include transaction if it's "next" for given account or
             // we've already sent its ancestor to Consensus
             if seen_previous || account_sequence_number == Some(&mut seq) {
-                let ptr = TxnPointer::from(txn);
+                let ptr = (address, sequence_number);
                 seen.insert(ptr);
-                trace_event!("mempool::get_block", {"txn", txn.address, txn.sequence_number});
+                trace_event!("mempool::get_block", {"txn", address, sequence_number});
                 result.push(ptr);
                 if (result.len() as u64) == batch_size {
                     break;
@@ -201,17 +213,17 @@ impl Mempool {
 
                 // check if we can now include some transactions
                 // that were skipped before for given account
-                let mut skipped_txn = (txn.address, seq + 1);
+                let mut skipped_txn = (address, seq + 1);
                 while skipped.contains(&skipped_txn) {
                     seen.insert(skipped_txn);
                     result.push(skipped_txn);
                     if (result.len() as u64) == batch_size {
                         break 'main;
                     }
-                    skipped_txn = (txn.address, skipped_txn.1 + 1);
+                    skipped_txn = (address, skipped_txn.1 + 1);
                 }
             } else {
-                skipped.insert(TxnPointer::from(txn));
+                skipped.insert((address, sequence_number));
             }
         }
         let result_size = result.len();
@@ -232,6 +244,57 @@ impl Mempool {
         block
     }
 
+    /// Returns all transactions of `address` that are currently in Mempool
+    pub(crate) fn get_account_transactions(
+        &mut self,
+        address: &AccountAddress,
+    ) -> Vec<PendingTransaction> {
+        let current_seq_number = self.current_sequence_number(address);
+        self.transactions
+            .get_account_transactions(address, current_seq_number)
+    }
+
+    /// Returns transaction by sender and sequence number, along with the reason it's parked if so
+    pub(crate) fn get_pending_transaction(
+        &mut self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<PendingTransaction> {
+        let current_seq_number = self.current_sequence_number(address);
+        self.transactions
+            .get_pending_transaction(address, sequence_number, current_seq_number)
+    }
+
+    /// Last known sequence number of `address` on chain
+    fn current_sequence_number(&mut self, address: &AccountAddress) -> u64 {
+        self.sequence_number_cache
+            .get_mut(address)
+            .map_or(0, |sequence_number| *sequence_number)
+    }
+
+    /// Returns number of transactions in Mempool
+    pub(crate) fn size(&self) -> MempoolSize {
+        self.transactions.size()
+    }
+
+    /// Reads all transactions from the on-disk journal, to validate and add them again on startup
+    /// Returns nothing if transactions aren't persisted
+    pub(crate) fn journaled_transactions(&self) -> Result<Vec<(SignedTransaction, TimelineState)>> {
+        self.transactions.journaled_transactions()
+    }
+
+    /// Removes the journaled transactions among `txns` that weren't added back to Mempool
+    pub(crate) fn forget_journaled_transactions(&mut self, txns: Vec<TxnPointer>) {
+        self.transactions.forget_journaled(txns);
+    }
+
+    /// Writes the journal changes since the last call to disk, in a single batch
+    /// Changes are buffered so that operations touching many transactions, like a commit, write
+    /// them at once
+    pub(crate) fn flush_journal(&mut self) {
+        self.transactions.flush_journal();
+    }
+
     /// TTL based garbage collection. Remove all transactions that got expired
     pub(crate) fn gc_by_system_ttl(&mut self) {
         self.transactions.gc_by_system_ttl();
@@ -6,7 +6,11 @@ mod mempool;
 mod transaction;
 mod transaction_store;
 
-pub use self::{index::TxnPointer, mempool::Mempool as CoreMempool, transaction::TimelineState};
+pub use self::{
+    index::TxnPointer,
+    mempool::Mempool as CoreMempool,
+    transaction::{MempoolSize, PendingTransaction, PendingTransactionStatus, TimelineState},
+};
 
 #[cfg(test)]
 mod unit_tests;
@@ -53,3 +53,38 @@ pub enum TimelineState {
     // currently we don't broadcast transactions originated on other peers
     NonQualified,
 }
+
+/// Transaction that is currently in Mempool, as seen by Mempool's inspection API
+#[derive(Clone, Debug)]
+pub struct PendingTransaction {
+    /// transaction content
+    pub transaction: SignedTransaction,
+    /// whether transaction can be included in next block
+    pub status: PendingTransactionStatus,
+    /// time at which Mempool drops the transaction regardless of its client-specified expiration
+    pub system_expiration_time: Duration,
+}
+
+/// Readiness of transaction in Mempool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingTransactionStatus {
+    /// transaction is in the priority queue and can be included in next block
+    Ready,
+    /// transaction is in the parking lot: it can't be included in next block
+    /// until transaction with `missing_sequence_number` from the same sender arrives
+    Parked {
+        /// closest sequence number below the parked transaction that Mempool doesn't have
+        missing_sequence_number: u64,
+    },
+}
+
+/// Number of transactions in Mempool, split by readiness
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MempoolSize {
+    /// all transactions in Mempool
+    pub total: usize,
+    /// transactions that can be included in next block
+    pub ready: usize,
+    /// transactions in the parking lot
+    pub parked: usize,
+}
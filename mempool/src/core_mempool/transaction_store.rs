@@ -4,15 +4,19 @@
 use crate::{
     core_mempool::{
         index::{
-            AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            AccountTransactions, ParkingLotIndex, PriorityIndex, TTLIndex, TimelineIndex,
+            TxnPointer,
+        },
+        transaction::{
+            MempoolSize, MempoolTransaction, PendingTransaction, PendingTransactionStatus,
+            TimelineState,
         },
-        transaction::{MempoolTransaction, TimelineState},
     },
+    mempooldb::{MempoolDB, PersistedTransaction},
     OP_COUNTERS,
 };
 use anyhow::{format_err, Result};
-use libra_config::config::MempoolConfig;
+use libra_config::config::{MempoolConfig, TransactionOrdering};
 use libra_logger::prelude::*;
 use libra_types::{
     account_address::AccountAddress,
@@ -20,6 +24,7 @@ use libra_types::{
     transaction::SignedTransaction,
 };
 use std::{
+    cmp::max,
     collections::HashMap,
     ops::Bound,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -45,10 +50,17 @@ pub struct TransactionStore {
     // configuration
     capacity: usize,
     capacity_per_user: usize,
+    // minimal gas price increase, in percent, for a transaction to replace a pending one
+    gas_price_bump_percent: u64,
+
+    // on-disk journal of transactions, if they are persisted
+    journal: Option<MempoolDB>,
+    // journal changes since the last flush, `None` for transactions that left mempool
+    journal_updates: HashMap<TxnPointer, Option<PersistedTransaction>>,
 }
 
 impl TransactionStore {
-    pub(crate) fn new(config: &MempoolConfig) -> Self {
+    pub(crate) fn new(config: &MempoolConfig, journal: Option<MempoolDB>) -> Self {
         Self {
             // main DS
             transactions: HashMap::new(),
@@ -65,6 +77,10 @@ impl TransactionStore {
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
+            gas_price_bump_percent: config.gas_price_bump_percent,
+
+            journal,
+            journal_updates: HashMap::new(),
         }
     }
 
@@ -84,6 +100,72 @@ impl TransactionStore {
         None
     }
 
+    /// fetch all transactions of given account along with their readiness,
+    /// ordered by sequence number
+    /// `current_sequence_number` is the account's sequence number on chain
+    pub(crate) fn get_account_transactions(
+        &self,
+        address: &AccountAddress,
+        current_sequence_number: u64,
+    ) -> Vec<PendingTransaction> {
+        self.transactions
+            .get(&address)
+            .map(|txns| {
+                txns.values()
+                    .map(|txn| self.pending_transaction(txns, txn, current_sequence_number))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// fetch transaction by account address + sequence_number along with its readiness
+    /// `current_sequence_number` is the account's sequence number on chain
+    pub(crate) fn get_pending_transaction(
+        &self,
+        address: &AccountAddress,
+        sequence_number: u64,
+        current_sequence_number: u64,
+    ) -> Option<PendingTransaction> {
+        let txns = self.transactions.get(&address)?;
+        txns.get(&sequence_number)
+            .map(|txn| self.pending_transaction(txns, txn, current_sequence_number))
+    }
+
+    /// transactions that are not in PriorityIndex are parked because of a sequence number gap
+    /// we report the lowest missing sequence number at or above the account's sequence number,
+    /// since that's the one that has to be submitted next
+    fn pending_transaction(
+        &self,
+        txns: &AccountTransactions,
+        txn: &MempoolTransaction,
+        current_sequence_number: u64,
+    ) -> PendingTransaction {
+        let status = if self.priority_index.contains(txn) {
+            PendingTransactionStatus::Ready
+        } else {
+            let missing_sequence_number = (current_sequence_number..txn.get_sequence_number())
+                .find(|sequence_number| !txns.contains_key(sequence_number))
+                .unwrap_or(current_sequence_number);
+            PendingTransactionStatus::Parked {
+                missing_sequence_number,
+            }
+        };
+        PendingTransaction {
+            transaction: txn.txn.clone(),
+            status,
+            system_expiration_time: txn.expiration_time,
+        }
+    }
+
+    /// number of transactions in TransactionStore
+    pub(crate) fn size(&self) -> MempoolSize {
+        MempoolSize {
+            total: self.system_ttl_index.size(),
+            ready: self.priority_index.size(),
+            parked: self.parking_lot_index.size(),
+        }
+    }
+
     /// insert transaction into TransactionStore
     /// performs validation checks and updates indexes
     pub(crate) fn insert(
@@ -91,14 +173,15 @@ impl TransactionStore {
         txn: MempoolTransaction,
         current_sequence_number: u64,
     ) -> MempoolStatus {
-        if self.handle_gas_price_update(&txn).is_err() {
+        if let Err(e) = self.handle_gas_price_update(&txn) {
             return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(format!(
-                "Failed to update gas price to {}",
-                txn.get_gas_price()
+                "Failed to update gas price to {}: {}",
+                txn.get_gas_price(),
+                e
             ));
         }
 
-        if self.check_if_full() {
+        if self.check_if_full(&txn, current_sequence_number) {
             return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
                 "mempool size: {}, capacity: {}",
                 self.system_ttl_index.size(),
@@ -128,6 +211,14 @@ impl TransactionStore {
             }
 
             // insert into storage and other indexes
+            if self.journal.is_some() {
+                let persisted = PersistedTransaction {
+                    transaction: txn.txn.clone(),
+                    broadcast_qualified: txn.timeline_state != TimelineState::NonQualified,
+                };
+                self.journal_updates
+                    .insert((address, sequence_number), Some(persisted));
+            }
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
             txns.insert(sequence_number, txn);
@@ -144,41 +235,62 @@ impl TransactionStore {
     }
 
     /// checks if Mempool is full
-    /// If it's full, tries to free some space by evicting transactions from ParkingLot
-    fn check_if_full(&mut self) -> bool {
+    /// If it's full, tries to free some space for `txn` by evicting the cheapest transaction from
+    /// ParkingLot. A ready transaction can evict any parked one, while a transaction that would be
+    /// parked itself only evicts one with lower gas ranking score
+    fn check_if_full(&mut self, txn: &MempoolTransaction, current_sequence_number: u64) -> bool {
         if self.system_ttl_index.size() >= self.capacity {
             // try to free some space in Mempool from ParkingLot
-            if let Some((address, sequence_number)) = self.parking_lot_index.pop() {
-                if let Some(txn) = self
-                    .transactions
-                    .get_mut(&address)
-                    .and_then(|txns| txns.remove(&sequence_number))
+            if let Some(key) = self.parking_lot_index.lowest() {
+                if self.is_ready(txn, current_sequence_number)
+                    || key.gas_ranking_score < txn.ranking_score
                 {
-                    self.index_remove(&txn);
+                    if let Some(evicted) = self
+                        .transactions
+                        .get_mut(&key.address)
+                        .and_then(|txns| txns.remove(&key.sequence_number))
+                    {
+                        OP_COUNTERS.inc("txn.evicted");
+                        self.index_remove(&evicted);
+                    }
                 }
             }
         }
         self.system_ttl_index.size() >= self.capacity
     }
 
+    /// checks if transaction would be sequential to current sequence number once inserted
+    /// e.g. if it can be included in next block
+    fn is_ready(&self, txn: &MempoolTransaction, current_sequence_number: u64) -> bool {
+        let txns = self.transactions.get(&txn.get_sender());
+        (current_sequence_number..txn.get_sequence_number())
+            .all(|sequence_number| txns.map_or(false, |txns| txns.contains_key(&sequence_number)))
+    }
+
     /// check if transaction is already present in Mempool
     /// e.g. given request is update
-    /// we allow increase in gas price to speed up process
+    /// we allow replacing it with same transaction paying higher gas price to speed up process,
+    /// as long as the increase is at least `gas_price_bump_percent`
     fn handle_gas_price_update(&mut self, txn: &MempoolTransaction) -> Result<()> {
+        let gas_price_bump_percent = self.gas_price_bump_percent;
         if let Some(txns) = self.transactions.get_mut(&txn.get_sender()) {
             if let Some(current_version) = txns.get_mut(&txn.get_sequence_number()) {
+                let min_gas_price =
+                    replacement_gas_price(current_version.get_gas_price(), gas_price_bump_percent);
                 if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
                     && current_version.txn.payload() == txn.txn.payload()
                     && current_version.txn.expiration_time() == txn.txn.expiration_time()
-                    && current_version.get_gas_price() < txn.get_gas_price()
+                    && txn.get_gas_price() >= min_gas_price
                 {
                     if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
+                        OP_COUNTERS.inc("txn.replaced");
                         self.index_remove(&txn);
                     }
                 } else {
-                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}",
+                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}, min gas price: {}",
                             txn.get_gas_price(),
-                            current_version.get_gas_price()));
+                            current_version.get_gas_price(),
+                            min_gas_price));
                 }
             }
         }
@@ -199,6 +311,7 @@ impl TransactionStore {
             let mut sequence_number = current_sequence_number;
             while let Some(txn) = txns.get_mut(&sequence_number) {
                 self.priority_index.insert(txn);
+                self.parking_lot_index.remove(txn);
 
                 if txn.timeline_state == TimelineState::NotReady {
                     self.timeline_index.insert(txn);
@@ -259,8 +372,12 @@ impl TransactionStore {
         }
     }
 
-    /// removes transaction from all indexes
+    /// removes transaction from all indexes and from the journal
     fn index_remove(&mut self, txn: &MempoolTransaction) {
+        if self.journal.is_some() {
+            self.journal_updates
+                .insert((txn.get_sender(), txn.get_sequence_number()), None);
+        }
         self.system_ttl_index.remove(&txn);
         self.expiration_time_index.remove(&txn);
         self.priority_index.remove(&txn);
@@ -356,7 +473,71 @@ impl TransactionStore {
         self.track_indices();
     }
 
-    pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
-        self.priority_index.iter()
+    /// iterates over ready transactions in the order given by `ordering`
+    pub(crate) fn iter_queue(
+        &self,
+        ordering: TransactionOrdering,
+    ) -> Box<dyn Iterator<Item = TxnPointer> + '_> {
+        match ordering {
+            TransactionOrdering::GasPrice => {
+                Box::new(self.priority_index.iter().map(TxnPointer::from))
+            }
+            TransactionOrdering::Fifo => Box::new(self.priority_index.iter_by_arrival()),
+            TransactionOrdering::RoundRobin => Box::new(self.priority_index.iter_round_robin()),
+        }
+    }
+
+    /// returns all transactions in the journal along with their timeline state, so that they can be
+    /// validated again before they're inserted back
+    /// they stay in the journal until inserting overwrites them or `forget_journaled` drops them
+    pub(crate) fn journaled_transactions(&self) -> Result<Vec<(SignedTransaction, TimelineState)>> {
+        let persisted = match &self.journal {
+            Some(journal) => journal.get_transactions()?,
+            None => vec![],
+        };
+        Ok(persisted
+            .into_iter()
+            .map(|persisted| {
+                let timeline_state = if persisted.broadcast_qualified {
+                    TimelineState::NotReady
+                } else {
+                    TimelineState::NonQualified
+                };
+                (persisted.transaction, timeline_state)
+            })
+            .collect())
+    }
+
+    /// removes the journaled transactions among `txns` that didn't make it back into mempool
+    pub(crate) fn forget_journaled(&mut self, txns: Vec<TxnPointer>) {
+        if self.journal.is_none() {
+            return;
+        }
+        for (address, sequence_number) in txns {
+            if self.get(&address, sequence_number).is_none() {
+                self.journal_updates
+                    .insert((address, sequence_number), None);
+            }
+        }
     }
+
+    /// writes the journal changes made since the last flush in a single batch
+    pub(crate) fn flush_journal(&mut self) {
+        if let Some(journal) = &self.journal {
+            if self.journal_updates.is_empty() {
+                return;
+            }
+            if let Err(e) = journal.write_updates(&self.journal_updates) {
+                error!("[Mempool] failed to update journal: {:?}", e);
+            }
+            self.journal_updates.clear();
+        }
+    }
+}
+
+/// lowest gas price that replaces a transaction paying `gas_price`:
+/// it's bumped by `bump_percent`, and by at least one
+fn replacement_gas_price(gas_price: u64, bump_percent: u64) -> u64 {
+    let bump = gas_price.saturating_mul(bump_percent) / 100;
+    gas_price.saturating_add(max(bump, 1))
 }
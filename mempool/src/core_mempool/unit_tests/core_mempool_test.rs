@@ -4,11 +4,11 @@
 use crate::core_mempool::{
     unit_tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
-    CoreMempool, TimelineState,
+    CoreMempool, MempoolSize, PendingTransactionStatus, TimelineState,
 };
-use libra_config::config::NodeConfig;
+use libra_config::config::{NodeConfig, TransactionOrdering};
 use libra_types::transaction::SignedTransaction;
 use std::{collections::HashSet, time::Duration};
 
@@ -62,6 +62,67 @@ fn test_transaction_ordering() {
     }
 }
 
+#[test]
+fn test_fifo_ordering() {
+    let mut config = NodeConfig::random();
+    config.mempool.transaction_ordering = TransactionOrdering::Fifo;
+    let mut mempool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+
+    let transactions = add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(1, 0, 5),
+            TestTransaction::new(0, 1, 10),
+        ],
+    );
+    // order of arrival, regardless of gas price
+    assert_eq!(consensus.get_block(&mut mempool, 3), transactions);
+}
+
+#[test]
+fn test_round_robin_ordering() {
+    let mut config = NodeConfig::random();
+    config.mempool.transaction_ordering = TransactionOrdering::RoundRobin;
+    let mut mempool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+
+    let transactions = add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(0, 0, 10),
+            TestTransaction::new(0, 1, 10),
+            TestTransaction::new(0, 2, 10),
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(1, 1, 1),
+        ],
+    );
+    // senders take turns, regardless of gas price
+    assert_eq!(
+        consensus.get_block(&mut mempool, 5),
+        vec![
+            transactions[0].clone(),
+            transactions[3].clone(),
+            transactions[1].clone(),
+            transactions[4].clone(),
+            transactions[2].clone(),
+        ]
+    );
+
+    // once a transaction commits, its sender's turn follows its oldest transaction left
+    mempool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    assert_eq!(
+        ConsensusMock::new().get_block(&mut mempool, 4),
+        vec![
+            transactions[1].clone(),
+            transactions[3].clone(),
+            transactions[2].clone(),
+            transactions[4].clone(),
+        ]
+    );
+}
+
 #[test]
 fn test_metric_cache_add_local_txns() {
     let (mut mempool, _) = setup_mempool();
@@ -112,6 +173,31 @@ fn test_update_invalid_transaction_in_mempool() {
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_replace_by_fee() {
+    let mut config = NodeConfig::random();
+    config.mempool.gas_price_bump_percent = 10;
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = setup_mempool().1;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 100),
+            TestTransaction::new(1, 0, 105),
+        ],
+    );
+
+    // the increase has to be at least 10%
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 0, 109)).is_err());
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 0, 110)).is_ok());
+
+    // the replacement takes the place of the original transaction
+    let block = consensus.get_block(&mut pool, 2);
+    let gas_prices: Vec<_> = block.iter().map(|txn| txn.gas_unit_price()).collect();
+    assert_eq!(gas_prices, vec![110, 105]);
+    assert_eq!(pool.size().total, 2);
+}
+
 #[test]
 fn test_remove_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -283,6 +369,75 @@ fn test_parking_lot_eviction() {
     assert!(add_txn(&mut pool, TestTransaction::new(0, 2, 1)).is_err());
 }
 
+#[test]
+fn test_parking_lot_eviction_by_gas_price() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 5, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 5, 3)).unwrap();
+
+    // Mempool is full. A parked txn only evicts a cheaper parked one
+    add_txn(&mut pool, TestTransaction::new(1, 6, 2)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 7, 2)).is_err());
+
+    // while a ready txn evicts the cheapest parked one, whatever its gas price
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+
+    let sequence_numbers = |address| -> Vec<_> {
+        pool.get_account_transactions(&TestTransaction::get_address(address))
+            .iter()
+            .map(|txn| txn.transaction.sequence_number())
+            .collect()
+    };
+    assert_eq!(sequence_numbers(1), vec![0, 1]);
+    assert_eq!(sequence_numbers(0), vec![5]);
+}
+
+#[test]
+fn test_journal() {
+    let mut config = NodeConfig::random();
+    config.mempool.persist_transactions = true;
+    let txns = {
+        let mut pool = CoreMempool::new(&config);
+        let txns = add_txns_to_mempool(
+            &mut pool,
+            vec![
+                TestTransaction::new(1, 0, 1),
+                TestTransaction::new(1, 1, 1),
+                TestTransaction::new(0, 0, 1),
+            ],
+        );
+        // committed and rejected transactions leave the journal
+        pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+        pool.remove_transaction(&TestTransaction::get_address(0), 0, true);
+        pool.flush_journal();
+        txns
+    };
+
+    // after restart, the rest is replayed, and stays journaled until it's dealt with
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.size().total, 0);
+    let journaled: Vec<_> = pool
+        .journaled_transactions()
+        .unwrap()
+        .into_iter()
+        .map(|(txn, timeline_state)| {
+            assert_eq!(timeline_state, TimelineState::NotReady);
+            txn
+        })
+        .collect();
+    assert_eq!(journaled, vec![txns[1].clone()]);
+    assert_eq!(pool.journaled_transactions().unwrap().len(), 1);
+
+    // a replayed transaction that isn't added back is removed from the journal
+    pool.forget_journaled_transactions(vec![(TestTransaction::get_address(1), 1)]);
+    assert_eq!(pool.journaled_transactions().unwrap().len(), 1);
+    pool.flush_journal();
+    assert!(pool.journaled_transactions().unwrap().is_empty());
+}
+
 #[test]
 fn test_gc_ready_transaction() {
     let mut pool = setup_mempool().0;
@@ -328,3 +483,93 @@ fn test_clean_stuck_transactions() {
     assert_eq!(block.len(), 1);
     assert_eq!(block[0].sequence_number(), 10);
 }
+
+#[test]
+fn test_pending_transactions() {
+    let mut pool = setup_mempool().0;
+    let address = TestTransaction::get_address(1);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(1, 1, 1),
+            TestTransaction::new(1, 3, 1),
+            TestTransaction::new(1, 4, 1),
+            TestTransaction::new(0, 2, 1),
+        ],
+    );
+
+    // txns 0 and 1 are ready, while 3 and 4 wait for 2
+    let statuses: Vec<_> = pool
+        .get_account_transactions(&address)
+        .into_iter()
+        .map(|txn| (txn.transaction.sequence_number(), txn.status))
+        .collect();
+    let parked = PendingTransactionStatus::Parked {
+        missing_sequence_number: 2,
+    };
+    assert_eq!(
+        statuses,
+        vec![
+            (0, PendingTransactionStatus::Ready),
+            (1, PendingTransactionStatus::Ready),
+            (3, parked),
+            (4, parked),
+        ]
+    );
+    assert_eq!(
+        pool.get_pending_transaction(&address, 4).unwrap().status,
+        parked
+    );
+    assert!(pool.get_pending_transaction(&address, 2).is_none());
+    assert!(pool
+        .get_account_transactions(&TestTransaction::get_address(2))
+        .is_empty());
+
+    // account 0 is parked as well, since its sequence number is 0 on chain
+    assert_eq!(
+        pool.get_pending_transaction(&TestTransaction::get_address(0), 2)
+            .unwrap()
+            .status,
+        PendingTransactionStatus::Parked {
+            missing_sequence_number: 0,
+        }
+    );
+    assert_eq!(
+        pool.size(),
+        MempoolSize {
+            total: 5,
+            ready: 2,
+            parked: 3,
+        }
+    );
+
+    // filling the gap unparks the rest
+    add_txn(&mut pool, TestTransaction::new(1, 2, 1)).unwrap();
+    assert!(pool
+        .get_account_transactions(&address)
+        .iter()
+        .all(|txn| txn.status == PendingTransactionStatus::Ready));
+    assert_eq!(
+        pool.size(),
+        MempoolSize {
+            total: 6,
+            ready: 5,
+            parked: 1,
+        }
+    );
+
+    // every parked transaction waits for the lowest sequence number missing since the one on chain
+    let address = TestTransaction::get_address(3);
+    for sequence_number in &[2, 5] {
+        let txn = TestTransaction::new(3, *sequence_number, 1).make_signed_transaction();
+        pool.add_txn(txn, 0, 1, 1, TimelineState::NotReady);
+    }
+    let parked = PendingTransactionStatus::Parked {
+        missing_sequence_number: 1,
+    };
+    assert!(pool
+        .get_account_transactions(&address)
+        .iter()
+        .all(|txn| txn.status == parked));
+}
@@ -3,4 +3,5 @@
 
 mod common;
 mod core_mempool_test;
+mod peer_reputation_test;
 mod shared_mempool_test;
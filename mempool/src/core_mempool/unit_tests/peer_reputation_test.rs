@@ -0,0 +1,135 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    core_mempool::unit_tests::common::TestTransaction,
+    peer_reputation::{
+        count_long_expired, Admission, PeerReputations, BAN_SCORE, EXPIRATION_GRACE_PERIOD,
+        MAX_SCORE,
+    },
+    shared_mempool::SubmissionStatus,
+};
+use libra_config::config::MempoolConfig;
+use libra_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    vm_error::{StatusCode, VMStatus},
+    PeerId,
+};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+fn setup_reputations() -> PeerReputations {
+    let mut config = MempoolConfig::default();
+    config.shared_mempool_peer_quota = 10;
+    config.shared_mempool_peer_quota_window_ms = 1_000;
+    config.shared_mempool_peer_ban_secs = 60;
+    PeerReputations::new(&config)
+}
+
+fn accepted(count: usize) -> Vec<SubmissionStatus> {
+    vec![(MempoolStatus::new(MempoolStatusCode::Accepted), None); count]
+}
+
+fn rejected(code: StatusCode, count: usize) -> Vec<SubmissionStatus> {
+    vec![
+        (
+            MempoolStatus::new(MempoolStatusCode::VmError),
+            Some(VMStatus::new(code))
+        );
+        count
+    ]
+}
+
+#[test]
+fn test_peer_quota() {
+    let mut reputations = setup_reputations();
+    let peer = PeerId::random();
+    let now = Instant::now();
+
+    assert_eq!(reputations.admit(peer, 6, now), Admission::Admitted);
+    assert_eq!(reputations.admit(peer, 6, now), Admission::QuotaExceeded);
+    assert_eq!(reputations.admit(peer, 4, now), Admission::Admitted);
+    // quotas are tracked per peer
+    assert_eq!(
+        reputations.admit(PeerId::random(), 10, now),
+        Admission::Admitted
+    );
+    // quota is refilled in the next window
+    let later = now + Duration::from_secs(1);
+    assert_eq!(reputations.admit(peer, 10, later), Admission::Admitted);
+}
+
+#[test]
+fn test_peer_reputation_score() {
+    let mut reputations = setup_reputations();
+    let peer = PeerId::random();
+    let now = Instant::now();
+
+    assert!(!reputations.update(peer, &accepted(MAX_SCORE as usize + 10), 0, now));
+    assert_eq!(reputations.score(&peer), MAX_SCORE);
+
+    // rejections that depend on the state we validate against don't affect reputation
+    for code in &[
+        StatusCode::SEQUENCE_NUMBER_TOO_OLD,
+        StatusCode::TRANSACTION_EXPIRED,
+        StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+        StatusCode::INVALID_AUTH_KEY,
+        StatusCode::LOOKUP_FAILED,
+        StatusCode::MISSING_DEPENDENCY,
+        StatusCode::LINKER_ERROR,
+    ] {
+        assert!(!reputations.update(peer, &rejected(*code, 10), 0, now));
+    }
+    assert_eq!(reputations.score(&peer), MAX_SCORE);
+
+    // while badly signed or malformed transactions do
+    assert!(!reputations.update(peer, &rejected(StatusCode::MALFORMED, 1), 0, now));
+    assert_eq!(reputations.score(&peer), MAX_SCORE - 10);
+    assert!(!reputations.update(peer, &rejected(StatusCode::INVALID_SIGNATURE, 10), 0, now));
+    assert_eq!(reputations.score(&peer), MAX_SCORE - 110);
+
+    // peer with a negative score only gets half of the quota
+    assert_eq!(reputations.admit(peer, 6, now), Admission::QuotaExceeded);
+    assert_eq!(reputations.admit(peer, 5, now), Admission::Admitted);
+}
+
+#[test]
+fn test_peer_ban() {
+    let mut reputations = setup_reputations();
+    let peer = PeerId::random();
+    let now = Instant::now();
+
+    assert!(reputations.update(peer, &rejected(StatusCode::INVALID_SIGNATURE, 10), 0, now));
+    assert_eq!(reputations.score(&peer), BAN_SCORE);
+    assert_eq!(reputations.admit(peer, 1, now), Admission::Banned);
+    // peer is only reported once
+    assert!(!reputations.update(peer, &rejected(StatusCode::INVALID_SIGNATURE, 1), 0, now));
+
+    // once the ban expires, peer starts over
+    let later = now + Duration::from_secs(60);
+    assert_eq!(reputations.admit(peer, 1, later), Admission::Admitted);
+    assert_eq!(reputations.score(&peer), 0);
+}
+
+#[test]
+fn test_long_expired_penalty() {
+    let mut reputations = setup_reputations();
+    let peer = PeerId::random();
+    let now = Instant::now();
+    let arrival = UNIX_EPOCH + Duration::from_secs(10_000);
+
+    let txn = |expiration_time: Duration| {
+        TestTransaction::new(0, 0, 1).make_signed_transaction_with_expiration_time(expiration_time)
+    };
+    let transactions = vec![
+        // expired within the grace period, e.g. due to clock drift
+        txn(Duration::from_secs(10_000) - EXPIRATION_GRACE_PERIOD),
+        txn(Duration::from_secs(9_000) - EXPIRATION_GRACE_PERIOD),
+        txn(Duration::from_secs(1)),
+        // far future expiration doesn't overflow
+        txn(Duration::from_secs(u64::max_value())),
+    ];
+    assert_eq!(count_long_expired(&transactions, arrival), 2);
+
+    assert!(!reputations.update(peer, &rejected(StatusCode::TRANSACTION_EXPIRED, 2), 2, now));
+    assert_eq!(reputations.score(&peer), -10);
+}
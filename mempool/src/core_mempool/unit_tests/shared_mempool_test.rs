@@ -7,7 +7,7 @@ use crate::{
         CoreMempool, TimelineState,
     },
     mocks::MockSharedMempool,
-    network::{MempoolNetworkEvents, MempoolNetworkSender, MempoolSyncMsg},
+    network::{BroadcastStatus, MempoolNetworkEvents, MempoolNetworkSender, MempoolSyncMsg},
     shared_mempool::{
         start_shared_mempool, ConsensusRequest, SharedMempoolNotification, SyncEvent,
     },
@@ -21,7 +21,7 @@ use futures::{
     },
     executor::block_on,
     sink::SinkExt,
-    StreamExt,
+    FutureExt, StreamExt,
 };
 use libra_config::config::{NetworkConfig, NodeConfig, RoleType};
 use libra_types::{transaction::SignedTransaction, PeerId};
@@ -78,6 +78,7 @@ fn init_single_shared_mempool(smp: &mut SharedMempoolNetwork, peer_id: PeerId, c
     let (sender, subscriber) = unbounded();
     let (timer_sender, timer_receiver) = unbounded();
     let (_ac_endpoint_sender, ac_endpoint_receiver) = mpsc::channel(1_024);
+    let (_inspection_sender, inspection_receiver) = mpsc::channel(1_024);
     let network_handles = vec![(peer_id, network_sender, network_events)];
     let (_consensus_sender, consensus_events) = mpsc::channel(1_024);
     let (_state_sync_sender, state_sync_events) = mpsc::channel(1_024);
@@ -96,6 +97,7 @@ fn init_single_shared_mempool(smp: &mut SharedMempoolNetwork, peer_id: PeerId, c
         Arc::clone(&mempool),
         network_handles,
         ac_endpoint_receiver,
+        inspection_receiver,
         consensus_events,
         state_sync_events,
         reconfig_events_receiver,
@@ -175,9 +177,50 @@ impl SharedMempoolNetwork {
     }
 
     fn send_connection_event(&mut self, peer: &PeerId, notif: ConnectionStatusNotification) {
+        self.send_connection_event_with_announcement(peer, notif, true);
+    }
+
+    /// sends `notif` to `peer`. A new upstream peer is sent an announcement that `peer` understands
+    /// broadcast status responses, which is dropped unless `deliver_announcement`, as if the new
+    /// peer ran a version without it
+    fn send_connection_event_with_announcement(
+        &mut self,
+        peer: &PeerId,
+        notif: ConnectionStatusNotification,
+        deliver_announcement: bool,
+    ) {
+        let is_new_peer = match notif {
+            ConnectionStatusNotification::NewPeer(..) => true,
+            _ => false,
+        };
         let conn_notifs_tx = self.network_conn_event_notifs_txs.get_mut(peer).unwrap();
         conn_notifs_tx.push(*peer, notif).unwrap();
         self.wait_for_event(peer, SharedMempoolNotification::PeerStateChange);
+
+        if !is_new_peer {
+            return;
+        }
+        // the announcement is sent before the connection event is notified
+        let network_reqs_rx = self.network_reqs_rxs.get_mut(peer).unwrap();
+        let (recipient, msg) = match network_reqs_rx.next().now_or_never() {
+            Some(Some(PeerManagerRequest::SendMessage(recipient, msg))) => (recipient, msg),
+            None => return,
+            _ => panic!("peer {:?} sent unexpected request", peer),
+        };
+        match lcs::from_bytes(&msg.mdata).unwrap() {
+            MempoolSyncMsg::BroadcastStatusSupported => (),
+            _ => panic!("did not receive expected BroadcastStatusSupported"),
+        }
+        if deliver_announcement {
+            let receiver_network_notif_tx = self.network_notifs_txs.get_mut(&recipient).unwrap();
+            receiver_network_notif_tx
+                .push(
+                    (*peer, ProtocolId::MempoolDirectSend),
+                    PeerManagerNotification::RecvMessage(*peer, msg),
+                )
+                .unwrap();
+            self.wait_for_event(&recipient, SharedMempoolNotification::PeerStateChange);
+        }
     }
 
     fn wait_for_event(&mut self, peer_id: &PeerId, event: SharedMempoolNotification) {
@@ -187,8 +230,24 @@ impl SharedMempoolNetwork {
         }
     }
 
-    /// delivers next broadcast message from `peer`
+    /// delivers next broadcast message from `peer` and its ACK
     fn deliver_message(&mut self, peer: &PeerId) -> (Vec<SignedTransaction>, PeerId) {
+        let (transactions, peer_id) = self.deliver_request(peer);
+
+        // verify transaction was inserted into Mempool
+        let mempool = self.mempools.get(&peer_id).unwrap();
+        let block = mempool.lock().unwrap().get_block(100, HashSet::new());
+        for txn in transactions.iter() {
+            assert!(block.contains(txn));
+        }
+
+        // deliver ACK for this request
+        self.deliver_response(&peer_id, BroadcastStatus::Ack);
+        (transactions, peer_id)
+    }
+
+    /// delivers next broadcast message from `peer`, without its response
+    fn deliver_request(&mut self, peer: &PeerId) -> (Vec<SignedTransaction>, PeerId) {
         // emulate timer tick
         self.timers
             .get(peer)
@@ -216,16 +275,6 @@ impl SharedMempoolNetwork {
 
                 // await message delivery
                 self.wait_for_event(&peer_id, SharedMempoolNotification::NewTransactions);
-
-                // verify transaction was inserted into Mempool
-                let mempool = self.mempools.get(&peer_id).unwrap();
-                let block = mempool.lock().unwrap().get_block(100, HashSet::new());
-                for txn in transactions.iter() {
-                    assert!(block.contains(txn));
-                }
-
-                // deliver ACK for this request
-                self.deliver_response(&peer_id);
                 (transactions, peer_id)
             } else {
                 panic!("did not receive expected BroadcastTransactionsRequest");
@@ -235,14 +284,23 @@ impl SharedMempoolNetwork {
         }
     }
 
-    /// delivers broadcast ACK from `peer`
-    fn deliver_response(&mut self, peer: &PeerId) {
+    /// delivers broadcast response from `peer`, which is expected to have status `expected_status`
+    fn deliver_response(&mut self, peer: &PeerId, expected_status: BroadcastStatus) {
         let network_reqs_rx = self.network_reqs_rxs.get_mut(peer).unwrap();
         let network_req = block_on(network_reqs_rx.next()).unwrap();
 
         if let PeerManagerRequest::SendMessage(peer_id, msg) = network_req {
-            let sync_msg = lcs::from_bytes(&msg.mdata).unwrap();
-            if let MempoolSyncMsg::BroadcastTransactionsResponse(_start, _end) = sync_msg {
+            let status = match lcs::from_bytes(&msg.mdata).unwrap() {
+                MempoolSyncMsg::BroadcastTransactionsResponse(_start, _end) => {
+                    Some(BroadcastStatus::Ack)
+                }
+                MempoolSyncMsg::BroadcastTransactionsStatusResponse(_start, _end, status) => {
+                    Some(status)
+                }
+                _ => None,
+            };
+            if let Some(status) = status {
+                assert_eq!(status, expected_status);
                 // send it to peer
                 let receiver_network_notif_tx = self.network_notifs_txs.get_mut(&peer_id).unwrap();
                 receiver_network_notif_tx
@@ -253,7 +311,11 @@ impl SharedMempoolNetwork {
                     .unwrap();
 
                 // await ACK delivery
-                self.wait_for_event(&peer_id, SharedMempoolNotification::ACK);
+                let event = match status {
+                    BroadcastStatus::Ack => SharedMempoolNotification::ACK,
+                    _ => SharedMempoolNotification::NACK,
+                };
+                self.wait_for_event(&peer_id, event);
             } else {
                 panic!("did not receive expected broadcast ACK");
             }
@@ -300,6 +362,36 @@ fn test_basic_flow() {
     }
 }
 
+#[test]
+fn test_replay_journal() {
+    let mut config = NodeConfig::random();
+    config.mempool.persist_transactions = true;
+    let txns = vec![
+        TestTransaction::new(1, 0, 1).make_signed_transaction(),
+        TestTransaction::new(1, 1, 1).make_signed_transaction(),
+    ];
+    {
+        // mempool before restart
+        let mut mempool = CoreMempool::new(&config);
+        batch_add_signed_txn(&mut mempool, txns.clone()).unwrap();
+        mempool.flush_journal();
+    }
+
+    let mut smp = SharedMempoolNetwork::default();
+    let peer_id = PeerId::random();
+    init_single_shared_mempool(&mut smp, peer_id, config);
+    smp.wait_for_event(&peer_id, SharedMempoolNotification::NewTransactions);
+
+    let block = smp
+        .mempools
+        .get(&peer_id)
+        .unwrap()
+        .lock()
+        .unwrap()
+        .get_block(100, HashSet::new());
+    assert_eq!(block, txns);
+}
+
 #[test]
 fn test_metric_cache_ignore_shared_txns() {
     let (mut smp, peers) = SharedMempoolNetwork::bootstrap_validator_network(2, 1);
@@ -692,3 +784,105 @@ fn test_broadcast_ack_multiple_accounts_single_peer() {
         assert!(block.contains(&txn.make_signed_transaction_with_max_gas_amount(5)));
     }
 }
+
+#[test]
+fn test_broadcast_retry_on_full_mempool() {
+    let mut smp = SharedMempoolNetwork::default();
+
+    // validator without room for any transaction
+    let validator = PeerId::random();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 0;
+    init_single_shared_mempool(&mut smp, validator, config);
+
+    // full node that resends batches as soon as they are NACKed
+    let full_node = PeerId::random();
+    let mut fn_config = NodeConfig::random();
+    fn_config.base.role = RoleType::FullNode;
+    fn_config.mempool.shared_mempool_ack_timeout_ms = 0;
+    fn_config.state_sync.upstream_peers.upstream_peers = vec![validator];
+    init_single_shared_mempool(&mut smp, full_node, fn_config);
+
+    let all_txns = vec![TestTransaction::new(1, 0, 1), TestTransaction::new(1, 1, 1)];
+    smp.add_txns(&full_node, all_txns.clone());
+    smp.send_connection_event(
+        &full_node,
+        ConnectionStatusNotification::NewPeer(validator, Multiaddr::empty()),
+    );
+
+    // validator reports that it is full
+    let (transactions, recipient) = smp.deliver_request(&full_node);
+    assert_eq!(recipient, validator);
+    assert_eq!(transactions.len(), all_txns.len());
+    smp.deliver_response(&validator, BroadcastStatus::MempoolFull);
+
+    // full node keeps the NACKed transactions
+    let mempool = smp.mempools.get(&full_node).unwrap();
+    let block = mempool.lock().unwrap().get_block(100, HashSet::new());
+    assert_eq!(block.len(), all_txns.len());
+
+    // and sends them again
+    let (resent, recipient) = smp.deliver_request(&full_node);
+    assert_eq!(recipient, validator);
+    assert_eq!(resent, transactions);
+}
+
+#[test]
+fn test_broadcast_no_nack_to_previous_version() {
+    let mut smp = SharedMempoolNetwork::default();
+
+    // validator without room for any transaction
+    let validator = PeerId::random();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 0;
+    init_single_shared_mempool(&mut smp, validator, config);
+
+    // full node whose announcement doesn't get through, as if it ran a version without NACKs
+    let full_node = PeerId::random();
+    let mut fn_config = NodeConfig::random();
+    fn_config.base.role = RoleType::FullNode;
+    fn_config.state_sync.upstream_peers.upstream_peers = vec![validator];
+    init_single_shared_mempool(&mut smp, full_node, fn_config);
+
+    let all_txns = vec![TestTransaction::new(1, 0, 1), TestTransaction::new(1, 1, 1)];
+    smp.add_txns(&full_node, all_txns.clone());
+    smp.send_connection_event_with_announcement(
+        &full_node,
+        ConnectionStatusNotification::NewPeer(validator, Multiaddr::empty()),
+        false,
+    );
+
+    // validator drops the batch without any response
+    let (_transactions, recipient) = smp.deliver_request(&full_node);
+    assert_eq!(recipient, validator);
+    smp.wait_for_event(&validator, SharedMempoolNotification::BroadcastDropped);
+    let network_reqs_rx = smp.network_reqs_rxs.get_mut(&validator).unwrap();
+    assert!(network_reqs_rx.next().now_or_never().is_none());
+
+    // so full node keeps the transactions
+    let mempool = smp.mempools.get(&full_node).unwrap();
+    let block = mempool.lock().unwrap().get_block(100, HashSet::new());
+    assert_eq!(block.len(), all_txns.len());
+}
+
+#[test]
+fn test_broadcast_ack_wire_format() {
+    /// `MempoolSyncMsg` as peers without `BroadcastStatus` know it
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    enum PreviousMempoolSyncMsg {
+        BroadcastTransactionsRequest((u64, u64), Vec<SignedTransaction>),
+        BroadcastTransactionsResponse(u64, u64),
+    }
+
+    // ACKs are still understood by such peers
+    let ack = MempoolSyncMsg::broadcast_response(1, 2, BroadcastStatus::Ack);
+    let bytes = lcs::to_bytes(&ack).unwrap();
+    assert_eq!(
+        lcs::from_bytes::<PreviousMempoolSyncMsg>(&bytes).unwrap(),
+        PreviousMempoolSyncMsg::BroadcastTransactionsResponse(1, 2)
+    );
+    // while NACKs aren't, which is why they are only sent to peers that announce they understand them
+    let nack = MempoolSyncMsg::broadcast_response(1, 2, BroadcastStatus::Nack);
+    let bytes = lcs::to_bytes(&nack).unwrap();
+    assert!(lcs::from_bytes::<PreviousMempoolSyncMsg>(&bytes).is_err());
+}
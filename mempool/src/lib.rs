@@ -38,7 +38,9 @@
 //! to current for account). This queue is ordered by gas price so that if a client is willing to
 //! pay more (than other clients) per unit of execution, then they can enter consensus earlier. Note
 //! that although global ordering is maintained by gas price, for a single account, transactions are
-//! ordered by sequence number.
+//! ordered by sequence number. Deployments that prefer other priorities can set
+//! `transaction_ordering` to propose ready transactions in order of arrival, or to let senders
+//! take turns instead.
 //!
 //! All transactions that are not ready to be included in the next block are part of separate
 //! `ParkingLotIndex`. They will be moved to the ordered queue once some event unblocks them. For
@@ -48,13 +50,31 @@
 //! Such event “unblocks” local transaction and txn4 will be moved to OrderedQueue.
 //!
 //! Mempool only holds a limited number of transactions to prevent OOMing the system. Additionally
-//! there's a limit of number of transactions per account to prevent different abuses/attacks
+//! there's a limit of number of transactions per account to prevent different abuses/attacks.
+//! Once Mempool is full, the cheapest transaction in `ParkingLotIndex` is evicted to make room for
+//! a ready transaction, or for a non-ready one that pays a higher gas price.
+//!
+//! A client can speed up its pending transaction by resubmitting it with a higher gas price, which
+//! replaces the pending one if the price goes up by at least `gas_price_bump_percent`.
 //!
 //! Transactions in Mempool have two types of expirations: systemTTL and client-specified
 //! expiration. Once we hit either of those, the transaction is removed from Mempool. SystemTTL is
 //! checked periodically in the background, while the client-specified expiration is checked on
 //! every Consensus commit request. We use a separate system TTL to ensure that a transaction won't
 //! remain stuck in Mempool forever, even if Consensus doesn't make progress
+//!
+//! If `persist_transactions` is set, Mempool also journals its transactions on disk, with one
+//! write per batch of changes. After a restart, journaled transactions are validated again and
+//! added back to Mempool, and they stay in the journal until that's done
+//!
+//! Recipients of a broadcast respond with an ACK, or with a NACK if they couldn't take its
+//! transactions. Batches that are NACKed or not ACKed in time are sent again with exponential
+//! backoff, and peers that report a full Mempool receive no broadcasts until that backoff passes.
+//!
+//! Transactions broadcast by other peers are subject to a per-peer quota. Each peer also has a
+//! reputation score which drops when it sends transactions that are invalid whatever the state,
+//! like badly signed ones. Peers with a low score get a smaller quota, and are eventually banned
+//! for a while, which also disconnects them unless they're on the validator network
 
 #[macro_use]
 extern crate prometheus;
@@ -62,15 +82,18 @@ extern crate prometheus;
 /// This module provides mocks of shared mempool for tests.
 #[cfg(feature = "fuzzing")]
 pub mod mocks;
+pub use core_mempool::{MempoolSize, PendingTransaction, PendingTransactionStatus};
 pub use shared_mempool::{
     bootstrap, CommitNotification, CommitResponse, CommittedTransaction, ConsensusRequest,
-    ConsensusResponse, MempoolClientSender, SubmissionStatus, TransactionExclusion,
-    MEMPOOL_SUBSCRIBED_CONFIGS,
+    ConsensusResponse, MempoolClientSender, MempoolInspectionRequest, MempoolInspectionResponse,
+    MempoolInspectionSender, SubmissionStatus, TransactionExclusion, MEMPOOL_SUBSCRIBED_CONFIGS,
 };
 
 mod core_mempool;
 mod counters;
+mod mempooldb;
 pub mod network;
+mod peer_reputation;
 mod shared_mempool;
 
 // module op counters
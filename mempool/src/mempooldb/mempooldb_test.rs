@@ -0,0 +1,60 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use libra_temppath::TempPath;
+use libra_types::test_helpers::transaction_test_helpers::get_test_signed_txn;
+
+fn persisted_transaction(
+    address: AccountAddress,
+    sequence_number: u64,
+    broadcast_qualified: bool,
+) -> PersistedTransaction {
+    let key = Ed25519PrivateKey::generate_for_testing();
+    PersistedTransaction {
+        transaction: get_test_signed_txn(address, sequence_number, &key, key.public_key(), None),
+        broadcast_qualified,
+    }
+}
+
+fn write_updates(
+    db: &MempoolDB,
+    updates: Vec<((AccountAddress, u64), Option<PersistedTransaction>)>,
+) {
+    db.write_updates(updates.iter().map(|(key, transaction)| (key, transaction)))
+        .unwrap();
+}
+
+#[test]
+fn test_write_updates() {
+    let tmp_dir = TempPath::new();
+    let db = MempoolDB::new(&tmp_dir);
+    assert!(db.get_transactions().unwrap().is_empty());
+
+    let address = AccountAddress::random();
+    let first = persisted_transaction(address, 0, true);
+    let second = persisted_transaction(address, 1, false);
+    write_updates(
+        &db,
+        vec![
+            ((address, 0), Some(first.clone())),
+            ((address, 1), Some(second.clone())),
+            ((address, 2), Some(persisted_transaction(address, 2, true))),
+        ],
+    );
+    write_updates(&db, vec![((address, 2), None)]);
+    assert_eq!(
+        db.get_transactions().unwrap(),
+        vec![first.clone(), second.clone()]
+    );
+
+    // a replacement overwrites the recorded transaction, and reading leaves it in place
+    let replacement = persisted_transaction(address, 1, true);
+    write_updates(&db, vec![((address, 1), Some(replacement.clone()))]);
+    assert_eq!(
+        db.get_transactions().unwrap(),
+        vec![first.clone(), replacement.clone()]
+    );
+    assert_eq!(db.get_transactions().unwrap(), vec![first, replacement]);
+}
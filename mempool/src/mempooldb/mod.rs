@@ -0,0 +1,75 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Journal of transactions in mempool, so that they survive a restart of the node
+
+#[cfg(test)]
+mod mempooldb_test;
+mod schema;
+
+pub(crate) use schema::transaction::PersistedTransaction;
+
+use crate::mempooldb::schema::{transaction::TransactionSchema, TRANSACTION_CF_NAME};
+use anyhow::Result;
+use libra_logger::prelude::*;
+use libra_types::account_address::AccountAddress;
+use schemadb::{
+    ColumnFamilyOptions, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME,
+};
+use std::{path::Path, time::Instant};
+
+pub(crate) struct MempoolDB {
+    db: DB,
+}
+
+impl MempoolDB {
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        let cf_opts_map: ColumnFamilyOptionsMap = [
+            (
+                /* UNUSED CF = */ DEFAULT_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
+            (TRANSACTION_CF_NAME, ColumnFamilyOptions::default()),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let path = db_root_path.as_ref().join("mempooldb");
+        let instant = Instant::now();
+        let db =
+            DB::open(path.clone(), cf_opts_map).expect("MempoolDB open failed; unable to continue");
+
+        info!(
+            "Opened MempoolDB at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self { db }
+    }
+
+    /// Records a batch of changes in one write: `Some` transaction accepted by mempool replaces
+    /// the one with the same sender and sequence number, and `None` records that it left mempool
+    pub fn write_updates<'a, I>(&self, updates: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (&'a (AccountAddress, u64), &'a Option<PersistedTransaction>)>,
+    {
+        let mut batch = SchemaBatch::new();
+        for (key, transaction) in updates {
+            match transaction {
+                Some(transaction) => batch.put::<TransactionSchema>(key, transaction)?,
+                None => batch.delete::<TransactionSchema>(key)?,
+            }
+        }
+        self.db.write_schemas(batch)
+    }
+
+    /// Get all recorded transactions, ordered by sender and sequence number.
+    pub fn get_transactions(&self) -> Result<Vec<PersistedTransaction>> {
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first()?;
+        iter.map(|value| value.map(|(_key, transaction)| transaction))
+            .collect()
+    }
+}
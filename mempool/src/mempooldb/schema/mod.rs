@@ -0,0 +1,19 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+pub(crate) mod transaction;
+
+use anyhow::{ensure, Result};
+use schemadb::ColumnFamilyName;
+
+pub(super) const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
+
+fn ensure_slice_len_eq(data: &[u8], len: usize) -> Result<()> {
+    ensure!(
+        data.len() == len,
+        "Unexpected data len {}, expected {}.",
+        data.len(),
+        len,
+    );
+    Ok(())
+}
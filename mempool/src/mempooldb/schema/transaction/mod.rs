@@ -0,0 +1,73 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for transactions accepted by mempool.
+//!
+//! Serialized transaction identified by its sender and sequence number, along with whether it
+//! qualifies for broadcast to other peers.
+//! ```text
+//! |<-------key------->|<-------------value------------->|
+//! | address | seq_num | transaction | broadcast_qualified |
+//! ```
+
+use super::{ensure_slice_len_eq, TRANSACTION_CF_NAME};
+use anyhow::Result;
+use libra_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, mem::size_of};
+
+define_schema!(
+    TransactionSchema,
+    Key,
+    PersistedTransaction,
+    TRANSACTION_CF_NAME
+);
+
+type SeqNum = u64;
+type Key = (AccountAddress, SeqNum);
+
+/// Transaction in the mempool journal
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PersistedTransaction {
+    pub transaction: SignedTransaction,
+    /// false for transactions that came from upstream peers, which aren't broadcast again
+    pub broadcast_qualified: bool,
+}
+
+impl KeyCodec<TransactionSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, seq_num) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.extend_from_slice(&seq_num.to_be_bytes());
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let mut seq_num = [0u8; size_of::<SeqNum>()];
+        seq_num.copy_from_slice(&data[AccountAddress::LENGTH..]);
+
+        Ok((address, SeqNum::from_be_bytes(seq_num)))
+    }
+}
+
+impl ValueCodec<TransactionSchema> for PersistedTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(lcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(lcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,21 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use libra_types::test_helpers::transaction_test_helpers::get_test_signed_txn;
+use schemadb::schema::assert_encode_decode;
+
+#[test]
+fn test_encode_decode() {
+    let address = AccountAddress::random();
+    let key = Ed25519PrivateKey::generate_for_testing();
+    let transaction = get_test_signed_txn(address, 7, &key, key.public_key(), None);
+    assert_encode_decode::<TransactionSchema>(
+        &(address, 7),
+        &PersistedTransaction {
+            transaction,
+            broadcast_qualified: true,
+        },
+    );
+}
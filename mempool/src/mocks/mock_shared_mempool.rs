@@ -4,7 +4,7 @@ use crate::{
     core_mempool::{CoreMempool, TimelineState},
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{start_shared_mempool, SubmissionStatus},
-    CommitNotification, ConsensusRequest,
+    CommitNotification, ConsensusRequest, MempoolInspectionRequest,
 };
 use anyhow::{format_err, Result};
 use channel::{self, libra_channel, message_queues::QueueStyle};
@@ -33,6 +33,8 @@ pub struct MockSharedMempool {
     _runtime: Runtime,
     /// sender from admission control to shared mempool
     pub ac_client: mpsc::Sender<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+    /// sender from client endpoints inspecting shared mempool
+    pub inspection_sender: mpsc::Sender<MempoolInspectionRequest>,
     /// mempool
     pub mempool: Arc<Mutex<CoreMempool>>,
     /// sender from consensus to shared mempool
@@ -74,6 +76,7 @@ impl MockSharedMempool {
         let network_events = MempoolNetworkEvents::new(network_notifs_rx, conn_notifs_rx);
         let (sender, _subscriber) = unbounded();
         let (ac_client, client_events) = mpsc::channel(1_024);
+        let (inspection_sender, inspection_events) = mpsc::channel(1_024);
         let (consensus_sender, consensus_events) = mpsc::channel(1_024);
         let (state_sync_sender, state_sync_events) = match state_sync {
             None => {
@@ -92,6 +95,7 @@ impl MockSharedMempool {
             mempool.clone(),
             network_handles,
             client_events,
+            inspection_events,
             consensus_events,
             state_sync_events,
             reconfig_event_subscriber,
@@ -104,6 +108,7 @@ impl MockSharedMempool {
         Self {
             _runtime: runtime,
             ac_client,
+            inspection_sender,
             mempool,
             consensus_sender,
             state_sync_sender,
@@ -31,6 +31,41 @@ pub enum MempoolSyncMsg {
         u64, // first timeline ID of corresponding request for this response
         u64, // last timeline ID of corresponding request for this response
     ),
+    /// Response for the BroadcastTransactionsRequest reporting that its transactions weren't
+    /// processed. Only sent to peers that announced BroadcastStatusSupported: peers running a
+    /// version without it fail to decode it, so they get no response instead, and keep the
+    /// transactions in their mempool without resending them
+    BroadcastTransactionsStatusResponse(
+        u64,             // first timeline ID of corresponding request for this response
+        u64,             // last timeline ID of corresponding request for this response
+        BroadcastStatus, // why the request's transactions weren't processed
+    ),
+    /// Announces that the sender understands BroadcastTransactionsStatusResponse. Sent once to
+    /// each upstream peer when connecting to it, before broadcasting anything
+    BroadcastStatusSupported,
+}
+
+impl MempoolSyncMsg {
+    /// Response for a BroadcastTransactionsRequest with `status`. ACKs are sent as
+    /// BroadcastTransactionsResponse, which every peer understands
+    pub fn broadcast_response(start_id: u64, end_id: u64, status: BroadcastStatus) -> Self {
+        match status {
+            BroadcastStatus::Ack => MempoolSyncMsg::BroadcastTransactionsResponse(start_id, end_id),
+            _ => MempoolSyncMsg::BroadcastTransactionsStatusResponse(start_id, end_id, status),
+        }
+    }
+}
+
+/// Outcome of a BroadcastTransactionsRequest, as reported by its recipient
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum BroadcastStatus {
+    /// Transactions were processed
+    Ack,
+    /// Transactions were dropped, sender should resend them later
+    Nack,
+    /// Recipient's mempool is full, sender should resend the transactions later and hold off
+    /// further broadcasts until then
+    MempoolFull,
 }
 
 /// Protocol id for mempool direct-send calls
@@ -96,4 +131,9 @@ impl MempoolNetworkSender {
         let protocol = ProtocolId::MempoolDirectSend;
         self.inner.send_to(recipient, protocol, message)
     }
+
+    /// Disconnect from the given peer, e.g. because it misbehaved.
+    pub async fn disconnect_peer(&mut self, peer: PeerId) -> Result<(), NetworkError> {
+        self.inner.disconnect_peer(peer).await
+    }
 }
@@ -0,0 +1,210 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control for transactions broadcast to us by other peers.
+//!
+//! Every peer gets a quota of transactions it may broadcast to us per window, and a reputation
+//! score that grows with valid transactions and drops with ones that no honest peer would relay,
+//! such as badly signed or malformed ones, or ones that were already long expired when they
+//! arrived. Transactions rejected because of the current state, like an insufficient balance or a
+//! script calling a module that isn't published, aren't held against the peer, since it may see a
+//! different state. Peers with a negative score only get half of the quota, and peers whose
+//! score drops to `BAN_SCORE` are banned: their broadcasts are dropped until the ban expires, and
+//! they are disconnected unless they're on the validator network.
+
+use crate::shared_mempool::SubmissionStatus;
+use libra_config::config::MempoolConfig;
+use libra_types::{
+    mempool_status::MempoolStatusCode,
+    transaction::SignedTransaction,
+    vm_error::{StatusCode, StatusType, VMStatus},
+    PeerId,
+};
+use std::{
+    cmp::{max, min},
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Highest score a peer can build up
+pub(crate) const MAX_SCORE: i64 = 100;
+/// Score at which a peer gets banned
+pub(crate) const BAN_SCORE: i64 = -100;
+/// Reward for every transaction of a peer that made it into Mempool
+const VALID_TXN_REWARD: i64 = 1;
+/// Penalty for every transaction of a peer that's invalid whatever the state, see `is_peer_fault`
+const INVALID_TXN_PENALTY: i64 = 10;
+/// Penalty for every transaction of a peer that was long expired when it arrived
+const EXPIRED_TXN_PENALTY: i64 = 5;
+/// How far past its expiration time a transaction must be on arrival to be held against the peer.
+/// Leaves room for clock drift and for broadcasts delayed by a busy network
+pub(crate) const EXPIRATION_GRACE_PERIOD: Duration = Duration::from_secs(600);
+
+/// Verdict on a broadcast a peer wants us to process
+#[derive(Debug, PartialEq)]
+pub(crate) enum Admission {
+    Admitted,
+    QuotaExceeded,
+    Banned,
+}
+
+struct PeerReputation {
+    score: i64,
+    // start of the current quota window and number of transactions admitted in it
+    window_start: Instant,
+    window_count: usize,
+    banned_until: Option<Instant>,
+}
+
+impl PeerReputation {
+    fn new(now: Instant) -> Self {
+        Self {
+            score: 0,
+            window_start: now,
+            window_count: 0,
+            banned_until: None,
+        }
+    }
+}
+
+/// Reputation and quota usage of all peers that broadcast transactions to us
+pub(crate) struct PeerReputations {
+    peers: HashMap<PeerId, PeerReputation>,
+    quota: usize,
+    quota_window: Duration,
+    ban_duration: Duration,
+}
+
+impl PeerReputations {
+    pub(crate) fn new(config: &MempoolConfig) -> Self {
+        Self {
+            peers: HashMap::new(),
+            quota: config.shared_mempool_peer_quota,
+            quota_window: Duration::from_millis(config.shared_mempool_peer_quota_window_ms),
+            ban_duration: Duration::from_secs(config.shared_mempool_peer_ban_secs),
+        }
+    }
+
+    /// Decides whether a broadcast of `num_txns` transactions from `peer` is processed.
+    /// Admitted transactions count against the peer's quota for the current window
+    pub(crate) fn admit(&mut self, peer: PeerId, num_txns: usize, now: Instant) -> Admission {
+        let quota = self.quota;
+        let quota_window = self.quota_window;
+        let reputation = self
+            .peers
+            .entry(peer)
+            .or_insert_with(|| PeerReputation::new(now));
+
+        if let Some(banned_until) = reputation.banned_until {
+            if now < banned_until {
+                return Admission::Banned;
+            }
+            // ban expired: peer starts over with a neutral score
+            *reputation = PeerReputation::new(now);
+        }
+
+        if now.duration_since(reputation.window_start) >= quota_window {
+            reputation.window_start = now;
+            reputation.window_count = 0;
+        }
+        // misbehaving peers are deprioritized by only getting half of the quota
+        let quota = if reputation.score < 0 {
+            quota / 2
+        } else {
+            quota
+        };
+        if reputation.window_count + num_txns > quota {
+            return Admission::QuotaExceeded;
+        }
+        reputation.window_count += num_txns;
+        Admission::Admitted
+    }
+
+    /// Updates reputation of `peer` based on the results of processing its broadcast, of which
+    /// `num_long_expired` transactions were long expired on arrival, see `count_long_expired`.
+    /// Returns true if this made the peer banned
+    pub(crate) fn update(
+        &mut self,
+        peer: PeerId,
+        results: &[SubmissionStatus],
+        num_long_expired: usize,
+        now: Instant,
+    ) -> bool {
+        let reputation = self
+            .peers
+            .entry(peer)
+            .or_insert_with(|| PeerReputation::new(now));
+        if reputation.banned_until.is_some() {
+            return false;
+        }
+
+        for (mempool_status, vm_status) in results {
+            let delta = match vm_status {
+                Some(vm_status) if is_peer_fault(vm_status) => -INVALID_TXN_PENALTY,
+                Some(_) => 0,
+                None if mempool_status.code == MempoolStatusCode::Accepted => VALID_TXN_REWARD,
+                None => 0,
+            };
+            reputation.score = min(max(reputation.score + delta, BAN_SCORE), MAX_SCORE);
+        }
+        let expired_penalty = (num_long_expired as i64).saturating_mul(EXPIRED_TXN_PENALTY);
+        reputation.score = max(reputation.score.saturating_sub(expired_penalty), BAN_SCORE);
+
+        if reputation.score <= BAN_SCORE {
+            reputation.banned_until = Some(now + self.ban_duration);
+            return true;
+        }
+        false
+    }
+
+    /// Returns current reputation score of `peer`
+    #[cfg(test)]
+    pub(crate) fn score(&self, peer: &PeerId) -> i64 {
+        self.peers
+            .get(peer)
+            .map_or(0, |reputation| reputation.score)
+    }
+}
+
+/// Number of `transactions` that were past their expiration time by more than
+/// `EXPIRATION_GRACE_PERIOD` when they arrived at `now`. Unlike a `TRANSACTION_EXPIRED` rejection,
+/// which compares against the time of the latest block we have, this doesn't depend on our state
+pub(crate) fn count_long_expired(transactions: &[SignedTransaction], now: SystemTime) -> usize {
+    let now = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    transactions
+        .iter()
+        .filter(|txn| {
+            txn.expiration_time()
+                .checked_add(EXPIRATION_GRACE_PERIOD)
+                .map_or(false, |deadline| deadline < now)
+        })
+        .count()
+}
+
+/// Whether a transaction failing validation with `status` is invalid regardless of the state it's
+/// validated against, so that a peer relaying it must be faulty. Most verification errors are
+/// left out on purpose: ones like `LOOKUP_FAILED`, `MISSING_DEPENDENCY` or `LINKER_ERROR` depend on
+/// the modules published on chain, which a peer may see differently
+fn is_peer_fault(status: &VMStatus) -> bool {
+    match status.status_type() {
+        StatusType::Deserialization => true,
+        _ => match status.major_status {
+            // signature and size checks only look at the transaction itself
+            StatusCode::INVALID_SIGNATURE | StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE => true,
+            // structural checks that the bytecode verifier runs on the script or module alone
+            StatusCode::INDEX_OUT_OF_BOUNDS
+            | StatusCode::RANGE_OUT_OF_BOUNDS
+            | StatusCode::INVALID_SIGNATURE_TOKEN
+            | StatusCode::INVALID_FIELD_DEF
+            | StatusCode::RECURSIVE_STRUCT_DEFINITION
+            | StatusCode::INVALID_RESOURCE_FIELD
+            | StatusCode::INVALID_FALL_THROUGH
+            | StatusCode::NEGATIVE_STACK_SIZE_WITHIN_BLOCK
+            | StatusCode::UNBALANCED_STACK
+            | StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE
+            | StatusCode::DUPLICATE_ELEMENT
+            | StatusCode::INVALID_MODULE_HANDLE => true,
+            _ => false,
+        },
+    }
+}
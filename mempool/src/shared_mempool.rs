@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{CoreMempool, MempoolSize, PendingTransaction, TimelineState, TxnPointer},
     counters,
-    network::{MempoolNetworkEvents, MempoolNetworkSender, MempoolSyncMsg},
+    network::{BroadcastStatus, MempoolNetworkEvents, MempoolNetworkSender, MempoolSyncMsg},
+    peer_reputation::{count_long_expired, Admission, PeerReputations},
 };
 use anyhow::{format_err, Result};
 use bounded_executor::BoundedExecutor;
@@ -36,11 +37,11 @@ use libra_types::{
 use network::protocols::network::Event;
 use std::{
     cmp,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::Deref,
     pin::Pin,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant, SystemTime},
 };
 use storage_client::{StorageRead, StorageReadServiceClient, SyncStorageClient};
 use tokio::{
@@ -54,11 +55,59 @@ use vm_validator::vm_validator::{get_account_sequence_number, TransactionValidat
 /// `timeline_id` is position in log of ready transactions
 /// `is_alive` - is connection healthy
 /// `network_id` - ID of the mempool network that this peer belongs to
+/// `broadcast_info` - broadcasts to this peer that weren't ACKed yet
 #[derive(Clone)]
 struct PeerSyncState {
     timeline_id: u64,
     is_alive: bool,
     network_id: PeerId,
+    broadcast_info: BroadcastInfo,
+}
+
+/// Broadcasts to a peer that await an ACK
+#[derive(Clone, Default)]
+struct BroadcastInfo {
+    // timeline range of the broadcast batch -> resend state of that batch
+    pending: BTreeMap<(u64, u64), PendingBroadcast>,
+    // set when peer reported a full mempool: no broadcasts to it until then
+    paused_until: Option<Instant>,
+}
+
+#[derive(Clone)]
+struct PendingBroadcast {
+    // time at which the batch is sent again, unless it gets ACKed before
+    retry_at: Instant,
+    // number of times the batch was sent
+    attempts: u32,
+}
+
+/// Change to the broadcast state of a peer, collected while syncing with peers
+enum BroadcastUpdate {
+    Sent((u64, u64), PendingBroadcast),
+    Drop((u64, u64)),
+}
+
+/// Timing of broadcast resends
+#[derive(Clone, Copy)]
+struct BroadcastBackoff {
+    ack_timeout: Duration,
+    max_backoff: Duration,
+}
+
+impl BroadcastBackoff {
+    fn new(config: &MempoolConfig) -> Self {
+        Self {
+            ack_timeout: Duration::from_millis(config.shared_mempool_ack_timeout_ms),
+            max_backoff: Duration::from_millis(config.shared_mempool_max_backoff_ms),
+        }
+    }
+
+    /// delay before resending a batch that was sent `attempts` times,
+    /// doubles with every attempt up to `max_backoff`
+    fn delay(&self, attempts: u32) -> Duration {
+        let exponent = cmp::min(attempts.saturating_sub(1), 16);
+        cmp::min(self.ack_timeout * 2u32.pow(exponent), self.max_backoff)
+    }
 }
 
 /// stores only peers that receive txns from this node
@@ -77,12 +126,17 @@ pub type SubmissionStatus = (MempoolStatus, Option<VMStatus>);
 pub type MempoolClientSender =
     mpsc::Sender<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>;
 
+/// sender type: used by client endpoints to inspect content of shared mempool
+pub type MempoolInspectionSender = mpsc::Sender<MempoolInspectionRequest>;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum SharedMempoolNotification {
     Sync,
     PeerStateChange,
     NewTransactions,
     ACK,
+    NACK,
+    BroadcastDropped,
 }
 
 /// Struct that owns all dependencies required by shared mempool routines
@@ -97,6 +151,9 @@ where
     storage_read_client: Arc<dyn StorageRead>,
     validator: Arc<RwLock<V>>,
     peer_info: Arc<Mutex<PeerInfo>>,
+    peer_reputations: Arc<Mutex<PeerReputations>>,
+    /// connected peers that announced they understand BroadcastTransactionsStatusResponse
+    broadcast_status_peers: Arc<Mutex<HashSet<PeerId>>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
 }
 
@@ -131,6 +188,42 @@ pub enum ConsensusResponse {
     CommitResponse(),
 }
 
+/// Message sent from client endpoints to mempool to inspect its content
+pub enum MempoolInspectionRequest {
+    /// request to fetch all transactions of an account that are currently in mempool
+    GetAccountTransactions(
+        // sender of transactions
+        AccountAddress,
+        // callback to send response back to sender
+        oneshot::Sender<Result<MempoolInspectionResponse>>,
+    ),
+    /// request to fetch single transaction along with the reason it's parked, if it is
+    GetTransaction(
+        // sender of transaction
+        AccountAddress,
+        // sequence number of transaction
+        u64,
+        // callback to send response back to sender
+        oneshot::Sender<Result<MempoolInspectionResponse>>,
+    ),
+    /// request to fetch number of transactions in mempool
+    GetSize(
+        // callback to send response back to sender
+        oneshot::Sender<Result<MempoolInspectionResponse>>,
+    ),
+}
+
+/// Response sent from mempool to client endpoints
+#[derive(Debug)]
+pub enum MempoolInspectionResponse {
+    /// transactions of requested account, ordered by sequence number
+    AccountTransactions(Vec<PendingTransaction>),
+    /// requested transaction, if it's in mempool
+    Transaction(Option<PendingTransaction>),
+    /// number of transactions in mempool
+    Size(MempoolSize),
+}
+
 /// notification from state sync to mempool of commit event
 /// This notifies mempool to remove committed txns
 pub struct CommitNotification {
@@ -192,6 +285,7 @@ fn new_peer(peer_info: &Mutex<PeerInfo>, peer_id: PeerId, network_id: PeerId) {
             timeline_id: 0,
             is_alive: true,
             network_id,
+            broadcast_info: BroadcastInfo::default(),
         })
         .is_alive = true;
 }
@@ -207,6 +301,18 @@ fn lost_peer(peer_info: &Mutex<PeerInfo>, peer_id: PeerId) {
     }
 }
 
+/// whether `peer_id` announced it understands BroadcastTransactionsStatusResponse. Other peers
+/// get no response to broadcasts that weren't processed
+fn supports_broadcast_status<V>(smp: &SharedMempool<V>, peer_id: PeerId) -> bool
+where
+    V: TransactionValidation,
+{
+    smp.broadcast_status_peers
+        .lock()
+        .expect("[shared mempool] failed to acquire broadcast_status_peers lock")
+        .contains(&peer_id)
+}
+
 fn send_mempool_sync_msg(
     msg: MempoolSyncMsg,
     recipient: PeerId,
@@ -223,12 +329,13 @@ fn send_mempool_sync_msg(
 }
 
 /// sync routine
-/// used to periodically broadcast ready to go transactions to peers
+/// used to periodically broadcast ready to go transactions to peers.
+/// Batches that weren't ACKed in time are sent again before any new batch
 async fn sync_with_peers<'a>(
     peer_info: &'a Mutex<PeerInfo>,
     mempool: &'a Mutex<CoreMempool>,
     mut network_senders: HashMap<PeerId, MempoolNetworkSender>,
-    batch_size: usize,
+    config: &'a MempoolConfig,
 ) {
     // Clone the underlying peer_info map and use this to sync and collect
     // state updates. We do this instead of holding the lock for the whole
@@ -238,40 +345,76 @@ async fn sync_with_peers<'a>(
         .expect("[shared mempool] failed to acquire peer_info lock")
         .deref()
         .clone();
+    let backoff = BroadcastBackoff::new(config);
+    let now = Instant::now();
     let mut state_updates = vec![];
 
     for (peer_id, peer_state) in peer_info_copy.into_iter() {
-        if peer_state.is_alive {
-            let timeline_id = peer_state.timeline_id;
-            let (transactions, new_timeline_id) = mempool
-                .lock()
-                .expect("[shared mempool] failed to acquire mempool lock")
-                .read_timeline(timeline_id, batch_size);
-
-            if !transactions.is_empty() {
-                counters::SHARED_MEMPOOL_TRANSACTION_BROADCAST.inc_by(transactions.len() as i64);
-
-                let network_sender = network_senders
-                    .get_mut(&peer_state.network_id)
-                    .expect("[shared mempool] missign network sender")
-                    .clone();
-                if let Err(e) = send_mempool_sync_msg(
-                    MempoolSyncMsg::BroadcastTransactionsRequest(
-                        (timeline_id, new_timeline_id),
-                        transactions,
-                    ),
-                    peer_id,
-                    network_sender,
-                ) {
-                    error!(
-                        "[shared mempool] error broadcasting transations to peer {}: {}",
-                        peer_id, e
-                    );
-                } else {
-                    // only update state for successful sends
-                    state_updates.push((peer_id, new_timeline_id));
-                }
+        if !peer_state.is_alive {
+            continue;
+        }
+        let broadcast_info = peer_state.broadcast_info;
+        if let Some(paused_until) = broadcast_info.paused_until {
+            if now < paused_until {
+                continue;
+            }
+        }
+
+        // resend the oldest batch that is due, or else broadcast a new one
+        let due_batch = broadcast_info
+            .pending
+            .iter()
+            .find(|(_, pending)| pending.retry_at <= now)
+            .map(|(range, pending)| (*range, pending.attempts));
+        let (range, attempts, transactions) = match due_batch {
+            Some(((start_id, end_id), attempts)) => {
+                let transactions = mempool
+                    .lock()
+                    .expect("[shared mempool] failed to acquire mempool lock")
+                    .timeline_range(start_id, end_id);
+                ((start_id, end_id), attempts, transactions)
+            }
+            None if broadcast_info.pending.len() < config.shared_mempool_max_pending_broadcasts => {
+                let timeline_id = peer_state.timeline_id;
+                let (transactions, new_timeline_id) = mempool
+                    .lock()
+                    .expect("[shared mempool] failed to acquire mempool lock")
+                    .read_timeline(timeline_id, config.shared_mempool_batch_size);
+                ((timeline_id, new_timeline_id), 0, transactions)
+            }
+            None => continue,
+        };
+
+        if transactions.is_empty() {
+            if attempts > 0 {
+                // every transaction of the batch has left mempool in the meantime
+                state_updates.push((peer_id, BroadcastUpdate::Drop(range)));
             }
+            continue;
+        }
+
+        counters::SHARED_MEMPOOL_TRANSACTION_BROADCAST.inc_by(transactions.len() as i64);
+        let network_sender = network_senders
+            .get_mut(&peer_state.network_id)
+            .expect("[shared mempool] missign network sender")
+            .clone();
+        if let Err(e) = send_mempool_sync_msg(
+            MempoolSyncMsg::BroadcastTransactionsRequest(range, transactions),
+            peer_id,
+            network_sender,
+        ) {
+            error!(
+                "[shared mempool] error broadcasting transations to peer {}: {}",
+                peer_id, e
+            );
+        } else {
+            // only update state for successful sends
+            let attempts = attempts + 1;
+            let pending = PendingBroadcast {
+                retry_at: now + backoff.delay(attempts),
+                attempts,
+            };
+            state_updates.push((peer_id, BroadcastUpdate::Sent(range, pending)));
         }
     }
 
@@ -279,9 +422,15 @@ async fn sync_with_peers<'a>(
     let mut peer_info = peer_info
         .lock()
         .expect("[shared mempool] failed to acquire peer_info lock");
-    for (peer_id, new_timeline_id) in state_updates {
-        peer_info.entry(peer_id).and_modify(|t| {
-            t.timeline_id = new_timeline_id;
+    for (peer_id, update) in state_updates {
+        peer_info.entry(peer_id).and_modify(|t| match update {
+            BroadcastUpdate::Sent(range, pending) => {
+                t.timeline_id = cmp::max(t.timeline_id, range.1);
+                t.broadcast_info.pending.insert(range, pending);
+            }
+            BroadcastUpdate::Drop(range) => {
+                t.broadcast_info.pending.remove(&range);
+            }
         });
     }
 }
@@ -373,6 +522,7 @@ where
                 }
             }
         }
+        mempool.flush_journal();
     }
     notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
     statuses
@@ -441,6 +591,7 @@ async fn process_transaction_broadcast<V>(
     timeline_state: TimelineState,
     peer_id: PeerId,
     network_id: PeerId,
+    is_validator_network: bool,
 ) where
     V: TransactionValidation,
 {
@@ -449,11 +600,51 @@ async fn process_transaction_broadcast<V>(
         .get_mut(&network_id)
         .expect("[shared mempool] missing network sender")
         .clone();
+    let peer_reputations = smp.peer_reputations.clone();
+    let status_supported = supports_broadcast_status(&smp, peer_id);
+    let subscribers = smp.subscribers.clone();
+    let num_long_expired = count_long_expired(&transactions, SystemTime::now());
     let results = process_incoming_transactions(smp, transactions, timeline_state).await;
+    let banned = peer_reputations
+        .lock()
+        .expect("[shared mempool] failed to acquire peer_reputations lock")
+        .update(peer_id, &results, num_long_expired, Instant::now());
+    // ask peer to resend the batch later if we had no room for some of its transactions
+    let status = if results
+        .iter()
+        .any(|(mempool_status, _)| mempool_status.code == MempoolStatusCode::MempoolIsFull)
+    {
+        BroadcastStatus::MempoolFull
+    } else {
+        BroadcastStatus::Ack
+    };
     log_txn_process_results(results, Some(peer_id));
+    if banned {
+        security_log(SecurityEvent::MisbehavingPeerMP)
+            .error("PeerBanned")
+            .data(&peer_id)
+            .log();
+        // a validator would be redialed right away, and losing the connection would also cut
+        // consensus and state sync off, so its broadcasts are only dropped until the ban expires
+        if !is_validator_network {
+            if let Err(e) = network_sender.clone().disconnect_peer(peer_id).await {
+                error!(
+                    "[shared mempool] failed to disconnect banned peer {}: {}",
+                    peer_id, e
+                );
+            }
+        }
+        // no ACK: a banned peer should not drop the transactions from its own mempool
+        return;
+    }
+    if status != BroadcastStatus::Ack && !status_supported {
+        // no ACK either, so the peer keeps the transactions we had no room for
+        notify_subscribers(SharedMempoolNotification::BroadcastDropped, &subscribers);
+        return;
+    }
     // send back ACK
     if let Err(e) = send_mempool_sync_msg(
-        MempoolSyncMsg::BroadcastTransactionsResponse(start_id, end_id),
+        MempoolSyncMsg::broadcast_response(start_id, end_id, status),
         peer_id,
         network_sender,
     ) {
@@ -464,11 +655,49 @@ async fn process_transaction_broadcast<V>(
     }
 }
 
-fn process_broadcast_ack<V>(smp: SharedMempool<V>, start_id: u64, end_id: u64, is_validator: bool)
-where
+/// handles response of `peer_id` to a broadcast.
+/// NACKed batches are resent after a backoff, and a peer with full mempool doesn't receive any
+/// broadcast until then
+fn process_broadcast_ack<V>(
+    smp: SharedMempool<V>,
+    peer_id: PeerId,
+    start_id: u64,
+    end_id: u64,
+    status: BroadcastStatus,
+    is_validator: bool,
+) where
     V: TransactionValidation,
 {
-    if is_validator {
+    if let Some(state) = smp
+        .peer_info
+        .lock()
+        .expect("[shared mempool] failed to acquire peer_info lock")
+        .get_mut(&peer_id)
+    {
+        let broadcast_info = &mut state.broadcast_info;
+        if status == BroadcastStatus::Ack {
+            broadcast_info.pending.remove(&(start_id, end_id));
+        } else {
+            let backoff = BroadcastBackoff::new(&smp.config);
+            let now = Instant::now();
+            let retry_at = match broadcast_info.pending.get_mut(&(start_id, end_id)) {
+                Some(pending) => {
+                    pending.retry_at = now + backoff.delay(pending.attempts);
+                    pending.retry_at
+                }
+                None => now + backoff.delay(1),
+            };
+            if status == BroadcastStatus::MempoolFull {
+                warn!(
+                    "[shared mempool] peer {} is full, pausing broadcasts to it",
+                    peer_id
+                );
+                broadcast_info.paused_until = Some(retry_at);
+            }
+        }
+    }
+
+    if is_validator || status != BroadcastStatus::Ack {
         return;
     }
     if start_id < end_id {
@@ -480,6 +709,7 @@ where
         for txn in mempool.timeline_range(start_id, end_id).iter() {
             mempool.remove_transaction(&txn.sender(), txn.sequence_number(), false);
         }
+        mempool.flush_journal();
     } else {
         warn!(
             "[shared mempool] ACK with invalid broadcast range {} to {}",
@@ -497,12 +727,12 @@ where
     let peer_info = smp.peer_info;
     let mempool = smp.mempool;
     let network_senders = smp.network_senders;
-    let batch_size = smp.config.shared_mempool_batch_size;
+    let config = smp.config;
     let subscribers = smp.subscribers;
 
     while let Some(sync_event) = interval.next().await {
         trace!("SyncEvent: {:?}", sync_event);
-        sync_with_peers(&peer_info, &mempool, network_senders.clone(), batch_size).await;
+        sync_with_peers(&peer_info, &mempool, network_senders.clone(), &config).await;
         notify_subscribers(SharedMempoolNotification::Sync, &subscribers);
     }
 
@@ -533,6 +763,7 @@ async fn commit_txns<V>(
     if block_timestamp_usecs > 0 {
         pool.gc_by_expiration_time(Duration::from_micros(block_timestamp_usecs));
     }
+    pool.flush_journal();
 }
 
 async fn process_state_sync_request<V>(smp: SharedMempool<V>, req: CommitNotification)
@@ -598,6 +829,38 @@ where
     }
 }
 
+async fn process_inspection_request<V>(smp: SharedMempool<V>, req: MempoolInspectionRequest)
+where
+    V: TransactionValidation,
+{
+    let (resp, callback) = {
+        let mut mempool = smp
+            .mempool
+            .lock()
+            .expect("[inspection] acquire mempool lock");
+        match req {
+            MempoolInspectionRequest::GetAccountTransactions(address, callback) => (
+                MempoolInspectionResponse::AccountTransactions(
+                    mempool.get_account_transactions(&address),
+                ),
+                callback,
+            ),
+            MempoolInspectionRequest::GetTransaction(address, sequence_number, callback) => (
+                MempoolInspectionResponse::Transaction(
+                    mempool.get_pending_transaction(&address, sequence_number),
+                ),
+                callback,
+            ),
+            MempoolInspectionRequest::GetSize(callback) => {
+                (MempoolInspectionResponse::Size(mempool.size()), callback)
+            }
+        }
+    };
+    if callback.send(Ok(resp)).is_err() {
+        error!("[shared mempool] failed to send back mempool inspection response");
+    }
+}
+
 async fn process_config_update<V>(config_update: OnChainConfigPayload, validator: Arc<RwLock<V>>)
 where
     V: TransactionValidation,
@@ -619,6 +882,7 @@ async fn inbound_network_task<V>(
         SignedTransaction,
         oneshot::Sender<Result<SubmissionStatus>>,
     )>,
+    mut inspection_requests: mpsc::Receiver<MempoolInspectionRequest>,
     mut consensus_requests: mpsc::Receiver<ConsensusRequest>,
     mut state_sync_requests: mpsc::Receiver<CommitNotification>,
     mut mempool_reconfig_events: libra_channel::Receiver<(), OnChainConfigPayload>,
@@ -634,6 +898,10 @@ async fn inbound_network_task<V>(
         .collect();
     let mut events = select_all(smp_events).fuse();
     let is_validator = node_config.base.role.is_validator();
+    let validator_network_id = node_config
+        .validator_network
+        .as_ref()
+        .map(|network| network.peer_id);
 
     // Use a BoundedExecutor to restrict only `workers_available` concurrent
     // worker tasks that can process incoming transactions.
@@ -652,6 +920,11 @@ async fn inbound_network_task<V>(
                 ))
                 .await;
             },
+            msg = inspection_requests.select_next_some() => {
+                bounded_executor
+                .spawn(process_inspection_request(smp.clone(), msg))
+                .await;
+            }
             msg = consensus_requests.select_next_some() => {
                 process_consensus_request(smp.clone(), msg).await;
             }
@@ -673,6 +946,19 @@ async fn inbound_network_task<V>(
                                     .inc();
                                 if node_config.is_upstream_peer(peer_id, network_id) {
                                     new_peer(&peer_info, peer_id, network_id);
+                                    // let the peer know it can NACK our broadcasts
+                                    let network_sender = smp
+                                        .network_senders
+                                        .get(&network_id)
+                                        .expect("[shared mempool] missing network sender")
+                                        .clone();
+                                    if let Err(e) = send_mempool_sync_msg(
+                                        MempoolSyncMsg::BroadcastStatusSupported,
+                                        peer_id,
+                                        network_sender,
+                                    ) {
+                                        error!("[shared mempool] failed to announce broadcast status support to peer {}: {}", peer_id, e);
+                                    }
                                 }
                                 notify_subscribers(SharedMempoolNotification::PeerStateChange, &subscribers);
                             }
@@ -683,6 +969,11 @@ async fn inbound_network_task<V>(
                                 if node_config.is_upstream_peer(peer_id, network_id) {
                                     lost_peer(&peer_info, peer_id);
                                 }
+                                // a reconnected peer announces itself again
+                                smp.broadcast_status_peers
+                                    .lock()
+                                    .expect("[shared mempool] failed to acquire broadcast_status_peers lock")
+                                    .remove(&peer_id);
                                 notify_subscribers(SharedMempoolNotification::PeerStateChange, &subscribers);
                             }
                             Event::Message((peer_id, msg)) => {
@@ -694,27 +985,71 @@ async fn inbound_network_task<V>(
                                         counters::SHARED_MEMPOOL_TRANSACTIONS_PROCESSED
                                             .with_label_values(&["received".to_string().deref(), peer_id.to_string().deref()])
                                             .inc_by(transactions.len() as i64);
-                                        let smp_clone = smp.clone();
-                                        let timeline_state = match node_config.is_upstream_peer(peer_id, network_id) {
-                                            true => TimelineState::NonQualified,
-                                            false => TimelineState::NotReady,
-                                        };
-                                        bounded_executor
-                                            .spawn(process_transaction_broadcast(
-                                                smp_clone,
-                                                transactions,
-                                                start_id,
-                                                end_id,
-                                                timeline_state,
-                                                peer_id,
-                                                network_id,
-                                            ))
-                                            .await;
+                                        let admission = smp
+                                            .peer_reputations
+                                            .lock()
+                                            .expect("[shared mempool] failed to acquire peer_reputations lock")
+                                            .admit(peer_id, transactions.len(), Instant::now());
+                                        if admission == Admission::Admitted {
+                                            let smp_clone = smp.clone();
+                                            let timeline_state = match node_config.is_upstream_peer(peer_id, network_id) {
+                                                true => TimelineState::NonQualified,
+                                                false => TimelineState::NotReady,
+                                            };
+                                            bounded_executor
+                                                .spawn(process_transaction_broadcast(
+                                                    smp_clone,
+                                                    transactions,
+                                                    start_id,
+                                                    end_id,
+                                                    timeline_state,
+                                                    peer_id,
+                                                    network_id,
+                                                    validator_network_id == Some(network_id),
+                                                ))
+                                                .await;
+                                        } else {
+                                            // dropped without ACK, so the peer keeps these transactions
+                                            counters::SHARED_MEMPOOL_TRANSACTIONS_PROCESSED
+                                                .with_label_values(&[format!("{:?}", admission).deref(), peer_id.to_string().deref()])
+                                                .inc_by(transactions.len() as i64);
+                                            if admission == Admission::QuotaExceeded && supports_broadcast_status(&smp, peer_id) {
+                                                // ask peer to resend these transactions once it has quota again
+                                                let network_sender = smp
+                                                    .network_senders
+                                                    .get(&network_id)
+                                                    .expect("[shared mempool] missing network sender")
+                                                    .clone();
+                                                if let Err(e) = send_mempool_sync_msg(
+                                                    MempoolSyncMsg::broadcast_response(start_id, end_id, BroadcastStatus::Nack),
+                                                    peer_id,
+                                                    network_sender,
+                                                ) {
+                                                    error!("[shared mempool] failed to send NACK back to peer {}: {}", peer_id, e);
+                                                }
+                                            }
+                                            notify_subscribers(SharedMempoolNotification::BroadcastDropped, &subscribers);
+                                        }
                                     }
                                     MempoolSyncMsg::BroadcastTransactionsResponse(start_id, end_id) => {
-                                        process_broadcast_ack(smp.clone(), start_id, end_id, is_validator);
+                                        process_broadcast_ack(smp.clone(), peer_id, start_id, end_id, BroadcastStatus::Ack, is_validator);
                                         notify_subscribers(SharedMempoolNotification::ACK, &smp.subscribers);
                                     }
+                                    MempoolSyncMsg::BroadcastTransactionsStatusResponse(start_id, end_id, status) => {
+                                        process_broadcast_ack(smp.clone(), peer_id, start_id, end_id, status, is_validator);
+                                        let notification = match status {
+                                            BroadcastStatus::Ack => SharedMempoolNotification::ACK,
+                                            _ => SharedMempoolNotification::NACK,
+                                        };
+                                        notify_subscribers(notification, &smp.subscribers);
+                                    }
+                                    MempoolSyncMsg::BroadcastStatusSupported => {
+                                        smp.broadcast_status_peers
+                                            .lock()
+                                            .expect("[shared mempool] failed to acquire broadcast_status_peers lock")
+                                            .insert(peer_id);
+                                        notify_subscribers(SharedMempoolNotification::PeerStateChange, &smp.subscribers);
+                                    }
                                 };
                             }
                             _ => {
@@ -739,14 +1074,74 @@ async fn inbound_network_task<V>(
     crit!("[shared mempool] inbound_network_task terminated");
 }
 
+/// Validates transactions journaled before the node restarted and adds them back to mempool
+async fn replay_journal<V>(smp: SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let journaled = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .journaled_transactions();
+    let transactions = match journaled {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            error!("[shared mempool] failed to read mempool journal: {:?}", e);
+            return;
+        }
+    };
+    if transactions.is_empty() {
+        return;
+    }
+
+    // timeline state applies to a whole batch of incoming transactions
+    let total = transactions.len();
+    let replayed: Vec<_> = transactions
+        .iter()
+        .map(|(txn, _)| (txn.sender(), txn.sequence_number()))
+        .collect();
+    let (qualified, non_qualified): (Vec<_>, Vec<_>) = transactions
+        .into_iter()
+        .partition(|(_, timeline_state)| *timeline_state == TimelineState::NotReady);
+    let mut accepted = 0;
+    for (timeline_state, transactions) in vec![
+        (TimelineState::NotReady, qualified),
+        (TimelineState::NonQualified, non_qualified),
+    ] {
+        if transactions.is_empty() {
+            continue;
+        }
+        let transactions = transactions.into_iter().map(|(txn, _)| txn).collect();
+        let statuses =
+            process_incoming_transactions(smp.clone(), transactions, timeline_state).await;
+        accepted += statuses
+            .iter()
+            .filter(|(status, _)| status.code == MempoolStatusCode::Accepted)
+            .count();
+    }
+    // accepted transactions overwrote their journal entries, the rest are gone for good
+    let mut mempool = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock");
+    mempool.forget_journaled_transactions(replayed);
+    mempool.flush_journal();
+    info!(
+        "[shared mempool] replayed {} journaled transactions, {} accepted",
+        total, accepted
+    );
+}
+
 /// GC all expired transactions by SystemTTL
 async fn gc_task(mempool: Arc<Mutex<CoreMempool>>, gc_interval_ms: u64) {
     let mut interval = interval(Duration::from_millis(gc_interval_ms));
     while let Some(_interval) = interval.next().await {
-        mempool
+        let mut mempool = mempool
             .lock()
-            .expect("[shared mempool] failed to acquire mempool lock")
-            .gc_by_system_ttl();
+            .expect("[shared mempool] failed to acquire mempool lock");
+        mempool.gc_by_system_ttl();
+        mempool.flush_journal();
     }
 
     crit!("SharedMempool gc_task terminated");
@@ -757,6 +1152,7 @@ async fn gc_task(mempool: Arc<Mutex<CoreMempool>>, gc_interval_ms: u64) {
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers)
 ///   - inbound_network_task (task that handles inbound mempool messages and network events)
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL)
+///   - replay_journal (task that revalidates transactions persisted before restart)
 pub(crate) fn start_shared_mempool<V>(
     executor: &Handle,
     config: &NodeConfig,
@@ -765,6 +1161,7 @@ pub(crate) fn start_shared_mempool<V>(
     // See `NodeConfig::is_upstream_peer` for the definition of network ID
     mempool_network_handles: Vec<(PeerId, MempoolNetworkSender, MempoolNetworkEvents)>,
     client_events: mpsc::Receiver<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+    inspection_requests: mpsc::Receiver<MempoolInspectionRequest>,
     consensus_requests: mpsc::Receiver<ConsensusRequest>,
     state_sync_requests: mpsc::Receiver<CommitNotification>,
     mempool_reconfig_events: libra_channel::Receiver<(), OnChainConfigPayload>,
@@ -792,9 +1189,13 @@ pub(crate) fn start_shared_mempool<V>(
         storage_read_client,
         validator,
         peer_info,
+        peer_reputations: Arc::new(Mutex::new(PeerReputations::new(&config.mempool))),
+        broadcast_status_peers: Arc::new(Mutex::new(HashSet::new())),
         subscribers,
     };
 
+    executor.spawn(replay_journal(smp.clone()));
+
     let interval_ms = config.mempool.shared_mempool_tick_interval_ms;
     let smp_outbound = smp.clone();
     let f = async move {
@@ -809,6 +1210,7 @@ pub(crate) fn start_shared_mempool<V>(
         executor.clone(),
         all_network_events,
         client_events,
+        inspection_requests,
         consensus_requests,
         state_sync_requests,
         mempool_reconfig_events,
@@ -828,6 +1230,7 @@ pub fn bootstrap(
     // See `NodeConfig::is_upstream_peer` for the definition of network ID
     mempool_network_handles: Vec<(PeerId, MempoolNetworkSender, MempoolNetworkEvents)>,
     client_events: Receiver<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+    inspection_requests: Receiver<MempoolInspectionRequest>,
     consensus_requests: Receiver<ConsensusRequest>,
     state_sync_requests: Receiver<CommitNotification>,
     mempool_reconfig_events: libra_channel::Receiver<(), OnChainConfigPayload>,
@@ -849,6 +1252,7 @@ pub fn bootstrap(
         mempool,
         mempool_network_handles,
         client_events,
+        inspection_requests,
         consensus_requests,
         state_sync_requests,
         mempool_reconfig_events,
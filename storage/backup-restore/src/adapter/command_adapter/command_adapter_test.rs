@@ -0,0 +1,74 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use futures::executor::block_on_stream;
+use proptest::{collection::vec, prelude::*};
+use std::path::Path;
+use tokio::runtime::Runtime;
+
+fn gzip_adapter(dir: &Path) -> CommandAdapter {
+    CommandAdapter::new(CommandAdapterConfig {
+        create_cmd: format!("mktemp {}/XXXXXXXX", dir.display()),
+        write_cmd: "gzip > \"$FILE_HANDLE\"".to_string(),
+        read_cmd: "gzip -dc \"$FILE_HANDLE\"".to_string(),
+    })
+}
+
+fn write_file(
+    adapter: &CommandAdapter,
+    content: impl StreamExt<Item = Vec<u8>> + Send,
+) -> Result<FileHandle> {
+    // The write command is spawned on the tokio runtime.
+    let mut rt = Runtime::new().unwrap();
+    rt.block_on(adapter.write_new_file(content))
+}
+
+fn read_all(adapter: &CommandAdapter, handle: &FileHandle) -> Result<Vec<u8>> {
+    let mut content = vec![];
+    for res in block_on_stream(adapter.read_file_content(handle)) {
+        content.extend_from_slice(&res?);
+    }
+    Ok(content)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_command_adapter(contents in vec(vec(any::<u8>(), 1..1000), 1..10)) {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let adapter = gzip_adapter(tmpdir.path());
+
+        let file_handles: Vec<_> = contents.iter().map(|content| {
+            let iter = content.chunks(10).map(|c| c.to_vec());
+            let stream = futures::stream::iter(iter);
+            write_file(&adapter, stream).unwrap()
+        }).collect();
+
+        for (handle, expected_content) in itertools::zip_eq(file_handles, contents) {
+            prop_assert!(handle.starts_with(tmpdir.path().to_str().unwrap()));
+            prop_assert_eq!(read_all(&adapter, &handle).unwrap(), expected_content);
+        }
+    }
+}
+
+#[test]
+fn test_command_adapter_failures() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let adapter = gzip_adapter(tmpdir.path());
+
+    // Reading a file that doesn't exist.
+    assert!(read_all(&adapter, &"/nonexistent".to_string()).is_err());
+
+    let adapter = CommandAdapter::new(CommandAdapterConfig {
+        create_cmd: "true".to_string(),
+        write_cmd: "cat > /dev/null".to_string(),
+        read_cmd: "false".to_string(),
+    });
+    // `create_cmd` not printing a handle.
+    let stream = futures::stream::once(async { vec![1u8] });
+    assert!(write_file(&adapter, stream).is_err());
+    // `read_cmd` failing after printing nothing.
+    assert!(read_all(&adapter, &"handle".to_string()).is_err());
+}
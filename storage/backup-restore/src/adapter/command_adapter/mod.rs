@@ -0,0 +1,194 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod command_adapter_test;
+
+use super::Adapter;
+use crate::FileHandle;
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Read,
+    process::{Child, Command, Stdio},
+};
+use tokio::io::AsyncWriteExt;
+
+/// The environment variable through which the file handle is passed to the write and read
+/// commands.
+const FILE_HANDLE_ENV_VAR: &str = "FILE_HANDLE";
+
+const READ_CHUNK_SIZE: usize = 1 << 20;
+
+/// Shell commands used by [`CommandAdapter`]. Each of them is run with `sh -c`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CommandAdapterConfig {
+    /// Creates a new file and prints its handle to stdout, e.g. `mktemp /backup/XXXXXXXX`.
+    pub create_cmd: String,
+    /// Reads the content of the file from stdin and stores it at `$FILE_HANDLE`, e.g.
+    /// `gzip > $FILE_HANDLE`.
+    pub write_cmd: String,
+    /// Prints the content of the file at `$FILE_HANDLE` to stdout, e.g. `gzip -dc $FILE_HANDLE`.
+    pub read_cmd: String,
+}
+
+/// A storage backend that streams each file through user-configured shell commands, so that any
+/// object store, compressor or encryptor can be used without code changes.
+pub struct CommandAdapter {
+    config: CommandAdapterConfig,
+}
+
+impl CommandAdapter {
+    pub fn new(config: CommandAdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Adapter for CommandAdapter {
+    async fn write_new_file(
+        &self,
+        content: impl StreamExt<Item = Vec<u8>> + Send + 'async_trait,
+    ) -> Result<FileHandle> {
+        let mut content = Box::pin(content);
+
+        let output = spawn_async(&self.config.create_cmd, None, Stdio::null(), Stdio::piped())?
+            .wait_with_output()
+            .await?;
+        ensure!(
+            output.status.success(),
+            "Command `{}` failed: {}",
+            self.config.create_cmd,
+            output.status,
+        );
+        let handle = String::from_utf8(output.stdout)?.trim().to_string();
+        ensure!(
+            !handle.is_empty(),
+            "Command `{}` didn't print a file handle.",
+            self.config.create_cmd,
+        );
+
+        let mut child = spawn_async(
+            &self.config.write_cmd,
+            Some(&handle),
+            Stdio::piped(),
+            Stdio::null(),
+        )?;
+        {
+            let stdin = child.stdin.as_mut().expect("stdin should be piped.");
+            while let Some(bytes) = content.next().await {
+                stdin.write_all(&bytes).await?;
+            }
+        }
+        // Close stdin so the command sees EOF.
+        drop(child.stdin.take());
+        let status = child.await?;
+        ensure!(
+            status.success(),
+            "Command `{}` failed: {}",
+            self.config.write_cmd,
+            status,
+        );
+
+        Ok(handle)
+    }
+
+    fn read_file_content(&self, file_handle: &FileHandle) -> BoxStream<Result<Vec<u8>>> {
+        let child = match spawn(
+            &self.config.read_cmd,
+            Some(file_handle),
+            Stdio::null(),
+            Stdio::piped(),
+        ) {
+            Ok(child) => child,
+            Err(e) => return futures::stream::once(async { Err(e) }).boxed(),
+        };
+
+        futures::stream::iter(CommandOutputIterator::new(
+            self.config.read_cmd.clone(),
+            child,
+        ))
+        .boxed()
+    }
+}
+
+/// Runs `cmd` with `sh -c`, passing `file_handle` in the environment if provided.
+fn spawn(cmd: &str, file_handle: Option<&str>, stdin: Stdio, stdout: Stdio) -> Result<Child> {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(cmd).stdin(stdin).stdout(stdout);
+    if let Some(file_handle) = file_handle {
+        command.env(FILE_HANDLE_ENV_VAR, file_handle);
+    }
+    Ok(command.spawn()?)
+}
+
+/// Like [`spawn`], but the command is driven by the tokio runtime, so that waiting on it doesn't
+/// block the executor thread. Needs to be called within a tokio runtime.
+fn spawn_async(
+    cmd: &str,
+    file_handle: Option<&str>,
+    stdin: Stdio,
+    stdout: Stdio,
+) -> Result<tokio::process::Child> {
+    let mut command = tokio::process::Command::new("sh");
+    command.arg("-c").arg(cmd).stdin(stdin).stdout(stdout);
+    if let Some(file_handle) = file_handle {
+        command.env(FILE_HANDLE_ENV_VAR, file_handle);
+    }
+    Ok(command.spawn()?)
+}
+
+/// Waits for the command to exit and errors if it didn't succeed.
+fn wait(mut child: Child, cmd: &str) -> Result<()> {
+    let status = child.wait()?;
+    ensure!(status.success(), "Command `{}` failed: {}", cmd, status);
+    Ok(())
+}
+
+/// An iterator that reads one chunk from the stdout of a command at a time and yields the bytes.
+/// Once stdout is drained, the exit status of the command is checked, so a failed command yields
+/// an error instead of silently truncated content.
+struct CommandOutputIterator {
+    cmd: String,
+    /// `None` once the command has exited.
+    child: Option<Child>,
+}
+
+impl CommandOutputIterator {
+    fn new(cmd: String, child: Child) -> Self {
+        Self {
+            cmd,
+            child: Some(child),
+        }
+    }
+}
+
+impl Iterator for CommandOutputIterator {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stdout = self
+            .child
+            .as_mut()?
+            .stdout
+            .as_mut()
+            .expect("stdout should be piped.");
+        let mut buf = vec![0u8; READ_CHUNK_SIZE];
+        match stdout.read(&mut buf) {
+            Ok(0) => {
+                let child = self.child.take().expect("Checked above.");
+                wait(child, &self.cmd).err().map(Err)
+            }
+            Ok(len) => {
+                buf.truncate(len);
+                Some(Ok(buf))
+            }
+            Err(e) => {
+                self.child = None;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
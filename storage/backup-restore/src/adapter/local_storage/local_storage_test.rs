@@ -5,13 +5,32 @@ use super::*;
 use futures::executor::{block_on, block_on_stream};
 use proptest::{collection::vec, prelude::*};
 
+fn read_all(adapter: &LocalStorage, handle: &FileHandle) -> Result<Vec<u8>> {
+    let mut content = vec![];
+    for res in block_on_stream(adapter.read_file_content(handle)) {
+        content.extend_from_slice(&res?);
+    }
+    Ok(content)
+}
+
+fn arb_compression() -> impl Strategy<Value = Compression> {
+    prop_oneof![
+        Just(Compression::None),
+        Just(Compression::Gzip),
+        Just(Compression::Zstd),
+    ]
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
     #[test]
-    fn test_local_storage(contents in vec(vec(any::<u8>(), 1..1000), 1..10)) {
+    fn test_local_storage(
+        contents in vec(vec(any::<u8>(), 1..1000), 1..10),
+        compression in arb_compression(),
+    ) {
         let tmpdir = tempfile::tempdir().unwrap();
-        let adapter = LocalStorage::new(tmpdir.path().to_path_buf());
+        let adapter = LocalStorage::new_with_compression(tmpdir.path().to_path_buf(), compression);
 
         let file_handles: Vec<_> = contents.iter().map(|content| {
             let iter = content.chunks(10).map(|c| c.to_vec());
@@ -20,12 +39,24 @@ proptest! {
         }).collect();
 
         for (handle, expected_content) in itertools::zip_eq(file_handles, contents) {
-            let mut actual_content = vec![];
-            for res in block_on_stream(LocalStorage::read_file_content(&handle)) {
-                let bytes = res.unwrap();
-                actual_content.extend_from_slice(&bytes);
-            }
-            prop_assert_eq!(actual_content, expected_content);
+            prop_assert_eq!(read_all(&adapter, &handle).unwrap(), expected_content);
         }
     }
 }
+
+#[test]
+fn test_local_storage_compression_from_name() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let adapter =
+        LocalStorage::new_with_compression(tmpdir.path().to_path_buf(), Compression::Zstd);
+
+    let content = vec![1u8; 100];
+    let stream = futures::stream::once(async { vec![1u8; 100] });
+    let handle = block_on(adapter.write_new_file(stream)).unwrap();
+    assert!(handle.ends_with(Compression::Zstd.extension()));
+    assert_ne!(std::fs::read(&handle).unwrap(), content);
+
+    // Files are decompressed according to their names, whatever the adapter writes.
+    let adapter = LocalStorage::new(tmpdir.path().to_path_buf());
+    assert_eq!(read_all(&adapter, &handle).unwrap(), content);
+}
@@ -6,26 +6,85 @@ mod local_storage_test;
 
 use super::Adapter;
 use crate::FileHandle;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
-use futures::{stream::BoxStream, StreamExt};
+use futures::{stream::BoxStream, Stream, StreamExt};
 use rand::RngCore;
 use std::{
-    io::{BufRead, BufReader, Write},
+    io::{BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 const FILENAME_LEN: usize = 16;
 
+/// How files are compressed on disk. The compression of a file is identified by the extension of
+/// its name, so files written with different settings can be read back by the same
+/// `LocalStorage`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn extension(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    fn from_path(path: &str) -> Self {
+        if path.ends_with(Compression::Gzip.extension()) {
+            Compression::Gzip
+        } else if path.ends_with(Compression::Zstd.extension()) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Returns a reader that decompresses `file` as it's read.
+    fn decoder(self, file: std::fs::File) -> Result<Box<dyn Read + Send>> {
+        Ok(match self {
+            Compression::None => Box::new(file),
+            Compression::Gzip => Box::new(flate2::read::GzDecoder::new(file)),
+            Compression::Zstd => Box::new(zstd::stream::Decoder::new(file)?),
+        })
+    }
+}
+
+impl FromStr for Compression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "none" => Compression::None,
+            "gzip" => Compression::Gzip,
+            "zstd" => Compression::Zstd,
+            _ => bail!("Unknown compression: {}. Expecting none, gzip or zstd.", s),
+        })
+    }
+}
+
 /// A storage backend that stores everything in a local directory.
 pub struct LocalStorage {
     /// The path where everything is stored.
     dir: PathBuf,
+    /// How new files are compressed.
+    compression: Compression,
 }
 
 impl LocalStorage {
     pub fn new(dir: PathBuf) -> Self {
-        Self { dir }
+        Self::new_with_compression(dir, Compression::None)
+    }
+
+    pub fn new_with_compression(dir: PathBuf, compression: Compression) -> Self {
+        Self { dir, compression }
     }
 }
 
@@ -36,29 +95,52 @@ impl Adapter for LocalStorage {
         content: impl StreamExt<Item = Vec<u8>> + Send + 'async_trait,
     ) -> Result<FileHandle> {
         let mut content = Box::pin(content);
-        let (mut file, handle) = create_random_file(&self.dir)?;
-        while let Some(bytes) = content.next().await {
-            file.write_all(&bytes)?;
+        let (mut file, handle) = create_random_file(&self.dir, self.compression)?;
+        match self.compression {
+            Compression::None => write_all(&mut file, &mut content).await?,
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(file, flate2::Compression::default());
+                write_all(&mut encoder, &mut content).await?;
+                file = encoder.finish()?;
+            }
+            Compression::Zstd => {
+                let mut encoder = zstd::stream::Encoder::new(file, 0 /* default level */)?;
+                write_all(&mut encoder, &mut content).await?;
+                file = encoder.finish()?;
+            }
         }
         file.sync_data()?;
         Ok(handle)
     }
 
-    fn read_file_content(file_handle: &FileHandle) -> BoxStream<Result<Vec<u8>>> {
-        let file = match std::fs::File::open(&file_handle) {
-            Ok(f) => f,
-            Err(e) => return futures::stream::once(async { Err(e.into()) }).boxed(),
-        };
+    fn read_file_content(&self, file_handle: &FileHandle) -> BoxStream<Result<Vec<u8>>> {
+        let reader = std::fs::File::open(&file_handle)
+            .map_err(Into::into)
+            .and_then(|file| Compression::from_path(file_handle).decoder(file));
+        match reader {
+            Ok(reader) => futures::stream::iter(FileIterator::new(reader)).boxed(),
+            Err(e) => futures::stream::once(async { Err(e) }).boxed(),
+        }
+    }
+}
 
-        futures::stream::iter(FileIterator::new(file)).boxed()
+/// Writes everything in `content` to `writer` as it arrives.
+async fn write_all(
+    writer: &mut impl Write,
+    content: &mut (impl Stream<Item = Vec<u8>> + Unpin),
+) -> Result<()> {
+    while let Some(bytes) = content.next().await {
+        writer.write_all(&bytes)?;
     }
+    Ok(())
 }
 
 /// Creates a file with random name in the given directory. Returns the reference to the open file
 /// as well as the handle (name) of the file.
-fn create_random_file(dir: &Path) -> Result<(std::fs::File, FileHandle)> {
+fn create_random_file(dir: &Path, compression: Compression) -> Result<(std::fs::File, FileHandle)> {
     loop {
-        let filename = create_random_filename(dir)
+        let filename = create_random_filename(dir, compression)
             .into_os_string()
             .into_string()
             .expect("Filename should contain valid unicode.");
@@ -82,20 +164,24 @@ fn create_random_file(dir: &Path) -> Result<(std::fs::File, FileHandle)> {
 }
 
 /// Generates a random filename under given directory.
-fn create_random_filename(dir: &Path) -> PathBuf {
+fn create_random_filename(dir: &Path, compression: Compression) -> PathBuf {
     let mut filename = [0; FILENAME_LEN];
     rand::thread_rng().fill_bytes(&mut filename);
-    dir.join(hex::encode(filename))
+    dir.join(format!(
+        "{}{}",
+        hex::encode(filename),
+        compression.extension()
+    ))
 }
 
 /// An iterator that reads one chunk from a file at a time and yields the bytes.
 struct FileIterator {
-    /// The reference to the open file.
-    file: BufReader<std::fs::File>,
+    /// The reader of the open file, which decompresses it if needed.
+    file: BufReader<Box<dyn Read + Send>>,
 }
 
 impl FileIterator {
-    fn new(file: std::fs::File) -> Self {
+    fn new(file: Box<dyn Read + Send>) -> Self {
         Self {
             file: BufReader::new(file),
         }
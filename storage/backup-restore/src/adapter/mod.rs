@@ -1,12 +1,17 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod command_adapter;
 pub mod local_storage;
 
 use crate::FileHandle;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
+use command_adapter::CommandAdapterConfig;
 use futures::{stream::BoxStream, StreamExt};
+use local_storage::Compression;
+use std::path::PathBuf;
+use structopt::StructOpt;
 
 /// `Adapter` defines the interfaces of the storage backend we use for backup and restore.
 #[async_trait]
@@ -20,5 +25,59 @@ pub trait Adapter {
 
     /// Returns the content of the file in a stream.
     #[allow(clippy::ptr_arg)]
-    fn read_file_content(file_handle: &FileHandle) -> BoxStream<Result<Vec<u8>>>;
+    fn read_file_content(&self, file_handle: &FileHandle) -> BoxStream<Result<Vec<u8>>>;
+}
+
+/// Command line options selecting the storage backend, shared by the backup and restore tools.
+#[derive(Debug, StructOpt)]
+pub struct AdapterOpt {
+    /// Stores the backup as files under this local directory.
+    #[structopt(long, parse(from_os_str))]
+    pub local_dir: Option<PathBuf>,
+
+    /// How files under `local_dir` are compressed: none, gzip or zstd.
+    #[structopt(long, default_value = "none")]
+    pub compression: Compression,
+
+    /// Shell command that creates a new file and prints its handle, used instead of `local_dir`.
+    #[structopt(long)]
+    pub create_cmd: Option<String>,
+
+    /// Shell command that stores its stdin as the file at `$FILE_HANDLE`.
+    #[structopt(long)]
+    pub write_cmd: Option<String>,
+
+    /// Shell command that prints the content of the file at `$FILE_HANDLE`.
+    #[structopt(long)]
+    pub read_cmd: Option<String>,
+}
+
+/// The storage backend selected by [`AdapterOpt`].
+pub enum AdapterSpec {
+    LocalStorage(PathBuf, Compression),
+    Command(CommandAdapterConfig),
+}
+
+impl AdapterOpt {
+    pub fn spec(self) -> Result<AdapterSpec> {
+        match (
+            self.local_dir,
+            self.create_cmd,
+            self.write_cmd,
+            self.read_cmd,
+        ) {
+            (Some(dir), None, None, None) => Ok(AdapterSpec::LocalStorage(dir, self.compression)),
+            (None, Some(create_cmd), Some(write_cmd), Some(read_cmd)) => {
+                Ok(AdapterSpec::Command(CommandAdapterConfig {
+                    create_cmd,
+                    write_cmd,
+                    read_cmd,
+                }))
+            }
+            _ => bail!(
+                "Either --local-dir or all of --create-cmd, --write-cmd and --read-cmd must be \
+                 provided."
+            ),
+        }
+    }
 }
@@ -1,9 +1,15 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use backup_restore::{adapter::local_storage::LocalStorage, backup_account_state};
-use std::path::PathBuf;
-use storage_client::{StorageRead, StorageReadServiceClient};
+use backup_restore::{
+    adapter::{
+        command_adapter::CommandAdapter, local_storage::LocalStorage, Adapter, AdapterOpt,
+        AdapterSpec,
+    },
+    coordinator::{BackupCoordinator, BackupCoordinatorConfig},
+};
+use std::{path::PathBuf, time::Duration};
+use storage_client::StorageReadServiceClient;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -12,9 +18,41 @@ struct Opt {
     #[structopt(long, default_value = "4194304")]
     state_chunk_size: usize,
 
-    /// Where the backup is stored.
+    /// Number of account state partitions to back up at the same time.
+    #[structopt(long, default_value = "4")]
+    state_concurrency: usize,
+
+    /// Maximum bytes of account state to read from the node per second. Unlimited by default.
+    #[structopt(long)]
+    max_bytes_per_sec: Option<u64>,
+
+    /// Maximum account state requests to make to the node per second. Unlimited by default.
+    #[structopt(long)]
+    max_ops_per_sec: Option<u64>,
+
+    /// Maximum number of transactions in each transaction chunk.
+    #[structopt(long, default_value = "1000")]
+    transaction_chunk_size: u64,
+
+    /// Take a new state snapshot every this many epochs.
+    #[structopt(long, default_value = "1")]
+    state_snapshot_interval_epochs: u64,
+
+    #[structopt(flatten)]
+    adapter: AdapterOpt,
+
+    /// Path of the manifest recording what has been backed up. Defaults to `manifest.json` under
+    /// `local_dir`, required when backing up through commands.
     #[structopt(long, parse(from_os_str))]
-    local_dir: PathBuf,
+    manifest: Option<PathBuf>,
+
+    /// Keep running and back up new data every `interval_secs` seconds.
+    #[structopt(long)]
+    continuous: bool,
+
+    /// Seconds to wait between two rounds in continuous mode.
+    #[structopt(long, default_value = "3600")]
+    interval_secs: u64,
 
     /// The port of the storage service.
     #[structopt(long)]
@@ -28,20 +66,56 @@ async fn main() {
     let address = format!("127.0.0.1:{}", opt.node_port).parse().unwrap();
     let client = StorageReadServiceClient::new(&address);
 
-    let (version, state_root_hash) = client
-        .get_latest_state_root()
-        .await
-        .expect("Failed to get latest version and state root hash.");
-    println!("Latest version: {}", version);
-    println!("State root hash: {:x}", state_root_hash);
-
-    let adapter = LocalStorage::new(opt.local_dir);
-    let file_handles = backup_account_state(&client, version, &adapter, opt.state_chunk_size)
-        .await
-        .expect("Failed to backup account state.");
-
-    for (account_state_file, proof_file) in file_handles {
-        println!("{}", account_state_file);
-        println!("{}", proof_file);
+    let spec = opt.adapter.spec().expect("Invalid storage options.");
+    let manifest_path = match (opt.manifest, &spec) {
+        (Some(path), _) => path,
+        (None, AdapterSpec::LocalStorage(dir, _)) => dir.join("manifest.json"),
+        (None, AdapterSpec::Command(_)) => panic!("--manifest is required with --create-cmd."),
+    };
+    println!("Manifest: {:?}", manifest_path);
+
+    let config = BackupCoordinatorConfig {
+        manifest_path,
+        state_chunk_size: opt.state_chunk_size,
+        state_concurrency: opt.state_concurrency,
+        max_bytes_per_sec: opt.max_bytes_per_sec,
+        max_ops_per_sec: opt.max_ops_per_sec,
+        transaction_chunk_size: opt.transaction_chunk_size,
+        state_snapshot_interval_epochs: opt.state_snapshot_interval_epochs,
+        interval: Duration::from_secs(opt.interval_secs),
+    };
+    match spec {
+        AdapterSpec::LocalStorage(dir, compression) => {
+            run(
+                &client,
+                &LocalStorage::new_with_compression(dir, compression),
+                config,
+                opt.continuous,
+            )
+            .await
+        }
+        AdapterSpec::Command(adapter_config) => {
+            run(
+                &client,
+                &CommandAdapter::new(adapter_config),
+                config,
+                opt.continuous,
+            )
+            .await
+        }
+    }
+}
+
+async fn run<A: Adapter>(
+    client: &StorageReadServiceClient,
+    adapter: &A,
+    config: BackupCoordinatorConfig,
+    continuous: bool,
+) {
+    let coordinator = BackupCoordinator::new(client, adapter, config);
+    if continuous {
+        coordinator.run().await.expect("Backup failed.");
+    } else {
+        coordinator.run_once().await.expect("Backup failed.");
     }
 }
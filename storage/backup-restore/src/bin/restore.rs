@@ -1,108 +1,109 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use backup_restore::{
-    adapter::{local_storage::LocalStorage, Adapter},
-    FileHandle,
+    adapter::{
+        command_adapter::CommandAdapter, local_storage::LocalStorage, AdapterOpt, AdapterSpec,
+    },
+    manifest::BackupManifest,
+    rate_limiter::RateLimiter,
+    restore::{restore, TrustAnchor},
 };
-use byteorder::{LittleEndian, ReadBytesExt};
-use futures::executor::block_on_stream;
-use itertools::Itertools;
-use libra_crypto::HashValue;
-use libra_types::{account_state_blob::AccountStateBlob, proof::SparseMerkleRangeProof};
+use libra_types::{ledger_info::LedgerInfoWithSignatures, waypoint::Waypoint};
 use libradb::LibraDB;
-use std::{
-    io::{BufRead, Read},
-    path::PathBuf,
-};
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
     #[structopt(long, parse(from_os_str))]
     db_dir: PathBuf,
-}
-
-fn main() {
-    let opt = Opt::from_args();
 
-    let libradb = LibraDB::new(&opt.db_dir);
-
-    let stdin = std::io::stdin();
-    let mut iter = stdin.lock().lines();
+    /// The manifest written by the backup.
+    #[structopt(long, parse(from_os_str))]
+    manifest: PathBuf,
 
-    println!("Input version:");
-    let version = iter
-        .next()
-        .expect("Must provide version.")
-        .expect("Failed to read from stdin.")
-        .parse::<u64>()
-        .expect("Version must be valid u64.");
-    println!("Version: {}", version);
+    /// The waypoint to verify the backup against.
+    #[structopt(long)]
+    waypoint: Option<Waypoint>,
 
-    println!("Input state root hash:");
-    let root_hash_hex = iter
-        .next()
-        .expect("Must provide state root hash.")
-        .expect("Failed to read from stdin.");
-    let root_hash = HashValue::from_slice(
-        &hex::decode(&root_hash_hex).expect("State root hash must be valid hex."),
-    )
-    .expect("Invalid root hash.");
-    println!("State root hash: {:x}", root_hash);
+    /// A local file holding a trusted `LedgerInfoWithSignatures` to verify the backup against,
+    /// used instead of `waypoint`.
+    #[structopt(long, parse(from_os_str))]
+    trusted_ledger_info_file: Option<PathBuf>,
 
-    let chunk_and_proofs = iter.tuples().map(|(file1, file2)| {
-        let account_state_file = file1.expect("Failed to read from stdin.");
-        let accounts = read_account_state_chunk(account_state_file)
-            .expect("Failed to read account state file.");
+    #[structopt(flatten)]
+    adapter: AdapterOpt,
 
-        let proof_file = file2.expect("Failed to read from stdin");
-        let proof = read_proof(proof_file).expect("Failed to read proof file.");
+    /// Version of the state snapshot to restore. Defaults to the latest one.
+    #[structopt(long)]
+    snapshot_version: Option<u64>,
 
-        (accounts, proof)
-    });
+    /// Number of account state chunks to read in parallel. Chunks are still added to the DB one
+    /// at a time.
+    #[structopt(long, default_value = "4")]
+    concurrency: usize,
 
-    libradb
-        .restore_account_state(chunk_and_proofs, version, root_hash)
-        .expect("Failed to restore account state.");
+    /// Maximum bytes of account state to read from the backup per second. Unlimited by default.
+    #[structopt(long)]
+    max_bytes_per_sec: Option<u64>,
 
-    println!("Finished restoring account state.");
+    /// Maximum account state and proof files to read from the backup per second. Unlimited by
+    /// default.
+    #[structopt(long)]
+    max_ops_per_sec: Option<u64>,
 }
 
-fn read_account_state_chunk(file: FileHandle) -> Result<Vec<(HashValue, AccountStateBlob)>> {
-    let content = read_file(file)?;
-
-    let mut chunk = vec![];
-    let mut reader = std::io::Cursor::new(content);
-    loop {
-        let mut buf = [0u8; HashValue::LENGTH];
-        if reader.read_exact(&mut buf).is_err() {
-            break;
-        }
-        let key = HashValue::new(buf);
-
-        let len = reader.read_u32::<LittleEndian>()?;
-        let mut buf = vec![0u8; len as usize];
-        reader.read_exact(&mut buf)?;
-        let blob = AccountStateBlob::from(buf);
+fn main() {
+    let opt = Opt::from_args();
 
-        chunk.push((key, blob));
+    let anchor = get_trust_anchor(opt.waypoint, opt.trusted_ledger_info_file)
+        .expect("Failed to get trust anchor.");
+    let manifest =
+        BackupManifest::load_or_default(&opt.manifest).expect("Failed to load manifest.");
+    let libradb = LibraDB::new(&opt.db_dir);
+    let rate_limiter = RateLimiter::new(opt.max_bytes_per_sec, opt.max_ops_per_sec);
+
+    match opt.adapter.spec().expect("Invalid storage options.") {
+        // The compression of each file is identified by its name.
+        AdapterSpec::LocalStorage(dir, _) => restore(
+            &libradb,
+            &LocalStorage::new(dir),
+            &manifest,
+            &anchor,
+            opt.snapshot_version,
+            opt.concurrency,
+            &rate_limiter,
+        ),
+        AdapterSpec::Command(config) => restore(
+            &libradb,
+            &CommandAdapter::new(config),
+            &manifest,
+            &anchor,
+            opt.snapshot_version,
+            opt.concurrency,
+            &rate_limiter,
+        ),
     }
-
-    Ok(chunk)
-}
-
-fn read_proof(file: FileHandle) -> Result<SparseMerkleRangeProof> {
-    let content = read_file(file)?;
-    let proof = lcs::from_bytes(&content)?;
-    Ok(proof)
+    .expect("Failed to restore.");
+    println!("Finished restoring.");
 }
 
-fn read_file(file: FileHandle) -> Result<Vec<u8>> {
-    let mut content = vec![];
-    for bytes_res in block_on_stream(LocalStorage::read_file_content(&file)) {
-        content.extend(bytes_res?);
+fn get_trust_anchor(
+    waypoint: Option<Waypoint>,
+    trusted_ledger_info_file: Option<PathBuf>,
+) -> Result<TrustAnchor> {
+    ensure!(
+        waypoint.is_some() != trusted_ledger_info_file.is_some(),
+        "Exactly one of --waypoint and --trusted-ledger-info-file must be provided.",
+    );
+    if let Some(waypoint) = waypoint {
+        return Ok(TrustAnchor::Waypoint(waypoint));
     }
-    Ok(content)
+
+    let file = trusted_ledger_info_file.expect("Checked above.");
+    let content = std::fs::read(file)?;
+    let ledger_info_with_sigs: LedgerInfoWithSignatures = lcs::from_bytes(&content)?;
+    Ok(TrustAnchor::LedgerInfo(ledger_info_with_sigs))
 }
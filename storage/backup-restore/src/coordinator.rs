@@ -0,0 +1,250 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines [`BackupCoordinator`], which keeps backing up a node incrementally. It
+//! records what has been backed up in a [`BackupManifest`] and on every round only backs up what's
+//! been committed since the last round.
+
+use crate::{
+    adapter::Adapter,
+    backup_account_state, backup_epoch_change_ledger_infos, backup_ledger_info,
+    backup_transactions,
+    manifest::{
+        BackupManifest, EpochChangeBackup, PendingTransactionRange, StateSnapshotBackup,
+        TransactionRangeBackup,
+    },
+    rate_limiter::RateLimiter,
+    write_file,
+};
+use anyhow::{format_err, Result};
+use libra_types::ledger_info::LedgerInfoWithSignatures;
+use std::{path::PathBuf, time::Duration};
+use storage_client::{StorageRead, StorageReadServiceClient};
+
+/// Number of transaction chunks backed up between two saves of the manifest.
+const CHUNKS_PER_MANIFEST_SAVE: u64 = 10;
+
+/// Config for [`BackupCoordinator`].
+#[derive(Clone, Debug)]
+pub struct BackupCoordinatorConfig {
+    /// Where the manifest is persisted.
+    pub manifest_path: PathBuf,
+    /// Maximum size in bytes of each account state chunk.
+    pub state_chunk_size: usize,
+    /// Number of account state partitions backed up at the same time.
+    pub state_concurrency: usize,
+    /// Limits the bytes of account state read from the node per second, `None` for no limit.
+    pub max_bytes_per_sec: Option<u64>,
+    /// Limits the requests per second made to the node for account state, `None` for no limit.
+    pub max_ops_per_sec: Option<u64>,
+    /// Maximum number of transactions in each transaction chunk.
+    pub transaction_chunk_size: u64,
+    /// A new state snapshot is taken when the ledger is at least this many epochs ahead of the
+    /// latest snapshot.
+    pub state_snapshot_interval_epochs: u64,
+    /// How long to wait between two rounds in continuous mode.
+    pub interval: Duration,
+}
+
+pub struct BackupCoordinator<'a, A> {
+    client: &'a StorageReadServiceClient,
+    adapter: &'a A,
+    config: BackupCoordinatorConfig,
+    rate_limiter: RateLimiter,
+}
+
+impl<'a, A: Adapter> BackupCoordinator<'a, A> {
+    pub fn new(
+        client: &'a StorageReadServiceClient,
+        adapter: &'a A,
+        config: BackupCoordinatorConfig,
+    ) -> Self {
+        let rate_limiter = RateLimiter::new(config.max_bytes_per_sec, config.max_ops_per_sec);
+        Self {
+            client,
+            adapter,
+            config,
+            rate_limiter,
+        }
+    }
+
+    /// Runs backup rounds forever, sleeping `interval` between rounds.
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            self.run_once().await?;
+            tokio::time::delay_for(self.config.interval).await;
+        }
+    }
+
+    /// Backs up everything committed since the last round. The manifest is persisted after each
+    /// step so that an interrupted round resumes from where it stopped.
+    pub async fn run_once(&self) -> Result<()> {
+        let mut manifest = BackupManifest::load_or_default(&self.config.manifest_path)?;
+
+        let ledger_info_with_sigs = self
+            .client
+            .get_startup_info()
+            .await?
+            .ok_or_else(|| format_err!("DB not bootstrapped."))?
+            .latest_ledger_info;
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        println!(
+            "Latest ledger info at version {}, epoch {}.",
+            ledger_info.version(),
+            ledger_info.epoch()
+        );
+
+        self.backup_epoch_changes(&mut manifest, ledger_info.epoch())
+            .await?;
+        self.backup_new_transactions(&mut manifest, &ledger_info_with_sigs)
+            .await?;
+        self.maybe_backup_state_snapshot(&mut manifest, &ledger_info_with_sigs)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn backup_epoch_changes(
+        &self,
+        manifest: &mut BackupManifest,
+        ledger_epoch: u64,
+    ) -> Result<()> {
+        let start_epoch = manifest.next_epoch();
+        if start_epoch >= ledger_epoch {
+            return Ok(());
+        }
+
+        let ledger_infos =
+            backup_epoch_change_ledger_infos(self.client, start_epoch, ledger_epoch, self.adapter)
+                .await?;
+        manifest.epoch_changes.push(EpochChangeBackup {
+            start_epoch,
+            end_epoch: ledger_epoch,
+            ledger_infos,
+        });
+        manifest.save(&self.config.manifest_path)
+    }
+
+    /// Backs up the transactions committed since the last range, after finishing the range an
+    /// interrupted round left pending, if any.
+    async fn backup_new_transactions(
+        &self,
+        manifest: &mut BackupManifest,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        if let Some(pending) = manifest.pending_transaction_range.take() {
+            println!(
+                "Resuming transactions [{}, {}] from version {}.",
+                pending.range.first_version, pending.range.last_version, pending.next_version
+            );
+            self.backup_transaction_range(manifest, pending).await?;
+        }
+
+        let first_version = manifest.next_version();
+        let last_version = ledger_info_with_sigs.ledger_info().version();
+        if first_version > last_version {
+            println!("No new transactions since version {}.", first_version);
+            return Ok(());
+        }
+
+        let ledger_info = backup_ledger_info(ledger_info_with_sigs, self.adapter).await?;
+        let pending = PendingTransactionRange {
+            range: TransactionRangeBackup {
+                first_version,
+                last_version,
+                ledger_info,
+                transactions: vec![],
+            },
+            next_version: first_version,
+        };
+        self.backup_transaction_range(manifest, pending).await
+    }
+
+    /// Backs up the rest of `pending` against the ledger info it was started with. The manifest is
+    /// saved after every `CHUNKS_PER_MANIFEST_SAVE` chunks, so a crash only loses the chunks
+    /// written since.
+    async fn backup_transaction_range(
+        &self,
+        manifest: &mut BackupManifest,
+        mut pending: PendingTransactionRange,
+    ) -> Result<()> {
+        let last_version = pending.range.last_version;
+        let batch_size = self
+            .config
+            .transaction_chunk_size
+            .saturating_mul(CHUNKS_PER_MANIFEST_SAVE);
+        while pending.next_version <= last_version {
+            let num_transactions =
+                std::cmp::min(batch_size, last_version - pending.next_version + 1);
+            let transactions = backup_transactions(
+                self.client,
+                pending.next_version,
+                num_transactions,
+                last_version,
+                self.adapter,
+                self.config.transaction_chunk_size,
+            )
+            .await?;
+            pending.range.transactions.extend(transactions);
+            pending.next_version += num_transactions;
+            manifest.pending_transaction_range = Some(pending.clone());
+            manifest.save(&self.config.manifest_path)?;
+        }
+
+        manifest.pending_transaction_range = None;
+        manifest.transaction_ranges.push(pending.range);
+        manifest.save(&self.config.manifest_path)
+    }
+
+    async fn maybe_backup_state_snapshot(
+        &self,
+        manifest: &mut BackupManifest,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let epoch = ledger_info.epoch();
+        if let Some(last_epoch) = manifest.latest_state_snapshot_epoch() {
+            if epoch < last_epoch + self.config.state_snapshot_interval_epochs {
+                return Ok(());
+            }
+        }
+
+        let version = ledger_info.version();
+        let txn_list_with_proof = self
+            .client
+            .get_transactions(version, 1, version, /* fetch_events = */ false)
+            .await?;
+        let root_hash = txn_list_with_proof
+            .proof
+            .transaction_infos()
+            .first()
+            .map(|txn_info| txn_info.state_root_hash())
+            .ok_or_else(|| format_err!("No TransactionInfo at version {}.", version))?;
+        println!(
+            "Taking state snapshot at version {}, root hash {:x}.",
+            version, root_hash
+        );
+
+        let ledger_info = backup_ledger_info(ledger_info_with_sigs, self.adapter).await?;
+        let bytes = lcs::to_bytes(&txn_list_with_proof)?;
+        let transaction_info = write_file(self.adapter, bytes).await?;
+        let chunks = backup_account_state(
+            self.client,
+            version,
+            self.adapter,
+            self.config.state_chunk_size,
+            self.config.state_concurrency,
+            &self.rate_limiter,
+        )
+        .await?;
+        manifest.state_snapshots.push(StateSnapshotBackup {
+            epoch,
+            version,
+            root_hash,
+            ledger_info,
+            transaction_info,
+            chunks,
+        });
+        manifest.save(&self.config.manifest_path)
+    }
+}
@@ -2,29 +2,94 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod adapter;
+pub mod coordinator;
+pub mod manifest;
+pub mod rate_limiter;
+pub mod restore;
 
-use crate::adapter::Adapter;
-use anyhow::Result;
-use futures::{stream, StreamExt};
+use crate::{adapter::Adapter, manifest::BackupFile, rate_limiter::RateLimiter};
+use anyhow::{ensure, format_err, Result};
+use futures::{stream, StreamExt, TryStreamExt};
 use libra_crypto::HashValue;
-use libra_types::transaction::Version;
+use libra_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
 use storage_client::{StorageRead, StorageReadServiceClient};
 
 pub type FileHandle = String;
 
+/// The maximum number of transactions the storage service serves in a single `get_transactions`
+/// call.
+pub const MAX_TRANSACTION_CHUNK_SIZE: u64 = 1000;
+
+/// The account key space is split by the first nibble of the keys into this many partitions,
+/// which are backed up concurrently.
+const NUM_STATE_PARTITIONS: u8 = 16;
+
+/// Writes `bytes` as a new file and records its checksum.
+pub async fn write_file(adapter: &impl Adapter, bytes: Vec<u8>) -> Result<BackupFile> {
+    let checksum = HashValue::from_sha3_256(&bytes);
+    let handle = adapter
+        .write_new_file(stream::once(async move { bytes }))
+        .await?;
+    Ok(BackupFile { handle, checksum })
+}
+
+/// Backs up all accounts at `version` in chunks of at most `max_chunk_size` bytes, each with a
+/// range proof of its rightmost key. Up to `concurrency` partitions of the key space are fetched
+/// and written at the same time. The chunks are returned in key order.
 pub async fn backup_account_state(
     client: &StorageReadServiceClient,
     version: Version,
     adapter: &impl Adapter,
     max_chunk_size: usize,
-) -> Result<Vec<(FileHandle, FileHandle)>> {
+    concurrency: usize,
+    rate_limiter: &RateLimiter,
+) -> Result<Vec<(BackupFile, BackupFile)>> {
+    let partitions: Vec<Vec<(BackupFile, BackupFile)>> = stream::iter(0..NUM_STATE_PARTITIONS)
+        .map(|nibble| {
+            backup_account_state_partition(
+                client,
+                version,
+                adapter,
+                max_chunk_size,
+                rate_limiter,
+                nibble,
+            )
+        })
+        .buffered(std::cmp::max(concurrency, 1))
+        .try_collect()
+        .await?;
+    let ret: Vec<_> = partitions.into_iter().flatten().collect();
+    ensure!(!ret.is_empty(), "Should have at least one account.");
+    Ok(ret)
+}
+
+/// Backs up the accounts whose keys start with `nibble`.
+async fn backup_account_state_partition(
+    client: &StorageReadServiceClient,
+    version: Version,
+    adapter: &impl Adapter,
+    max_chunk_size: usize,
+    rate_limiter: &RateLimiter,
+    nibble: u8,
+) -> Result<Vec<(BackupFile, BackupFile)>> {
     let mut chunk = vec![];
     let mut ret = vec![];
 
-    let mut account_stream = client.backup_account_state(version).await?;
+    let mut start_key = [0u8; HashValue::LENGTH];
+    start_key[0] = nibble << 4;
+    // The whole partition is streamed by a single request, whose accounts only count against the
+    // bytes limit.
+    rate_limiter.acquire(0).await;
+    let mut account_stream = client
+        .backup_account_state(version, HashValue::new(start_key))
+        .await?;
     let mut prev_key = None;
     while let Some(resp) = account_stream.next().await.transpose()? {
         let key = resp.account_key;
+        if u8::from(key.get_nibble(0)) != nibble {
+            // Reached the next partition.
+            break;
+        }
         let blob = resp.account_state_blob;
         println!("Backing up key: {:x}", key);
 
@@ -35,18 +100,18 @@ pub async fn backup_account_state(
         bytes.extend_from_slice(&blob_len.to_le_bytes());
         bytes.extend(blob);
         assert!(bytes.len() <= max_chunk_size);
+        rate_limiter.acquire_bytes(bytes.len() as u64).await;
 
         if chunk.len() + bytes.len() > max_chunk_size {
             assert!(chunk.len() <= max_chunk_size);
-            let account_state_file = adapter
-                .write_new_file(stream::once(async move { chunk }))
-                .await?;
+            let account_state_file = write_file(adapter, chunk).await?;
 
             let prev_key = prev_key.expect("max_chunk_size should be larger than account size.");
             println!(
                 "Reached max_chunk_size. Asking proof for key: {:?}",
                 prev_key,
             );
+            rate_limiter.acquire(0).await;
             let proof_file = get_proof_and_write(client, adapter, prev_key, version).await?;
             ret.push((account_state_file, proof_file));
             chunk = vec![];
@@ -56,16 +121,16 @@ pub async fn backup_account_state(
         prev_key = Some(key);
     }
 
-    assert!(!chunk.is_empty());
-    assert!(chunk.len() <= max_chunk_size);
-    let account_state_file = adapter
-        .write_new_file(stream::once(async move { chunk }))
-        .await?;
+    if let Some(prev_key) = prev_key {
+        assert!(!chunk.is_empty());
+        assert!(chunk.len() <= max_chunk_size);
+        let account_state_file = write_file(adapter, chunk).await?;
 
-    let prev_key = prev_key.expect("Should have at least one account.");
-    println!("Asking proof for last key: {:x}", prev_key);
-    let proof_file = get_proof_and_write(client, adapter, prev_key, version).await?;
-    ret.push((account_state_file, proof_file));
+        println!("Asking proof for last key: {:x}", prev_key);
+        rate_limiter.acquire(0).await;
+        let proof_file = get_proof_and_write(client, adapter, prev_key, version).await?;
+        ret.push((account_state_file, proof_file));
+    }
 
     Ok(ret)
 }
@@ -75,11 +140,112 @@ async fn get_proof_and_write(
     adapter: &impl Adapter,
     key: HashValue,
     version: Version,
-) -> Result<FileHandle> {
+) -> Result<BackupFile> {
     let proof = client.get_account_state_range_proof(key, version).await?;
     let proof_bytes: Vec<u8> = lcs::to_bytes(&proof)?;
-    let file = adapter
-        .write_new_file(stream::once(async move { proof_bytes }))
-        .await?;
-    Ok(file)
+    write_file(adapter, proof_bytes).await
+}
+
+/// Backs up `num_transactions` transactions starting at `start_version`, together with their
+/// `TransactionInfo`s, events and accumulator range proofs relative to the ledger at
+/// `ledger_version`. Each chunk holds at most `max_chunk_size` transactions and is written as a
+/// serialized `TransactionListWithProof`.
+pub async fn backup_transactions(
+    client: &StorageReadServiceClient,
+    start_version: Version,
+    num_transactions: u64,
+    ledger_version: Version,
+    adapter: &impl Adapter,
+    max_chunk_size: u64,
+) -> Result<Vec<BackupFile>> {
+    ensure!(
+        max_chunk_size > 0 && max_chunk_size <= MAX_TRANSACTION_CHUNK_SIZE,
+        "Chunk size {} is not in [1, {}].",
+        max_chunk_size,
+        MAX_TRANSACTION_CHUNK_SIZE,
+    );
+    let end_version = start_version
+        .checked_add(num_transactions)
+        .filter(|end_version| *end_version <= ledger_version + 1)
+        .ok_or_else(|| {
+            format_err!(
+                "{} transactions from version {} go beyond ledger version {}.",
+                num_transactions,
+                start_version,
+                ledger_version,
+            )
+        })?;
+
+    let mut ret = vec![];
+    let mut version = start_version;
+    while version < end_version {
+        let limit = std::cmp::min(max_chunk_size, end_version - version);
+        println!(
+            "Backing up transactions [{}, {}).",
+            version,
+            version + limit
+        );
+        let txn_list_with_proof = client
+            .get_transactions(
+                version,
+                limit,
+                ledger_version,
+                /* fetch_events = */ true,
+            )
+            .await?;
+        ensure!(
+            txn_list_with_proof.len() as u64 == limit,
+            "Expected {} transactions from version {}, got {}.",
+            limit,
+            version,
+            txn_list_with_proof.len(),
+        );
+
+        let bytes = lcs::to_bytes(&txn_list_with_proof)?;
+        ret.push(write_file(adapter, bytes).await?);
+        version += limit;
+    }
+
+    Ok(ret)
+}
+
+/// Backs up the ledger infos that end each epoch in [`start_epoch`, `end_epoch`). Each file holds
+/// a serialized `Vec<LedgerInfoWithSignatures>`.
+pub async fn backup_epoch_change_ledger_infos(
+    client: &StorageReadServiceClient,
+    start_epoch: u64,
+    end_epoch: u64,
+    adapter: &impl Adapter,
+) -> Result<Vec<BackupFile>> {
+    let mut ret = vec![];
+    let mut epoch = start_epoch;
+    while epoch < end_epoch {
+        println!("Backing up epoch change ledger infos from epoch {}.", epoch);
+        let proof = client
+            .get_epoch_change_ledger_infos(epoch, end_epoch)
+            .await?;
+        let last_epoch = match proof.ledger_info_with_sigs.last() {
+            Some(li) => li.ledger_info().epoch(),
+            None => break,
+        };
+
+        let bytes = lcs::to_bytes(&proof.ledger_info_with_sigs)?;
+        ret.push(write_file(adapter, bytes).await?);
+
+        if !proof.more {
+            break;
+        }
+        epoch = last_epoch + 1;
+    }
+
+    Ok(ret)
+}
+
+/// Backs up the ledger info that the transaction backup is taken against.
+pub async fn backup_ledger_info(
+    ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    adapter: &impl Adapter,
+) -> Result<BackupFile> {
+    let bytes = lcs::to_bytes(ledger_info_with_sigs)?;
+    write_file(adapter, bytes).await
 }
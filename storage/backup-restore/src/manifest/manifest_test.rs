@@ -0,0 +1,102 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn file(handle: &str) -> BackupFile {
+    BackupFile {
+        handle: handle.to_string(),
+        checksum: HashValue::from_sha3_256(handle.as_bytes()),
+    }
+}
+
+fn txn_range(first_version: Version, last_version: Version) -> TransactionRangeBackup {
+    TransactionRangeBackup {
+        first_version,
+        last_version,
+        ledger_info: file(&format!("li_{}", last_version)),
+        transactions: vec![file(&format!("txns_{}", first_version))],
+    }
+}
+
+#[test]
+fn test_manifest_save_and_load() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("manifest.json");
+
+    let manifest = BackupManifest::load_or_default(&path).unwrap();
+    assert_eq!(manifest, BackupManifest::default());
+    assert_eq!(manifest.next_version(), 0);
+    assert_eq!(manifest.next_epoch(), 0);
+    assert_eq!(manifest.latest_state_snapshot_epoch(), None);
+
+    let manifest = BackupManifest {
+        state_snapshots: vec![StateSnapshotBackup {
+            epoch: 1,
+            version: 9,
+            root_hash: HashValue::random(),
+            ledger_info: file("li_9"),
+            transaction_info: file("txn_info_9"),
+            chunks: vec![(file("state"), file("proof"))],
+        }],
+        transaction_ranges: vec![txn_range(0, 9), txn_range(10, 19)],
+        epoch_changes: vec![EpochChangeBackup {
+            start_epoch: 0,
+            end_epoch: 2,
+            ledger_infos: vec![file("epochs")],
+        }],
+        pending_transaction_range: Some(PendingTransactionRange {
+            range: txn_range(20, 29),
+            next_version: 25,
+        }),
+    };
+    manifest.save(&path).unwrap();
+
+    let loaded = BackupManifest::load_or_default(&path).unwrap();
+    assert_eq!(loaded, manifest);
+    assert_eq!(loaded.next_version(), 20);
+    assert_eq!(loaded.next_epoch(), 2);
+    assert_eq!(loaded.latest_state_snapshot_epoch(), Some(1));
+
+    // Every file must come with a checksum.
+    let mut value = serde_json::to_value(&manifest).unwrap();
+    value["epoch_changes"][0]["ledger_infos"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("checksum");
+    std::fs::write(&path, serde_json::to_vec(&value).unwrap()).unwrap();
+    assert!(BackupManifest::load_or_default(&path).is_err());
+}
+
+#[test]
+fn test_manifest_verify() {
+    let mut manifest = BackupManifest::default();
+    manifest.transaction_ranges = vec![txn_range(0, 9), txn_range(11, 19)];
+    assert!(manifest.verify().is_err());
+
+    manifest.transaction_ranges = vec![txn_range(0, 9), txn_range(10, 19)];
+    assert!(manifest.verify().is_ok());
+
+    manifest.pending_transaction_range = Some(PendingTransactionRange {
+        range: txn_range(10, 19),
+        next_version: 15,
+    });
+    assert!(manifest.verify().is_err());
+    manifest.pending_transaction_range = Some(PendingTransactionRange {
+        range: txn_range(20, 29),
+        next_version: 31,
+    });
+    assert!(manifest.verify().is_err());
+    manifest.pending_transaction_range = Some(PendingTransactionRange {
+        range: txn_range(20, 29),
+        next_version: 30,
+    });
+    assert!(manifest.verify().is_ok());
+
+    manifest.epoch_changes = vec![EpochChangeBackup {
+        start_epoch: 1,
+        end_epoch: 2,
+        ledger_infos: vec![],
+    }];
+    assert!(manifest.verify().is_err());
+}
@@ -0,0 +1,177 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines [`BackupManifest`], the record of everything that has been backed up so
+//! far. It is persisted after each unit of work so that a backup process can resume after a crash
+//! and only back up what's new since the last run.
+
+#[cfg(test)]
+mod manifest_test;
+
+use crate::FileHandle;
+use anyhow::{ensure, Result};
+use libra_crypto::HashValue;
+use libra_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// A file written to the storage backend, with the SHA3-256 hash of its content. The checksum is
+/// kept in the manifest whatever the backend, and restoring fails on a file that doesn't match it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BackupFile {
+    pub handle: FileHandle,
+    pub checksum: HashValue,
+}
+
+/// A snapshot of the account state tree at a certain version.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotBackup {
+    /// The epoch of the ledger info the snapshot is taken at.
+    pub epoch: u64,
+    /// The version of the snapshot.
+    pub version: Version,
+    /// The state root hash at `version`.
+    pub root_hash: HashValue,
+    /// The ledger info at `version`.
+    pub ledger_info: BackupFile,
+    /// A single-transaction `TransactionListWithProof` at `version`, which proves the
+    /// `TransactionInfo` carrying `root_hash` against `ledger_info`.
+    pub transaction_info: BackupFile,
+    /// Pairs of account state chunk file and range proof file.
+    pub chunks: Vec<(BackupFile, BackupFile)>,
+}
+
+/// A range of consecutive transactions, backed up against the ledger info at `last_version`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionRangeBackup {
+    pub first_version: Version,
+    pub last_version: Version,
+    /// The ledger info the transaction chunks are proven against.
+    pub ledger_info: BackupFile,
+    /// Transaction chunk files, in order.
+    pub transactions: Vec<BackupFile>,
+}
+
+/// A transaction range that is being backed up. It's persisted after every few chunks so that an
+/// interrupted backup resumes from `next_version` instead of starting the range over, and only
+/// joins `transaction_ranges` once all of its chunks are written.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PendingTransactionRange {
+    /// The range, whose `transactions` only cover [`first_version`, `next_version`) so far.
+    pub range: TransactionRangeBackup,
+    /// The first version of the range that hasn't been backed up.
+    pub next_version: Version,
+}
+
+/// Ledger infos that end each epoch in [`start_epoch`, `end_epoch`).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EpochChangeBackup {
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+    pub ledger_infos: Vec<BackupFile>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BackupManifest {
+    pub state_snapshots: Vec<StateSnapshotBackup>,
+    pub transaction_ranges: Vec<TransactionRangeBackup>,
+    pub epoch_changes: Vec<EpochChangeBackup>,
+    #[serde(default)]
+    pub pending_transaction_range: Option<PendingTransactionRange>,
+}
+
+impl BackupManifest {
+    /// Loads the manifest at `path`, or returns an empty one if it doesn't exist yet.
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read(path)?;
+        let manifest: Self = serde_json::from_slice(&content)?;
+        manifest.verify()?;
+        Ok(manifest)
+    }
+
+    /// Persists the manifest at `path`. The content is written to a temporary file first and then
+    /// renamed, so a crash never leaves a partially written manifest behind.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp_path = tmp_path(path);
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(self)?)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// The version of the first transaction that hasn't been backed up.
+    pub fn next_version(&self) -> Version {
+        self.transaction_ranges
+            .last()
+            .map_or(0, |range| range.last_version + 1)
+    }
+
+    /// The first epoch whose ending ledger info hasn't been backed up.
+    pub fn next_epoch(&self) -> u64 {
+        self.epoch_changes
+            .last()
+            .map_or(0, |backup| backup.end_epoch)
+    }
+
+    /// The epoch of the latest state snapshot, if any.
+    pub fn latest_state_snapshot_epoch(&self) -> Option<u64> {
+        self.state_snapshots.last().map(|snapshot| snapshot.epoch)
+    }
+
+    /// Checks that transaction ranges and epoch ranges are consecutive.
+    pub fn verify(&self) -> Result<()> {
+        let mut next_version = 0;
+        for range in &self.transaction_ranges {
+            ensure!(
+                range.first_version == next_version && range.first_version <= range.last_version,
+                "Transaction range [{}, {}] in manifest not expected, next version should be {}.",
+                range.first_version,
+                range.last_version,
+                next_version,
+            );
+            next_version = range.last_version + 1;
+        }
+        if let Some(pending) = &self.pending_transaction_range {
+            let range = &pending.range;
+            ensure!(
+                range.first_version == next_version
+                    && range.first_version <= range.last_version
+                    && range.first_version <= pending.next_version
+                    && pending.next_version <= range.last_version + 1,
+                "Pending transaction range [{}, {}] backed up to version {} not expected, next \
+                 version should be {}.",
+                range.first_version,
+                range.last_version,
+                pending.next_version,
+                next_version,
+            );
+        }
+
+        let mut next_epoch = 0;
+        for backup in &self.epoch_changes {
+            ensure!(
+                backup.start_epoch == next_epoch && backup.start_epoch < backup.end_epoch,
+                "Epoch range [{}, {}) in manifest not expected, next epoch should be {}.",
+                backup.start_epoch,
+                backup.end_epoch,
+                next_epoch,
+            );
+            next_epoch = backup.end_epoch;
+        }
+
+        Ok(())
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
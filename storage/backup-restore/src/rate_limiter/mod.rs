@@ -0,0 +1,119 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines [`RateLimiter`], which caps the bandwidth and the number of I/O operations
+//! per second spent on backup and restore, so that backing up a live validator doesn't starve its
+//! RocksDB.
+
+#[cfg(test)]
+mod rate_limiter_test;
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A token bucket refilled at `rate` tokens per second, holding at most one second worth of
+/// tokens. The balance can go negative, in which case callers wait until it's paid back.
+struct TokenBucket {
+    rate: f64,
+    state: Mutex<TokenBucketState>,
+}
+
+struct TokenBucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        assert!(rate > 0, "Rate must be positive.");
+        let rate = rate as f64;
+        Self {
+            rate,
+            state: Mutex::new(TokenBucketState {
+                tokens: rate,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes `amount` tokens and returns how long the caller needs to wait before using them.
+    fn reserve(&self, amount: u64, now: Instant) -> Duration {
+        let mut state = self.state.lock().expect("Lock poisoned.");
+        let elapsed = now.saturating_duration_since(state.last_refill);
+        state.last_refill = std::cmp::max(state.last_refill, now);
+        state.tokens = (state.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+        state.tokens -= amount as f64;
+        if state.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+}
+
+/// Limits the bytes and operations per second. Shared by all concurrent workers of a backup or
+/// restore.
+pub struct RateLimiter {
+    bytes_per_sec: Option<TokenBucket>,
+    ops_per_sec: Option<TokenBucket>,
+}
+
+impl RateLimiter {
+    /// `None` means no limit.
+    pub fn new(max_bytes_per_sec: Option<u64>, max_ops_per_sec: Option<u64>) -> Self {
+        Self {
+            bytes_per_sec: max_bytes_per_sec.map(TokenBucket::new),
+            ops_per_sec: max_ops_per_sec.map(TokenBucket::new),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None, None)
+    }
+
+    /// Returns how long to wait before performing one operation of `bytes` bytes.
+    fn reserve(&self, bytes: u64) -> Duration {
+        self.reserve_ops(bytes, 1)
+    }
+
+    /// Returns how long to wait before transferring `bytes` bytes in `ops` operations.
+    fn reserve_ops(&self, bytes: u64, ops: u64) -> Duration {
+        let now = Instant::now();
+        let wait_bytes = self
+            .bytes_per_sec
+            .as_ref()
+            .map_or(Duration::from_secs(0), |bucket| bucket.reserve(bytes, now));
+        let wait_ops = match &self.ops_per_sec {
+            Some(bucket) if ops > 0 => bucket.reserve(ops, now),
+            _ => Duration::from_secs(0),
+        };
+        std::cmp::max(wait_bytes, wait_ops)
+    }
+
+    /// Waits until one operation of `bytes` bytes is allowed.
+    pub async fn acquire(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if wait > Duration::from_secs(0) {
+            tokio::time::delay_for(wait).await;
+        }
+    }
+
+    /// Waits until `bytes` more bytes are allowed, as part of an operation that's already been
+    /// accounted for, e.g. an item of a streaming request.
+    pub async fn acquire_bytes(&self, bytes: u64) {
+        let wait = self.reserve_ops(bytes, 0);
+        if wait > Duration::from_secs(0) {
+            tokio::time::delay_for(wait).await;
+        }
+    }
+
+    /// Same as [`acquire`](#method.acquire), blocking the current thread.
+    pub fn acquire_blocking(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if wait > Duration::from_secs(0) {
+            std::thread::sleep(wait);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_token_bucket() {
+    let bucket = TokenBucket::new(100);
+    let start = bucket.state.lock().unwrap().last_refill;
+
+    // The bucket starts full.
+    assert_eq!(bucket.reserve(100, start), Duration::from_secs(0));
+    // Going into debt requires waiting for it to be paid back.
+    assert_eq!(bucket.reserve(50, start), Duration::from_millis(500));
+    // Refilled at 100 tokens per second.
+    assert_eq!(
+        bucket.reserve(50, start + Duration::from_secs(1)),
+        Duration::from_secs(0)
+    );
+    // Never holds more than one second worth of tokens.
+    let later = start + Duration::from_secs(100);
+    assert_eq!(bucket.reserve(100, later), Duration::from_secs(0));
+    assert_eq!(bucket.reserve(100, later), Duration::from_secs(1));
+}
+
+#[test]
+fn test_rate_limiter() {
+    let unlimited = RateLimiter::unlimited();
+    assert_eq!(unlimited.reserve(std::u64::MAX), Duration::from_secs(0));
+
+    let limiter = RateLimiter::new(Some(1000), Some(2));
+    assert_eq!(limiter.reserve(10), Duration::from_secs(0));
+    assert_eq!(limiter.reserve(10), Duration::from_secs(0));
+    // The third operation within a second is throttled by the ops limit.
+    assert!(limiter.reserve(10) > Duration::from_millis(400));
+
+    // Bytes within an operation only count against the bytes limit.
+    let limiter = RateLimiter::new(Some(1000), Some(1));
+    assert_eq!(limiter.reserve(10), Duration::from_secs(0));
+    assert_eq!(limiter.reserve_ops(10, 0), Duration::from_secs(0));
+    assert!(limiter.reserve_ops(2000, 0) > Duration::from_millis(900));
+}
@@ -0,0 +1,410 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module restores a [`LibraDB`] from what's recorded in a [`BackupManifest`]. Nothing read
+//! from the backup is trusted: every ledger info is verified against a [`TrustAnchor`] provided by
+//! the operator, and all transactions as well as the state root hash of the snapshot are verified
+//! against it before the first one is written. Account states are verified against that root hash
+//! as they're added, and the restored DB is only given a latest ledger info, i.e. made usable, once
+//! both the transactions and the state snapshot are restored.
+
+#[cfg(test)]
+mod restore_test;
+
+use crate::{
+    adapter::Adapter,
+    manifest::{BackupFile, BackupManifest, StateSnapshotBackup},
+    rate_limiter::RateLimiter,
+};
+use anyhow::{ensure, format_err, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use futures::executor::block_on_stream;
+use libra_crypto::{
+    hash::{CryptoHash, TransactionAccumulatorHasher},
+    HashValue,
+};
+use libra_types::{
+    account_state_blob::AccountStateBlob,
+    epoch_info::EpochInfo,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{accumulator::InMemoryAccumulator, SparseMerkleRangeProof},
+    transaction::{TransactionListWithProof, Version},
+    validator_change::{ValidatorChangeProof, VerifierType},
+    waypoint::Waypoint,
+};
+use libradb::LibraDB;
+use rayon::prelude::*;
+use serde::de::DeserializeOwned;
+use std::{io::Read, sync::Arc};
+
+/// What the operator trusts when restoring from a backup.
+#[derive(Clone, Debug)]
+pub enum TrustAnchor {
+    /// A waypoint, normally the one the node is configured with.
+    Waypoint(Waypoint),
+    /// A ledger info obtained from a trusted source. Unless it's the exact ledger info being
+    /// verified, it needs to end an epoch so that the validator set of the next epoch is known.
+    LedgerInfo(LedgerInfoWithSignatures),
+}
+
+/// Verifies `ledger_info_with_sigs` against `anchor`, using the epoch change ledger infos to move
+/// from the epoch of the anchor to that of the target. Returns the epoch change ledger infos that
+/// were verified along the way.
+pub fn verify_ledger_info(
+    anchor: &TrustAnchor,
+    epoch_change_ledger_infos: &[LedgerInfoWithSignatures],
+    ledger_info_with_sigs: &LedgerInfoWithSignatures,
+) -> Result<Vec<LedgerInfoWithSignatures>> {
+    let target = ledger_info_with_sigs.ledger_info();
+    let verifier = match anchor {
+        TrustAnchor::Waypoint(waypoint) => {
+            if target.version() == waypoint.version() {
+                waypoint.verify(target)?;
+                return Ok(vec![]);
+            }
+            VerifierType::Waypoint(*waypoint)
+        }
+        TrustAnchor::LedgerInfo(trusted) => {
+            if trusted == ledger_info_with_sigs {
+                return Ok(vec![]);
+            }
+            let trusted = trusted.ledger_info();
+            let validator_set = trusted.next_validator_set().ok_or_else(|| {
+                format_err!("Trusted ledger info doesn't end an epoch, it can only verify itself.")
+            })?;
+            VerifierType::TrustedVerifier(EpochInfo {
+                epoch: trusted.epoch() + 1,
+                verifier: Arc::new(validator_set.into()),
+            })
+        }
+    };
+
+    // Only the ledger infos between the anchor and the target are needed to reach the target.
+    let chain: Vec<_> = epoch_change_ledger_infos
+        .iter()
+        .filter(|li| {
+            li.ledger_info().epoch() < target.epoch()
+                && !verifier.is_ledger_info_stale(li.ledger_info())
+        })
+        .cloned()
+        .collect();
+    let verifier = if chain.is_empty() {
+        verifier
+    } else {
+        let proof = ValidatorChangeProof::new(chain.clone(), /* more = */ false);
+        let epoch_change_li = proof.verify(&verifier)?.ledger_info();
+        let validator_set = epoch_change_li
+            .next_validator_set()
+            .ok_or_else(|| format_err!("Epoch change ledger info must carry a validator set."))?;
+        VerifierType::TrustedVerifier(EpochInfo {
+            epoch: epoch_change_li.epoch() + 1,
+            verifier: Arc::new(validator_set.into()),
+        })
+    };
+    verifier.verify(ledger_info_with_sigs)?;
+
+    Ok(chain)
+}
+
+/// Reads all epoch change ledger infos recorded in the manifest.
+pub fn read_epoch_change_ledger_infos(
+    adapter: &impl Adapter,
+    manifest: &BackupManifest,
+) -> Result<Vec<LedgerInfoWithSignatures>> {
+    let mut ret = vec![];
+    for backup in &manifest.epoch_changes {
+        for file in &backup.ledger_infos {
+            let chunk: Vec<LedgerInfoWithSignatures> = read_lcs(adapter, file)?;
+            ret.extend(chunk);
+        }
+    }
+    Ok(ret)
+}
+
+/// Restores all transaction ranges in the manifest together with the state snapshot at
+/// `snapshot_version`, or the latest one if `snapshot_version` is `None`. The snapshot must be
+/// covered by the transactions, so that the restored DB has the state its history ends up in.
+///
+/// Nothing is written before the whole history and the snapshot root hash are verified. The ledger
+/// infos are written last, so a restore that fails or crashes half way never leaves a DB that can
+/// be opened with history but no state.
+pub fn restore(
+    db: &LibraDB,
+    adapter: &(impl Adapter + Sync),
+    manifest: &BackupManifest,
+    anchor: &TrustAnchor,
+    snapshot_version: Option<Version>,
+    concurrency: usize,
+    rate_limiter: &RateLimiter,
+) -> Result<()> {
+    manifest.verify()?;
+    let last_range = manifest
+        .transaction_ranges
+        .last()
+        .ok_or_else(|| format_err!("No transactions in the manifest."))?;
+    let snapshot = match snapshot_version {
+        Some(version) => manifest
+            .state_snapshots
+            .iter()
+            .find(|snapshot| snapshot.version == version),
+        None => manifest.state_snapshots.last(),
+    }
+    .ok_or_else(|| format_err!("State snapshot not found in the manifest."))?;
+    ensure!(
+        snapshot.version <= last_range.last_version,
+        "State snapshot at version {} is not covered by the transactions, which end at version {}.",
+        snapshot.version,
+        last_range.last_version,
+    );
+
+    let epoch_change_ledger_infos = read_epoch_change_ledger_infos(adapter, manifest)?;
+    let target_li: LedgerInfoWithSignatures = read_lcs(adapter, &last_range.ledger_info)?;
+    let mut ledger_infos = verify_ledger_info(anchor, &epoch_change_ledger_infos, &target_li)?;
+    println!(
+        "Verified ledger info at version {}.",
+        target_li.ledger_info().version()
+    );
+
+    let range_lis = verify_transaction_ranges(adapter, manifest, &target_li)?;
+    println!(
+        "Verified transactions up to version {}.",
+        last_range.last_version
+    );
+
+    let root_hash = verify_state_snapshot(adapter, snapshot, anchor, &epoch_change_ledger_infos)?;
+    println!(
+        "Verified state root hash {:x} at version {}.",
+        root_hash, snapshot.version
+    );
+
+    restore_transactions(db, adapter, manifest, &range_lis, &target_li)?;
+    restore_account_state(db, adapter, snapshot, root_hash, concurrency, rate_limiter)?;
+    println!("Restored account state at version {}.", snapshot.version);
+
+    ledger_infos.push(target_li);
+    db.restore_ledger_infos(&ledger_infos)
+}
+
+/// Verifies the ledger infos and transaction chunks of all ranges in the manifest against the
+/// already verified `target_li`, without writing anything. Returns the ledger info of each range.
+///
+/// The transaction infos of all chunks are accumulated in memory. The ledger info of every range
+/// must match the accumulator at the end of its range, and the final accumulator must match
+/// `target_li`, which verifies every range ledger info and thus every chunk.
+fn verify_transaction_ranges(
+    adapter: &impl Adapter,
+    manifest: &BackupManifest,
+    target_li: &LedgerInfoWithSignatures,
+) -> Result<Vec<LedgerInfoWithSignatures>> {
+    let mut accumulator = InMemoryAccumulator::<TransactionAccumulatorHasher>::default();
+    let mut range_lis = vec![];
+    for range in &manifest.transaction_ranges {
+        let range_li: LedgerInfoWithSignatures = read_lcs(adapter, &range.ledger_info)?;
+        ensure!(
+            range_li.ledger_info().version() == range.last_version,
+            "Ledger info version {} doesn't match the end of range [{}, {}].",
+            range_li.ledger_info().version(),
+            range.first_version,
+            range.last_version,
+        );
+
+        let mut next_version = range.first_version;
+        for file in &range.transactions {
+            let txn_list_with_proof: TransactionListWithProof = read_lcs(adapter, file)?;
+            txn_list_with_proof.verify(range_li.ledger_info(), Some(next_version))?;
+            let txn_info_hashes: Vec<_> = txn_list_with_proof
+                .proof
+                .transaction_infos()
+                .iter()
+                .map(CryptoHash::hash)
+                .collect();
+            accumulator = accumulator.append(&txn_info_hashes);
+            next_version += txn_list_with_proof.len() as u64;
+        }
+        ensure!(
+            next_version == range.last_version + 1,
+            "Transaction chunks don't cover range [{}, {}].",
+            range.first_version,
+            range.last_version,
+        );
+        ensure!(
+            accumulator.root_hash() == range_li.ledger_info().transaction_accumulator_hash(),
+            "Ledger info of range [{}, {}] doesn't match its transactions.",
+            range.first_version,
+            range.last_version,
+        );
+        range_lis.push(range_li);
+    }
+
+    ensure!(
+        accumulator.root_hash() == target_li.ledger_info().transaction_accumulator_hash(),
+        "Transaction accumulator root hash doesn't match the verified ledger info.",
+    );
+    Ok(range_lis)
+}
+
+/// Writes the transactions of all ranges. Chunks are read again and verified against their range
+/// ledger info, which `verify_transaction_ranges` has verified. No ledger info is written.
+fn restore_transactions(
+    db: &LibraDB,
+    adapter: &impl Adapter,
+    manifest: &BackupManifest,
+    range_lis: &[LedgerInfoWithSignatures],
+    target_li: &LedgerInfoWithSignatures,
+) -> Result<()> {
+    let mut root_hash = None;
+    for (range, range_li) in manifest.transaction_ranges.iter().zip(range_lis.iter()) {
+        let mut next_version = range.first_version;
+        for file in &range.transactions {
+            let txn_list_with_proof: TransactionListWithProof = read_lcs(adapter, file)?;
+            // The file is read again, so it's verified again against the trusted ledger info.
+            txn_list_with_proof.verify(range_li.ledger_info(), Some(next_version))?;
+
+            let num_txns = txn_list_with_proof.len() as u64;
+            let TransactionListWithProof {
+                transactions,
+                events,
+                proof,
+                ..
+            } = txn_list_with_proof;
+            let events = events.ok_or_else(|| format_err!("Transaction chunk without events."))?;
+            root_hash = Some(db.restore_transactions(
+                next_version,
+                &transactions,
+                proof.transaction_infos(),
+                &events,
+            )?);
+            println!(
+                "Restored transactions [{}, {}).",
+                next_version,
+                next_version + num_txns
+            );
+            next_version += num_txns;
+        }
+        ensure!(
+            next_version == range.last_version + 1,
+            "Transaction chunks don't cover range [{}, {}].",
+            range.first_version,
+            range.last_version,
+        );
+    }
+
+    ensure!(
+        root_hash == Some(target_li.ledger_info().transaction_accumulator_hash()),
+        "Transaction accumulator root hash doesn't match the verified ledger info.",
+    );
+    Ok(())
+}
+
+/// Returns the state root hash of `snapshot`. It's not taken from the manifest but from a
+/// `TransactionInfo` proven against a ledger info that's verified against `anchor`.
+fn verify_state_snapshot(
+    adapter: &impl Adapter,
+    snapshot: &StateSnapshotBackup,
+    anchor: &TrustAnchor,
+    epoch_change_ledger_infos: &[LedgerInfoWithSignatures],
+) -> Result<HashValue> {
+    let ledger_info_with_sigs: LedgerInfoWithSignatures = read_lcs(adapter, &snapshot.ledger_info)?;
+    verify_ledger_info(anchor, epoch_change_ledger_infos, &ledger_info_with_sigs)?;
+
+    let txn_list_with_proof: TransactionListWithProof =
+        read_lcs(adapter, &snapshot.transaction_info)?;
+    txn_list_with_proof.verify(ledger_info_with_sigs.ledger_info(), Some(snapshot.version))?;
+    let root_hash = txn_list_with_proof
+        .proof
+        .transaction_infos()
+        .first()
+        .ok_or_else(|| format_err!("Missing TransactionInfo at version {}.", snapshot.version))?
+        .state_root_hash();
+    ensure!(
+        root_hash == snapshot.root_hash,
+        "State root hash in the manifest ({:x}) doesn't match the verified one ({:x}).",
+        snapshot.root_hash,
+        root_hash,
+    );
+    Ok(root_hash)
+}
+
+/// Adds the account states of `snapshot` to the DB, checking each chunk against the verified
+/// `root_hash` as it's added.
+///
+/// Up to `concurrency` chunks and their proofs are read and decoded in parallel, each file read
+/// counting as one operation for `rate_limiter`. Only the reads are concurrent: chunks are still
+/// added to the tree one by one in key order, which is how `JellyfishMerkleRestore` builds it.
+fn restore_account_state(
+    db: &LibraDB,
+    adapter: &(impl Adapter + Sync),
+    snapshot: &StateSnapshotBackup,
+    root_hash: HashValue,
+    concurrency: usize,
+    rate_limiter: &RateLimiter,
+) -> Result<()> {
+    let chunk_and_proofs = snapshot
+        .chunks
+        .chunks(std::cmp::max(concurrency, 1))
+        .flat_map(|batch| {
+            batch
+                .par_iter()
+                .map(|(account_state_file, proof_file)| {
+                    let chunk =
+                        read_account_state_chunk(adapter, account_state_file, rate_limiter)?;
+                    let proof_bytes = read_file(adapter, proof_file)?;
+                    rate_limiter.acquire_blocking(proof_bytes.len() as u64);
+                    let proof: SparseMerkleRangeProof = lcs::from_bytes(&proof_bytes)?;
+                    Ok((chunk, proof))
+                })
+                .collect::<Vec<_>>()
+        });
+    db.restore_account_state(chunk_and_proofs, snapshot.version, root_hash)
+}
+
+fn read_account_state_chunk(
+    adapter: &impl Adapter,
+    file: &BackupFile,
+    rate_limiter: &RateLimiter,
+) -> Result<Vec<(HashValue, AccountStateBlob)>> {
+    let content = read_file(adapter, file)?;
+    rate_limiter.acquire_blocking(content.len() as u64);
+
+    let mut chunk = vec![];
+    let mut reader = std::io::Cursor::new(content);
+    loop {
+        let mut buf = [0u8; HashValue::LENGTH];
+        if reader.read_exact(&mut buf).is_err() {
+            break;
+        }
+        let key = HashValue::new(buf);
+
+        let len = reader.read_u32::<LittleEndian>()?;
+        let mut buf = vec![0u8; len as usize];
+        reader.read_exact(&mut buf)?;
+        let blob = AccountStateBlob::from(buf);
+
+        chunk.push((key, blob));
+    }
+
+    Ok(chunk)
+}
+
+fn read_lcs<T: DeserializeOwned>(adapter: &impl Adapter, file: &BackupFile) -> Result<T> {
+    let content = read_file(adapter, file)?;
+    Ok(lcs::from_bytes(&content)?)
+}
+
+/// Reads the whole file and checks it against the checksum recorded in the manifest, so nothing
+/// tries to decode a corrupted file.
+fn read_file(adapter: &impl Adapter, file: &BackupFile) -> Result<Vec<u8>> {
+    let mut content = vec![];
+    for bytes_res in block_on_stream(adapter.read_file_content(&file.handle)) {
+        content.extend(bytes_res?);
+    }
+    let checksum = HashValue::from_sha3_256(&content);
+    ensure!(
+        checksum == file.checksum,
+        "Checksum mismatch for file {}: expected {:x}, got {:x}. The backup is corrupted.",
+        file.handle,
+        file.checksum,
+        checksum,
+    );
+    Ok(content)
+}
@@ -0,0 +1,116 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{adapter::local_storage::LocalStorage, write_file};
+use futures::executor::block_on;
+use libra_crypto::hash::CryptoHash;
+use libra_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfo, validator_info::ValidatorInfo,
+    validator_set::ValidatorSet, validator_signer::ValidatorSigner,
+};
+
+fn new_signers(seed: u8, num: usize) -> Vec<ValidatorSigner> {
+    (0..num)
+        .map(|i| ValidatorSigner::random([seed * 16 + i as u8; 32]))
+        .collect()
+}
+
+fn to_validator_set(signers: &[ValidatorSigner]) -> ValidatorSet {
+    ValidatorSet::new(
+        signers
+            .iter()
+            .map(|signer| {
+                ValidatorInfo::new_with_random_network_keys(
+                    signer.author(),
+                    signer.public_key(),
+                    1, /* voting power */
+                )
+            })
+            .collect(),
+    )
+}
+
+fn new_ledger_info(
+    epoch: u64,
+    version: Version,
+    next_validator_set: Option<ValidatorSet>,
+    signers: &[ValidatorSigner],
+) -> LedgerInfoWithSignatures {
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            epoch,
+            0,                 /* round */
+            HashValue::zero(), /* id */
+            HashValue::zero(), /* executed_state_id */
+            version,
+            0, /* timestamp_usecs */
+            next_validator_set,
+        ),
+        HashValue::zero(),
+    );
+    let signatures = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.sign_message(ledger_info.hash())))
+        .collect();
+    LedgerInfoWithSignatures::new(ledger_info, signatures)
+}
+
+#[test]
+fn test_verify_ledger_info() {
+    let signers0 = new_signers(0, 1);
+    let signers1 = new_signers(1, 2);
+    let signers2 = new_signers(2, 3);
+
+    let genesis_li = new_ledger_info(0, 0, Some(to_validator_set(&signers1)), &signers0);
+    let epoch1_li = new_ledger_info(1, 5, Some(to_validator_set(&signers2)), &signers1);
+    let target_li = new_ledger_info(2, 10, None, &signers2);
+    let epoch_change_lis = vec![genesis_li.clone(), epoch1_li.clone()];
+
+    let waypoint = TrustAnchor::Waypoint(Waypoint::new(genesis_li.ledger_info()).unwrap());
+    assert_eq!(
+        verify_ledger_info(&waypoint, &epoch_change_lis, &target_li).unwrap(),
+        epoch_change_lis
+    );
+    assert!(verify_ledger_info(&waypoint, &[], &genesis_li)
+        .unwrap()
+        .is_empty());
+    // The chain is broken.
+    assert!(verify_ledger_info(&waypoint, &[genesis_li.clone()], &target_li).is_err());
+
+    // Trusting the ledger info ending epoch 1 skips everything before it.
+    let trusted = TrustAnchor::LedgerInfo(epoch1_li.clone());
+    assert!(verify_ledger_info(&trusted, &epoch_change_lis, &target_li)
+        .unwrap()
+        .is_empty());
+    // A ledger info that doesn't end an epoch can only verify itself.
+    let trusted = TrustAnchor::LedgerInfo(target_li.clone());
+    assert!(verify_ledger_info(&trusted, &epoch_change_lis, &target_li).is_ok());
+    assert!(verify_ledger_info(&trusted, &epoch_change_lis, &epoch1_li).is_err());
+
+    // Tampered ledger infos don't pass.
+    let forged_li = new_ledger_info(2, 10, None, &signers1);
+    assert!(verify_ledger_info(&waypoint, &epoch_change_lis, &forged_li).is_err());
+    let forged_epoch1_li = new_ledger_info(1, 5, Some(to_validator_set(&signers1)), &signers2);
+    let forged_target_li = new_ledger_info(2, 10, None, &signers1);
+    assert!(verify_ledger_info(
+        &waypoint,
+        &[genesis_li, forged_epoch1_li],
+        &forged_target_li
+    )
+    .is_err());
+}
+
+#[test]
+fn test_read_file_checksum_mismatch() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let adapter = LocalStorage::new(tmpdir.path().to_path_buf());
+
+    let file = block_on(write_file(&adapter, vec![1u8; 100])).unwrap();
+    assert_eq!(read_file(&adapter, &file).unwrap(), vec![1u8; 100]);
+
+    // The file is replaced with something else of the same size.
+    std::fs::write(&file.handle, vec![2u8; 100]).unwrap();
+    let err = read_file(&adapter, &file).unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"));
+}
@@ -3,19 +3,34 @@
 
 #![forbid(unsafe_code)]
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
+use libra_crypto::hash::{CryptoHash, TransactionAccumulatorHasher};
 use libra_logger::info;
 use libradb::LibraDB;
-use std::path::PathBuf;
+use serde_json::json;
+use std::{
+    fs::File,
+    io::{stdout, BufWriter, Write},
+    path::PathBuf,
+};
 use storage_interface::DbReader;
 use transaction_builder::get_transaction_name;
 
 use libra_types::{
-    account_address::AccountAddress, account_config::AccountResource, account_state::AccountState,
+    account_address::AccountAddress,
+    account_config::{
+        AccountResource, BalanceResource, ACCOUNT_RESOURCE_PATH, BALANCE_RESOURCE_PATH,
+    },
+    account_state::AccountState,
+    proof::accumulator::InMemoryAccumulator,
+    transaction::Version,
 };
 use std::convert::TryFrom;
 use structopt::StructOpt;
 
+/// The most transactions `LibraDB::get_transactions()` returns in one call.
+const MAX_BATCH_SIZE: u64 = 1000;
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     #[structopt(long, parse(from_os_str))]
@@ -38,6 +53,27 @@ enum Command {
     },
     #[structopt(name = "list-accounts")]
     ListAccounts,
+    /// Checks all readable transactions and events against their transaction infos, the
+    /// transaction infos against the transaction accumulator and the state tree.
+    #[structopt(name = "verify")]
+    Verify,
+    /// Dumps transactions and their events to JSON lines.
+    #[structopt(name = "export-range")]
+    ExportRange {
+        start_version: u64,
+        num_transactions: u64,
+        /// File to write to, stdout if not specified.
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+    /// Compares the resources of an account between two versions.
+    #[structopt(name = "diff-account")]
+    DiffAccount {
+        #[structopt(parse(try_from_str))]
+        address: AccountAddress,
+        old_version: u64,
+        new_version: u64,
+    },
 }
 
 /// Print out latest information stored in the DB.
@@ -154,6 +190,241 @@ fn list_accounts(db: &LibraDB) {
     info!("Total Accounts: {}", num_account);
 }
 
+/// Recomputes the transaction accumulator root and checks transactions, events and state roots
+/// against the stored transaction infos. Every inconsistency found is printed, and an error is
+/// returned at the end if there was any.
+fn verify(db: &LibraDB) -> Result<()> {
+    let ledger_info_with_sigs = db.get_latest_ledger_info()?;
+    let ledger_info = ledger_info_with_sigs.ledger_info();
+    let latest_version = ledger_info.version();
+    let start_version = db.get_least_readable_version()?;
+    let least_readable_state_version = db.get_least_readable_state_version();
+    let backup = db.get_backup_handler();
+    info!(
+        "Verifying versions [{}, {}] against ledger info {}",
+        start_version, latest_version, ledger_info
+    );
+
+    let mut accumulator = InMemoryAccumulator::<TransactionAccumulatorHasher>::default();
+    // Whether every transaction info since genesis made it into `accumulator`.
+    let mut accumulator_complete = start_version == 0;
+    let mut num_errors = 0;
+    let mut num_pruned_state_roots = 0;
+    let mut version = start_version;
+    while version <= latest_version {
+        let limit = std::cmp::min(MAX_BATCH_SIZE, latest_version - version + 1);
+        let txn_list =
+            match db.get_transactions(version, limit, latest_version, true /* fetch_events */) {
+                Ok(txn_list) => txn_list,
+                Err(e) => {
+                    println!(
+                        "Failed to read transactions [{}, {}): {}",
+                        version,
+                        version + limit,
+                        e
+                    );
+                    num_errors += 1;
+                    accumulator_complete = false;
+                    version += limit;
+                    continue;
+                }
+            };
+        // Checks transactions and events against the transaction infos, and the transaction
+        // infos against the accumulator root in the ledger info.
+        if let Err(e) = txn_list.verify(ledger_info, Some(version)) {
+            println!(
+                "Transactions [{}, {}) failed verification: {}",
+                version,
+                version + limit,
+                e
+            );
+            num_errors += 1;
+        }
+
+        let txn_infos = txn_list.proof.transaction_infos();
+        let txn_info_hashes: Vec<_> = txn_infos.iter().map(CryptoHash::hash).collect();
+        accumulator = accumulator.append(&txn_info_hashes);
+        for (txn_info, v) in txn_infos.iter().zip(version..) {
+            match backup.get_state_root_hash(v) {
+                Ok(Some(root_hash)) if root_hash != txn_info.state_root_hash() => {
+                    println!(
+                        "State root mismatch at version {}: transaction info has {}, state tree \
+                         has {}.",
+                        v,
+                        txn_info.state_root_hash(),
+                        root_hash
+                    );
+                    num_errors += 1;
+                }
+                Ok(Some(_)) => (),
+                Ok(None) if v < least_readable_state_version => num_pruned_state_roots += 1,
+                Ok(None) => {
+                    println!("State root missing at version {}.", v);
+                    num_errors += 1;
+                }
+                Err(e) => {
+                    println!("Failed to read state root at version {}: {}", v, e);
+                    num_errors += 1;
+                }
+            }
+        }
+        version += limit;
+    }
+
+    if accumulator_complete {
+        if accumulator.root_hash() != ledger_info.transaction_accumulator_hash() {
+            println!(
+                "Transaction accumulator root mismatch: recomputed {}, ledger info has {}.",
+                accumulator.root_hash(),
+                ledger_info.transaction_accumulator_hash()
+            );
+            num_errors += 1;
+        }
+    } else if start_version > 0 {
+        info!(
+            "Versions before {} are pruned, skipped recomputing the accumulator root.",
+            start_version
+        );
+    } else {
+        info!("Some transactions are unreadable, skipped recomputing the accumulator root.");
+    }
+    if num_pruned_state_roots > 0 {
+        info!(
+            "State trees before version {} are pruned, skipped checking {} state roots.",
+            least_readable_state_version, num_pruned_state_roots
+        );
+    }
+
+    ensure!(num_errors == 0, "{} inconsistencies found.", num_errors);
+    println!("Verified versions [{}, {}].", start_version, latest_version);
+    Ok(())
+}
+
+/// Writes each transaction in the range together with its info and events as a JSON line.
+fn export_range(
+    db: &LibraDB,
+    start_version: Version,
+    num_transactions: u64,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(stdout()),
+    };
+    let latest_version = db.get_latest_version()?;
+    let end_version = std::cmp::min(
+        start_version.saturating_add(num_transactions),
+        latest_version + 1,
+    );
+
+    let mut version = start_version;
+    while version < end_version {
+        let limit = std::cmp::min(MAX_BATCH_SIZE, end_version - version);
+        let txn_list =
+            db.get_transactions(version, limit, latest_version, true /* fetch_events */)?;
+        let num_txns = txn_list.transactions.len();
+        let events = txn_list.events.unwrap_or_else(|| vec![vec![]; num_txns]);
+        for (((txn, txn_info), events), v) in txn_list
+            .transactions
+            .iter()
+            .zip(txn_list.proof.transaction_infos())
+            .zip(events)
+            .zip(version..)
+        {
+            let line = json!({
+                "version": v,
+                "transaction": txn,
+                "transaction_info": txn_info,
+                "events": events,
+            });
+            writeln!(writer, "{}", line)?;
+        }
+        version += limit;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns a readable name for a resource path if it's well known.
+fn resource_name(path: &[u8]) -> String {
+    if path == ACCOUNT_RESOURCE_PATH.as_slice() {
+        "AccountResource".to_string()
+    } else if path == BALANCE_RESOURCE_PATH.as_slice() {
+        "BalanceResource".to_string()
+    } else {
+        hex::encode(path)
+    }
+}
+
+/// Decodes a resource for printing if its type is known, otherwise prints the raw bytes.
+fn format_resource(path: &[u8], blob: &[u8]) -> String {
+    let decoded = if path == ACCOUNT_RESOURCE_PATH.as_slice() {
+        lcs::from_bytes::<AccountResource>(blob).map(|r| format!("{:?}", r))
+    } else if path == BALANCE_RESOURCE_PATH.as_slice() {
+        lcs::from_bytes::<BalanceResource>(blob).map(|r| format!("{:?}", r))
+    } else {
+        return hex::encode(blob);
+    };
+    decoded.unwrap_or_else(|e| format!("{} (decoding failed: {})", hex::encode(blob), e))
+}
+
+fn get_account_state(
+    db: &LibraDB,
+    address: AccountAddress,
+    version: Version,
+) -> Result<AccountState> {
+    let (blob, _proof) = db.get_account_state_with_proof_by_version(address, version)?;
+    match blob {
+        Some(blob) => AccountState::try_from(&blob),
+        None => Ok(AccountState::default()),
+    }
+}
+
+/// Prints resources added, removed or changed between `old_version` and `new_version`.
+fn diff_account(
+    db: &LibraDB,
+    address: AccountAddress,
+    old_version: Version,
+    new_version: Version,
+) -> Result<()> {
+    let old_state = get_account_state(db, address, old_version)?;
+    let new_state = get_account_state(db, address, new_version)?;
+
+    let mut num_diffs = 0;
+    for (path, old_blob) in old_state.iter() {
+        match new_state.get(path) {
+            None => println!(
+                "- {}: {}",
+                resource_name(path),
+                format_resource(path, old_blob)
+            ),
+            Some(new_blob) if new_blob != old_blob => println!(
+                "~ {}: {} => {}",
+                resource_name(path),
+                format_resource(path, old_blob),
+                format_resource(path, new_blob)
+            ),
+            Some(_) => continue,
+        }
+        num_diffs += 1;
+    }
+    for (path, new_blob) in new_state.iter() {
+        if old_state.get(path).is_none() {
+            println!(
+                "+ {}: {}",
+                resource_name(path),
+                format_resource(path, new_blob)
+            );
+            num_diffs += 1;
+        }
+    }
+    println!(
+        "Account {}: {} resources differ between versions {} and {}.",
+        address, num_diffs, old_version, new_version
+    );
+    Ok(())
+}
+
 fn main() {
     ::libra_logger::Logger::new().init();
 
@@ -187,6 +458,25 @@ fn main() {
             Command::ListAccounts => {
                 list_accounts(&db);
             }
+            Command::Verify => {
+                verify(&db).expect("Verification failed");
+            }
+            Command::ExportRange {
+                start_version,
+                num_transactions,
+                output,
+            } => {
+                export_range(&db, start_version, num_transactions, output)
+                    .expect("Unable to export transactions");
+            }
+            Command::DiffAccount {
+                address,
+                old_version,
+                new_version,
+            } => {
+                diff_account(&db, address, old_version, new_version)
+                    .expect("Unable to diff account");
+            }
         }
     } else {
         print_head(&db).expect("Unable to read information from DB");
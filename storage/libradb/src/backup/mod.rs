@@ -62,14 +62,27 @@ impl BackupHandler {
         &self,
         version: Version,
     ) -> Result<Box<dyn Iterator<Item = Result<(HashValue, AccountStateBlob)>> + Send>> {
-        let iterator = JellyfishMerkleIterator::new(
-            Arc::clone(&self.state_store),
-            version,
-            HashValue::zero(),
-        )?;
+        self.get_account_iter_from(version, HashValue::zero())
+    }
+
+    /// Gets an iterator which yields the accounts in the state tree with keys greater than or
+    /// equal to `start_key`.
+    pub fn get_account_iter_from(
+        &self,
+        version: Version,
+        start_key: HashValue,
+    ) -> Result<Box<dyn Iterator<Item = Result<(HashValue, AccountStateBlob)>> + Send>> {
+        let iterator =
+            JellyfishMerkleIterator::new(Arc::clone(&self.state_store), version, start_key)?;
         Ok(Box::new(iterator))
     }
 
+    /// Gets the root hash of the state tree at `version`, or `None` if the tree at that version
+    /// doesn't exist, e.g. having been pruned.
+    pub fn get_state_root_hash(&self, version: Version) -> Result<Option<HashValue>> {
+        self.state_store.get_root_hash_option(version)
+    }
+
     /// Gets the proof that proves a range of accounts.
     pub fn get_account_state_range_proof(
         &self,
@@ -5,7 +5,7 @@ use crate::{test_helper::arb_blocks_to_commit, LibraDB};
 use anyhow::Result;
 use libra_temppath::TempPath;
 use proptest::prelude::*;
-use storage_interface::DbWriter;
+use storage_interface::{DbReader, DbWriter};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -40,4 +40,48 @@ proptest! {
             .unwrap();
         prop_assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_restore_transactions(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new(&tmp_dir);
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+            db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as u64;
+        }
+        let ledger_version = cur_ver - 1;
+        let txn_list_with_proof = db
+            .get_transactions(0, cur_ver, ledger_version, /* fetch_events = */ true)
+            .unwrap();
+
+        let tmp_dir2 = TempPath::new();
+        let db2 = LibraDB::new(&tmp_dir2);
+        let root_hash = db2
+            .restore_transactions(
+                0,
+                &txn_list_with_proof.transactions,
+                txn_list_with_proof.proof.transaction_infos(),
+                txn_list_with_proof.events.as_ref().unwrap(),
+            )
+            .unwrap();
+        let ledger_infos: Vec<_> = input
+            .iter()
+            .map(|(_txns_to_commit, ledger_info_with_sigs)| ledger_info_with_sigs.clone())
+            .collect();
+        db2.restore_ledger_infos(&ledger_infos).unwrap();
+
+        let latest_ledger_info = db.get_latest_ledger_info().unwrap();
+        prop_assert_eq!(
+            root_hash,
+            latest_ledger_info.ledger_info().transaction_accumulator_hash()
+        );
+        prop_assert_eq!(db2.get_latest_ledger_info().unwrap(), latest_ledger_info);
+        prop_assert_eq!(
+            db2.get_transactions(0, cur_ver, ledger_version, true).unwrap(),
+            txn_list_with_proof
+        );
+    }
 }
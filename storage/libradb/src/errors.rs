@@ -11,6 +11,9 @@ pub enum LibraDbError {
     /// A requested item is not found.
     #[error("{0} not found.")]
     NotFound(String),
+    /// A requested item has been removed by the pruner.
+    #[error("{0} has been pruned.")]
+    Pruned(String),
     /// Requested too many items.
     #[error("Too many items requested: at least {0} requested, max is {1}")]
     TooManyRequested(u64, u64),
@@ -86,12 +86,13 @@ impl EventStore {
         Ok((event, proof))
     }
 
-    fn get_txn_ver_by_seq_num(&self, event_key: &EventKey, seq_num: u64) -> Result<u64> {
-        let (ver, _) = self
+    /// Returns `None` if the index entry has been pruned, which means the event is older than any
+    /// readable version.
+    fn get_txn_ver_by_seq_num(&self, event_key: &EventKey, seq_num: u64) -> Result<Option<u64>> {
+        Ok(self
             .db
             .get::<EventByKeySchema>(&(*event_key, seq_num))?
-            .ok_or_else(|| format_err!("Index entry should exist for seq_num {}", seq_num))?;
-        Ok(ver)
+            .map(|(ver, _)| ver))
     }
 
     /// Get the latest sequence number on `event_key` considering all transactions with versions
@@ -120,7 +121,7 @@ impl EventStore {
                     seq -= 1;
                     n_try_recent -= 1;
                     let ver = self.get_txn_ver_by_seq_num(event_key, seq)?;
-                    if ver <= ledger_version {
+                    if ver.map_or(true, |ver| ver <= ledger_version) {
                         return Ok(Some(seq));
                     }
                 }
@@ -130,7 +131,7 @@ impl EventStore {
                 while begin < end {
                     let mid = end - (end - begin) / 2;
                     let ver = self.get_txn_ver_by_seq_num(event_key, mid)?;
-                    if ver <= ledger_version {
+                    if ver.map_or(true, |ver| ver <= ledger_version) {
                         begin = mid;
                     } else {
                         end = mid - 1;
@@ -168,6 +169,14 @@ impl EventStore {
             if path != *event_key || ver > ledger_version {
                 break;
             }
+            if result.is_empty() && seq > cur_seq {
+                // Sequence numbers start from 0, the ones before `seq` must have been pruned.
+                return Err(LibraDbError::Pruned(format!(
+                    "Event {} of key {}",
+                    cur_seq, event_key
+                ))
+                .into());
+            }
             ensure!(
                 seq == cur_seq,
                 "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
@@ -94,6 +94,15 @@ impl LedgerStore {
         })
     }
 
+    /// Returns the last version of `epoch`, or `None` if the epoch hasn't ended.
+    pub fn get_epoch_ending_version(&self, epoch: u64) -> Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<LedgerInfoSchema>(&epoch)?
+            .filter(|li| li.ledger_info().next_validator_set().is_some())
+            .map(|li| li.ledger_info().version()))
+    }
+
     /// Returns the ledger infos reflecting epoch bumps with their 2f+1 signatures in
     /// [`start_epoch`, `end_epoch`). If there is no more than `limit` results, this function
     /// returns all of them, otherwise the first `limit` results are returned and a flag
@@ -46,14 +46,15 @@ use crate::{
 };
 use anyhow::{ensure, Result};
 use itertools::{izip, zip_eq};
-use jellyfish_merkle::restore::JellyfishMerkleRestore;
+use jellyfish_merkle::{iterator::JellyfishMerkleIterator, restore::JellyfishMerkleRestore};
+use libra_config::config::LedgerPruningWindow;
 use libra_crypto::hash::{CryptoHash, HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use libra_logger::prelude::*;
 use libra_metrics::OpMetrics;
 use libra_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof, AccountStateWithProof},
     contract_event::{ContractEvent, EventWithProof},
     event::EventKey,
     get_with_proof::{RequestItem, ResponseItem},
@@ -63,15 +64,20 @@ use libra_types::{
         SparseMerkleRangeProof, TransactionListProof, TransactionProof,
     },
     transaction::{
-        TransactionInfo, TransactionListWithProof, TransactionToCommit, TransactionWithProof,
-        Version, PRE_GENESIS_VERSION,
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionToCommit,
+        TransactionWithProof, Version, PRE_GENESIS_VERSION,
     },
     validator_change::ValidatorChangeProof,
 };
 use once_cell::sync::Lazy;
 use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 use schemadb::{ColumnFamilyOptions, ColumnFamilyOptionsMap, DB, DEFAULT_CF_NAME};
-use std::{iter::Iterator, path::Path, sync::Arc, time::Instant};
+use std::{
+    iter::Iterator,
+    path::Path,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
 use storage_interface::{DbReader, DbWriter};
 use storage_proto::{StartupInfo, TreeState};
 
@@ -130,6 +136,7 @@ pub struct LibraDB {
     event_store: EventStore,
     system_store: SystemStore,
     pruner: Pruner,
+    ledger_pruning_window: RwLock<Option<LedgerPruningWindow>>,
 }
 
 impl LibraDB {
@@ -137,6 +144,16 @@ impl LibraDB {
     const NUM_HISTORICAL_VERSIONS_TO_KEEP: u64 = 1_000_000;
 
     pub fn open<P: AsRef<Path> + Clone>(db_root_path: P, readonly: bool) -> Result<Self> {
+        Self::open_with_ledger_pruning_window(db_root_path, readonly, None)
+    }
+
+    /// Opens the DB, pruning the ledger history outside of `ledger_pruning_window` as new
+    /// transactions are committed. Nothing is pruned if it's `None`.
+    pub fn open_with_ledger_pruning_window<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        readonly: bool,
+        ledger_pruning_window: Option<LedgerPruningWindow>,
+    ) -> Result<Self> {
         let cf_opts_map: ColumnFamilyOptionsMap = [
             (
                 /* LedgerInfo CF = */ DEFAULT_CF_NAME,
@@ -151,6 +168,7 @@ impl LibraDB {
                 ColumnFamilyOptions::default(),
             ),
             (LEDGER_COUNTERS_CF_NAME, ColumnFamilyOptions::default()),
+            (PRUNER_PROGRESS_CF_NAME, ColumnFamilyOptions::default()),
             (STALE_NODE_INDEX_CF_NAME, ColumnFamilyOptions::default()),
             (TRANSACTION_CF_NAME, ColumnFamilyOptions::default()),
             (
@@ -161,6 +179,7 @@ impl LibraDB {
                 TRANSACTION_BY_ACCOUNT_CF_NAME,
                 ColumnFamilyOptions::default(),
             ),
+            (TRANSACTION_BY_HASH_CF_NAME, ColumnFamilyOptions::default()),
             (TRANSACTION_INFO_CF_NAME, ColumnFamilyOptions::default()),
         ]
         .iter()
@@ -190,6 +209,7 @@ impl LibraDB {
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&db))),
             system_store: SystemStore::new(Arc::clone(&db)),
             pruner: Pruner::new(Arc::clone(&db), Self::NUM_HISTORICAL_VERSIONS_TO_KEEP),
+            ledger_pruning_window: RwLock::new(ledger_pruning_window),
         })
     }
 
@@ -200,6 +220,39 @@ impl LibraDB {
 
     // ================================== Public API ==================================
 
+    /// Returns how much ledger history is kept currently, `None` meaning everything.
+    pub fn ledger_pruning_window(&self) -> Option<LedgerPruningWindow> {
+        *self
+            .ledger_pruning_window
+            .read()
+            .expect("ledger_pruning_window should lock.")
+    }
+
+    /// Returns the oldest version whose state tree is readable. Older state trees are pruned.
+    pub fn get_least_readable_state_version(&self) -> Version {
+        self.pruner.least_readable_state_version()
+    }
+
+    /// Changes how much ledger history to keep, taking effect immediately. Note that enlarging the
+    /// window doesn't bring back what's already pruned.
+    pub fn set_ledger_pruning_window(
+        &self,
+        ledger_pruning_window: Option<LedgerPruningWindow>,
+    ) -> Result<()> {
+        *self
+            .ledger_pruning_window
+            .write()
+            .expect("ledger_pruning_window should lock.") = ledger_pruning_window;
+        info!("Ledger pruning window set to {:?}.", ledger_pruning_window);
+
+        if let Some((latest_version, _)) = self.ledger_store.get_latest_transaction_info_option()? {
+            if let Some(least_readable_version) = self.get_ledger_pruning_target(latest_version)? {
+                self.pruner.wake_ledger(least_readable_version);
+            }
+        }
+        Ok(())
+    }
+
     /// Returns ledger infos reflecting epoch bumps starting with the given epoch. If there are no
     /// more than `MAX_NUM_EPOCH_CHANGE_LEDGER_INFO` results, this function returns all of them,
     /// otherwise the first `MAX_NUM_EPOCH_CHANGE_LEDGER_INFO` results are returned and a flag
@@ -222,6 +275,7 @@ impl LibraDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionWithProof> {
+        self.error_if_ledger_pruned("Transaction", version)?;
         let proof = {
             let (txn_info, txn_info_accumulator_proof) = self
                 .ledger_store
@@ -417,20 +471,146 @@ impl LibraDB {
 
     pub fn restore_account_state(
         &self,
-        iter: impl Iterator<Item = (Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>,
+        iter: impl Iterator<Item = Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>>,
         version: Version,
         expected_root_hash: HashValue,
     ) -> Result<()> {
         let mut restore =
             JellyfishMerkleRestore::new(&*self.state_store, version, expected_root_hash)?;
-        for (chunk, proof) in iter {
+        for res in iter {
+            let (chunk, proof) = res?;
             restore.add_chunk(chunk, proof)?;
         }
         restore.finish()?;
         Ok(())
     }
 
+    /// Restores a chunk of consecutive transactions, together with their `TransactionInfo`s and
+    /// events, starting from `first_version`. Chunks are expected to be restored in order, i.e.
+    /// `first_version` must be the next version after what's already in the DB.
+    pub fn restore_transactions(
+        &self,
+        first_version: Version,
+        txns: &[Transaction],
+        txn_infos: &[TransactionInfo],
+        events: &[Vec<ContractEvent>],
+    ) -> Result<HashValue> {
+        let num_txns = txns.len() as u64;
+        ensure!(num_txns > 0, "Nothing to restore.");
+        ensure!(
+            txn_infos.len() == txns.len() && events.len() == txns.len(),
+            "Number of transactions ({}), transaction infos ({}) and event lists ({}) mismatch.",
+            txns.len(),
+            txn_infos.len(),
+            events.len(),
+        );
+        let next_version = self
+            .ledger_store
+            .get_latest_transaction_info_option()?
+            .map_or(0, |(version, _)| version + 1);
+        ensure!(
+            first_version == next_version,
+            "Transactions must be restored in order: expecting version {}, got {}.",
+            next_version,
+            first_version,
+        );
+
+        let mut cs = ChangeSet::new();
+        for (ver, txn, txn_info, txn_events) in izip!(
+            first_version..first_version + num_txns,
+            txns,
+            txn_infos,
+            events
+        ) {
+            ensure!(
+                txn.hash() == txn_info.transaction_hash(),
+                "Transaction hash mismatch at version {}.",
+                ver,
+            );
+            self.transaction_store.put_transaction(ver, txn, &mut cs)?;
+            let event_root_hash = self.event_store.put_events(ver, txn_events, &mut cs)?;
+            ensure!(
+                event_root_hash == txn_info.event_root_hash(),
+                "Event root hash mismatch at version {}.",
+                ver,
+            );
+        }
+        let new_root_hash =
+            self.ledger_store
+                .put_transaction_infos(first_version, txn_infos, &mut cs)?;
+
+        let (sealed_cs, _counters) = self.seal_change_set(first_version, num_txns, cs)?;
+        self.commit(sealed_cs)?;
+
+        Ok(new_root_hash)
+    }
+
+    /// Restores ledger infos, normally the ones that end each epoch plus the latest one that the
+    /// backup was taken against. The latest in-memory ledger info is updated if any of them is
+    /// newer.
+    pub fn restore_ledger_infos(&self, ledger_infos: &[LedgerInfoWithSignatures]) -> Result<()> {
+        let mut cs = ChangeSet::new();
+        for ledger_info_with_sigs in ledger_infos {
+            self.ledger_store
+                .put_ledger_info(ledger_info_with_sigs, &mut cs)?;
+        }
+        self.db.write_schemas(cs.batch)?;
+
+        if let Some(newest) = ledger_infos
+            .iter()
+            .max_by_key(|li| li.ledger_info().version())
+        {
+            let is_newer = self
+                .ledger_store
+                .get_latest_ledger_info_option()
+                .map_or(true, |latest| {
+                    latest.ledger_info().version() < newest.ledger_info().version()
+                });
+            if is_newer {
+                self.ledger_store.set_latest_ledger_info(newest.clone());
+            }
+        }
+
+        Ok(())
+    }
+
     // ================================== Private APIs ==================================
+    /// Returns the `Pruned` error if the ledger history at `version` has been pruned.
+    fn error_if_ledger_pruned(&self, data_type: &str, version: Version) -> Result<()> {
+        let least_readable_version = self.pruner.least_readable_ledger_version();
+        if version < least_readable_version {
+            Err(LibraDbError::Pruned(format!(
+                "{} at version {} (the oldest readable version is {})",
+                data_type, version, least_readable_version,
+            ))
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the oldest version the ledger history pruner should keep given the latest version,
+    /// or `None` if nothing should be pruned.
+    fn get_ledger_pruning_target(&self, latest_version: Version) -> Result<Option<Version>> {
+        Ok(match self.ledger_pruning_window() {
+            None => None,
+            Some(LedgerPruningWindow::Versions(num_versions)) => {
+                latest_version.checked_sub(num_versions)
+            }
+            Some(LedgerPruningWindow::Epochs(num_epochs)) => {
+                let epoch = self.ledger_store.get_epoch(latest_version)?;
+                if epoch > num_epochs {
+                    // Keep everything since the start of epoch `epoch - num_epochs`.
+                    self.ledger_store
+                        .get_epoch_ending_version(epoch - num_epochs - 1)?
+                        .map(|version| version + 1)
+                } else {
+                    None
+                }
+            }
+        })
+    }
+
     /// Returns events specified by `query_path` with sequence number in range designated by
     /// `start_seq_num`, `ascending` and `limit`. If ascending is true this query will return up to
     /// `limit` events that were emitted after `start_event_seq_num`. Otherwise, it will return up
@@ -518,6 +698,7 @@ impl LibraDB {
         let mut events_with_proof = event_keys
             .into_iter()
             .map(|(seq, ver, idx)| {
+                self.error_if_ledger_pruned("Event", ver)?;
                 let (event, event_proof) = self
                     .event_store
                     .get_event_with_proof_by_version_and_index(ver, idx)?;
@@ -663,7 +844,9 @@ impl DbReader for LibraDB {
     }
 
     /// Returns a transaction that is the `seq_num`-th one associated with the given account. If
-    /// the transaction with given `seq_num` doesn't exist, returns `None`.
+    /// the transaction with given `seq_num` doesn't exist or has been pruned together with its
+    /// index entry, returns `None`. If the index entry is there but points to a pruned version,
+    /// returns the `Pruned` error.
     fn get_txn_by_account(
         &self,
         address: AccountAddress,
@@ -677,6 +860,18 @@ impl DbReader for LibraDB {
             .transpose()
     }
 
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.transaction_store
+            .lookup_transaction_by_hash(hash, ledger_version)?
+            .map(|version| self.get_transaction_with_proof(version, ledger_version, fetch_events))
+            .transpose()
+    }
+
     // ======================= State Synchronizer Internal APIs ===================================
     /// Gets a batch of transactions for the purpose of synchronizing state to another node.
     ///
@@ -695,6 +890,7 @@ impl DbReader for LibraDB {
         }
 
         let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+        self.error_if_ledger_pruned("Transaction", start_version)?;
 
         let txns = (start_version..start_version + limit)
             .map(|version| Ok(self.transaction_store.get_transaction(version)?))
@@ -809,6 +1005,7 @@ impl DbReader for LibraDB {
             latest_version
         );
 
+        self.error_if_ledger_pruned("Transaction info", version)?;
         let (txn_info, txn_info_accumulator_proof) = self
             .ledger_store
             .get_transaction_info_with_proof(version, ledger_version)?;
@@ -839,6 +1036,36 @@ impl DbReader for LibraDB {
         let (version, txn_info) = self.ledger_store.get_latest_transaction_info()?;
         Ok((version, txn_info.state_root_hash()))
     }
+
+    fn get_account_states_in_range(
+        &self,
+        version: Version,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        let account_blobs =
+            JellyfishMerkleIterator::new(Arc::clone(&self.state_store), version, start_key)?
+                .take(limit as usize)
+                .collect::<Result<Vec<_>>>()?;
+        let proof = match account_blobs.last() {
+            Some((rightmost_key, _)) => self
+                .state_store
+                .get_account_state_range_proof(*rightmost_key, version)?,
+            None => SparseMerkleRangeProof::new(vec![]),
+        };
+
+        Ok(AccountStateChunkWithProof::new(
+            version,
+            account_blobs,
+            proof,
+        ))
+    }
+
+    fn get_least_readable_version(&self) -> Result<Version> {
+        Ok(self.pruner.least_readable_ledger_version())
+    }
 }
 
 impl DbWriter for LibraDB {
@@ -911,6 +1138,11 @@ impl DbWriter for LibraDB {
                 .bump_op_counters();
 
             self.pruner.wake(last_version);
+            match self.get_ledger_pruning_target(last_version) {
+                Ok(Some(least_readable_version)) => self.pruner.wake_ledger(least_readable_version),
+                Ok(None) => (),
+                Err(e) => warn!("Failed to get ledger pruning target, ignored. Err: {}", e),
+            }
         }
 
         Ok(())
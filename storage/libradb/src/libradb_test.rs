@@ -3,7 +3,10 @@
 
 use super::*;
 use crate::{
-    schema::jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+    schema::{
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        transaction_by_hash::TransactionByHashSchema,
+    },
     test_helper::{arb_blocks_to_commit, arb_mock_genesis},
 };
 use jellyfish_merkle::node_type::{Node, NodeKey};
@@ -84,6 +87,44 @@ fn test_save_blocks_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSig
     );
     let (_, ledger_infos_with_sigs): (Vec<_>, Vec<_>) = input.iter().cloned().unzip();
     verify_epochs(&db, &ledger_infos_with_sigs);
+    verify_account_states_in_range(&db, cur_ver - 1);
+}
+
+fn verify_account_states_in_range(db: &LibraDB, version: Version) {
+    let expected = db
+        .get_backup_handler()
+        .get_account_iter(version)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    let mut actual = vec![];
+    let mut start_key = HashValue::zero();
+    loop {
+        let chunk = db
+            .get_account_states_in_range(version, start_key, 3 /* limit */)
+            .unwrap();
+        assert_eq!(chunk.version, version);
+        if let Some((rightmost_key, _)) = chunk.account_blobs.last() {
+            assert_eq!(
+                chunk.proof,
+                db.state_store
+                    .get_account_state_range_proof(*rightmost_key, version)
+                    .unwrap()
+            );
+        }
+        let next_key = chunk.next_key();
+        let num_accounts = chunk.account_blobs.len();
+        actual.extend(chunk.account_blobs);
+        match next_key {
+            Some(next_key) => {
+                assert_eq!(num_accounts, 3);
+                start_key = next_key;
+            }
+            None => break,
+        }
+    }
+    assert_eq!(actual, expected);
 }
 
 fn test_sync_transactions_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
@@ -337,6 +378,14 @@ fn verify_committed_transactions(
             .verify_user_txn(ledger_info, cur_ver, txn.sender(), txn.sequence_number())
             .unwrap();
 
+        let txn_with_proof = db
+            .get_transaction_by_hash(txn_info.transaction_hash(), ledger_version, true)
+            .unwrap()
+            .expect("Should exist.");
+        txn_with_proof
+            .verify_user_txn(ledger_info, cur_ver, txn.sender(), txn.sequence_number())
+            .unwrap();
+
         let txn_list_with_proof = db
             .get_transactions(cur_ver, 1, ledger_version, true /* fetch_events */)
             .unwrap();
@@ -368,6 +417,91 @@ fn verify_committed_transactions(
     );
 }
 
+fn test_ledger_pruning_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::open_with_ledger_pruning_window(
+        &tmp_dir,
+        false, /* readonly */
+        Some(LedgerPruningWindow::Versions(1)),
+    )
+    .unwrap();
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = cur_ver - 1;
+    let least_readable_version = latest_version.saturating_sub(1);
+    db.pruner
+        .wake_ledger_and_wait(least_readable_version)
+        .unwrap();
+    assert_eq!(
+        db.get_least_readable_version().unwrap(),
+        least_readable_version
+    );
+
+    let txns_to_commit: Vec<_> = input.iter().flat_map(|(txns, _)| txns).collect();
+    for (version, txn_to_commit) in txns_to_commit.into_iter().enumerate() {
+        let version = version as Version;
+        if version < least_readable_version {
+            let err = db
+                .get_transaction_with_proof(version, latest_version, true /* fetch_events */)
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<LibraDbError>(),
+                Some(LibraDbError::Pruned(_))
+            ));
+            assert!(db.transaction_store.get_transaction(version).is_err());
+            assert!(db.ledger_store.get_transaction_info(version).is_err());
+            assert!(db
+                .event_store
+                .get_events_by_version(version)
+                .unwrap()
+                .is_empty());
+            if let Ok(txn) = txn_to_commit.transaction().as_signed_user_txn() {
+                assert_eq!(
+                    db.transaction_store
+                        .lookup_transaction_by_account(
+                            txn.sender(),
+                            txn.sequence_number(),
+                            latest_version
+                        )
+                        .unwrap(),
+                    None
+                );
+            }
+            assert_eq!(
+                db.transaction_store
+                    .lookup_transaction_by_hash(txn_to_commit.transaction().hash(), latest_version)
+                    .unwrap(),
+                None
+            );
+            // An index entry that outlived its transaction still resolves to `Pruned`.
+            db.db
+                .put::<TransactionByHashSchema>(&txn_to_commit.transaction().hash(), &version)
+                .unwrap();
+            let err = db
+                .get_transaction_by_hash(
+                    txn_to_commit.transaction().hash(),
+                    latest_version,
+                    false, /* fetch_events */
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<LibraDbError>(),
+                Some(LibraDbError::Pruned(_))
+            ));
+        } else {
+            let txn_with_proof = db
+                .get_transaction_with_proof(version, latest_version, true /* fetch_events */)
+                .unwrap();
+            assert_eq!(&txn_with_proof.transaction, txn_to_commit.transaction());
+        }
+    }
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -380,6 +514,11 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input);
     }
+
+    #[test]
+    fn test_ledger_pruning(input in arb_blocks_to_commit()) {
+        test_ledger_pruning_impl(input);
+    }
 }
 
 #[test]
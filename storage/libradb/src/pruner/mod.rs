@@ -3,17 +3,38 @@
 
 //! This module provides `Pruner` which manages a thread pruning old data in the background and is
 //! meant to be triggered by other threads as they commit new data to the DB.
+//!
+//! Two kinds of data are pruned: stale nodes of the state tree, and optionally the ledger history,
+//! i.e. transactions, events, transaction infos and the indices on them. Ledger infos and the
+//! transaction accumulator are never pruned, since they are needed to prove the versions that are
+//! still readable.
+//!
+//! The progress of both is persisted in `PrunerProgressSchema` together with each batch of
+//! deletions, so that the worker resumes from where it left off after a restart.
 
 use crate::{
     schema::{
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        pruner_progress::{PrunerProgressSchema, PrunerTag},
+        stale_node_index::StaleNodeIndexSchema,
+        transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+        transaction_info::TransactionInfoSchema,
     },
     OP_COUNTER,
 };
 use anyhow::Result;
 use jellyfish_merkle::StaleNodeIndex;
+use libra_crypto::hash::CryptoHash;
 use libra_logger::prelude::*;
-use libra_types::transaction::Version;
+use libra_types::{
+    proof::position::Position,
+    transaction::{Transaction, Version},
+};
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
 #[cfg(test)]
 use std::thread::sleep;
@@ -42,22 +63,46 @@ pub(crate) struct Pruner {
     worker_thread: Option<JoinHandle<()>>,
     /// The sender side of the channel talking to the worker thread.
     command_sender: Mutex<Sender<Command>>,
-    /// (For tests) A way for the worker thread to inform the `Pruner` the pruning progress. If it
-    /// sets this atomic value to `V`, all versions before `V` can no longer be accessed.
-    #[allow(dead_code)]
+    /// A way for the worker thread to inform the `Pruner` the pruning progress. If it sets this
+    /// atomic value to `V`, all versions before `V` can no longer be accessed.
     worker_progress: Arc<AtomicU64>,
+    /// (For tests) Same as `worker_progress`, for the ledger history.
+    #[allow(dead_code)]
+    ledger_worker_progress: Arc<AtomicU64>,
+    /// The transactions, events and transaction infos of versions before this are pruned or being
+    /// pruned. The worker advances it before deleting anything, so that readers never take a
+    /// partially pruned version as readable.
+    least_readable_ledger_version: Arc<AtomicU64>,
 }
 
 impl Pruner {
     /// Creates a worker thread that waits on a channel for pruning commands.
     pub fn new(db: Arc<DB>, num_historical_versions_to_keep: u64) -> Self {
         let (command_sender, command_receiver) = channel();
-        let worker_progress = Arc::new(AtomicU64::new(0));
+        let least_readable_state_version = get_pruner_progress(&db, PrunerTag::StateNodes)
+            .expect("Reading state pruning progress should succeed.")
+            .unwrap_or(0);
+        let worker_progress = Arc::new(AtomicU64::new(least_readable_state_version));
         let worker_progress_clone = Arc::clone(&worker_progress);
+        let least_readable_ledger_version = get_least_readable_ledger_version(&db)
+            .expect("Reading ledger pruning progress should succeed.");
+        let ledger_worker_progress = Arc::new(AtomicU64::new(least_readable_ledger_version));
+        let ledger_worker_progress_clone = Arc::clone(&ledger_worker_progress);
+        let least_readable_ledger_version = Arc::new(AtomicU64::new(least_readable_ledger_version));
+        let least_readable_ledger_version_clone = Arc::clone(&least_readable_ledger_version);
 
         let worker_thread = std::thread::Builder::new()
             .name("libradb_pruner".into())
-            .spawn(move || Worker::new(db, command_receiver, worker_progress_clone).work_loop())
+            .spawn(move || {
+                Worker::new(
+                    db,
+                    command_receiver,
+                    worker_progress_clone,
+                    ledger_worker_progress_clone,
+                    least_readable_ledger_version_clone,
+                )
+                .work_loop()
+            })
             .expect("Creating pruner thread should succeed.");
 
         Self {
@@ -65,7 +110,49 @@ impl Pruner {
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
             worker_progress,
+            ledger_worker_progress,
+            least_readable_ledger_version,
+        }
+    }
+
+    /// Returns the oldest version whose transaction, events and transaction info are readable.
+    pub fn least_readable_ledger_version(&self) -> Version {
+        self.least_readable_ledger_version.load(Ordering::Relaxed)
+    }
+
+    /// Returns the oldest version whose state tree is fully readable. The state of older versions
+    /// is pruned or being pruned.
+    pub fn least_readable_state_version(&self) -> Version {
+        self.worker_progress.load(Ordering::Relaxed)
+    }
+
+    /// Sends a command to the worker thread to prune the ledger history before
+    /// `least_readable_version`.
+    pub fn wake_ledger(&self, least_readable_version: Version) {
+        self.command_sender
+            .lock()
+            .expect("command_sender to pruner thread should lock.")
+            .send(Command::PruneLedger {
+                least_readable_version,
+            })
+            .expect("Receiver should not destruct prematurely.");
+    }
+
+    /// (For tests only.) Notifies the worker thread to prune the ledger history and waits for it to
+    /// finish.
+    #[cfg(test)]
+    pub fn wake_ledger_and_wait(&self, least_readable_version: Version) -> Result<()> {
+        self.wake_ledger(least_readable_version);
+
+        const TIMEOUT: Duration = Duration::from_secs(10);
+        let end = Instant::now() + TIMEOUT;
+        while Instant::now() < end {
+            if self.ledger_worker_progress.load(Ordering::Relaxed) >= least_readable_version {
+                return Ok(());
+            }
+            sleep(Duration::from_millis(1));
         }
+        anyhow::bail!("Timeout waiting for pruner worker.");
     }
 
     /// Sends pruning command to the worker thread when necessary.
@@ -124,6 +211,7 @@ impl Drop for Pruner {
 enum Command {
     Quit,
     Prune { least_readable_version: Version },
+    PruneLedger { least_readable_version: Version },
 }
 
 struct Worker {
@@ -139,6 +227,11 @@ struct Worker {
     blocking_recv: bool,
     index_min_nonpurged_version: Version,
     index_purged_at: Instant,
+    target_least_readable_ledger_version: Version,
+    /// The ledger history pruning progress, updated after each batch is deleted.
+    ledger_progress: Arc<AtomicU64>,
+    /// Advanced before each batch of ledger history is deleted, for readers.
+    least_readable_ledger_version: Arc<AtomicU64>,
 }
 
 impl Worker {
@@ -148,15 +241,20 @@ impl Worker {
         db: Arc<DB>,
         command_receiver: Receiver<Command>,
         least_readable_version: Arc<AtomicU64>,
+        ledger_progress: Arc<AtomicU64>,
+        least_readable_ledger_version: Arc<AtomicU64>,
     ) -> Self {
         Self {
             db,
             command_receiver,
+            target_least_readable_version: least_readable_version.load(Ordering::Relaxed),
             least_readable_version,
-            target_least_readable_version: 0,
             blocking_recv: true,
             index_min_nonpurged_version: 0,
             index_purged_at: Instant::now(),
+            target_least_readable_ledger_version: ledger_progress.load(Ordering::Relaxed),
+            ledger_progress,
+            least_readable_ledger_version,
         }
     }
 
@@ -164,35 +262,73 @@ impl Worker {
         while self.receive_commands() {
             // Process a reasonably small batch of work before trying to receive commands again,
             // in case `Command::Quit` is received (that's when we should quit.)
-            match prune_state(
-                Arc::clone(&self.db),
-                self.least_readable_version.load(Ordering::Relaxed),
-                self.target_least_readable_version,
-                Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
-            ) {
-                Ok(least_readable_version) => {
-                    // Make next recv() blocking if all done.
-                    self.blocking_recv =
-                        least_readable_version == self.target_least_readable_version;
-
-                    // Log the progress.
-                    self.least_readable_version
-                        .store(least_readable_version, Ordering::Relaxed);
-                    OP_COUNTER.set(
-                        "pruner.least_readable_state_version",
-                        least_readable_version as usize,
-                    );
-
-                    // Try to purge the log.
-                    if let Err(e) = self.maybe_purge_index() {
-                        crit!("Failed purging state state node index, ignored. Err: {}", e);
-                    }
-                }
-                Err(e) => {
-                    crit!("Error pruning stale state nodes. {:?}", e);
-                    // On error, stop retrying vigorously by making next recv() blocking.
-                    self.blocking_recv = true;
+            let state_done = self.prune_state_batch();
+            let ledger_done = self.prune_ledger_batch();
+            // Make next recv() blocking if all done.
+            self.blocking_recv = state_done && ledger_done;
+        }
+    }
+
+    /// Prunes a batch of stale state nodes. Returns `true` if there's nothing left to do, or
+    /// pruning failed and shouldn't be retried vigorously.
+    fn prune_state_batch(&mut self) -> bool {
+        match prune_state(
+            Arc::clone(&self.db),
+            self.least_readable_version.load(Ordering::Relaxed),
+            self.target_least_readable_version,
+            Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
+        ) {
+            Ok(least_readable_version) => {
+                // Log the progress.
+                self.least_readable_version
+                    .store(least_readable_version, Ordering::Relaxed);
+                OP_COUNTER.set(
+                    "pruner.least_readable_state_version",
+                    least_readable_version as usize,
+                );
+
+                // Try to purge the log.
+                if let Err(e) = self.maybe_purge_index() {
+                    crit!("Failed purging state state node index, ignored. Err: {}", e);
                 }
+
+                least_readable_version == self.target_least_readable_version
+            }
+            Err(e) => {
+                crit!("Error pruning stale state nodes. {:?}", e);
+                // On error, stop retrying vigorously.
+                true
+            }
+        }
+    }
+
+    /// Prunes a batch of ledger history. Returns `true` if there's nothing left to do, or pruning
+    /// failed and shouldn't be retried vigorously.
+    fn prune_ledger_batch(&mut self) -> bool {
+        let begin = self.ledger_progress.load(Ordering::Relaxed);
+        let end = std::cmp::min(
+            self.target_least_readable_ledger_version,
+            begin + Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH as u64,
+        );
+        if begin >= end {
+            return true;
+        }
+
+        self.least_readable_ledger_version
+            .store(end, Ordering::Relaxed);
+        match prune_ledger(&self.db, begin, end) {
+            Ok(()) => {
+                self.ledger_progress.store(end, Ordering::Relaxed);
+                OP_COUNTER.set("pruner.least_readable_ledger_version", end as usize);
+                end == self.target_least_readable_ledger_version
+            }
+            Err(e) => {
+                crit!("Error pruning ledger history. {:?}", e);
+                // Nothing is deleted, so these versions are still readable.
+                self.least_readable_ledger_version
+                    .store(begin, Ordering::Relaxed);
+                // On error, stop retrying vigorously.
+                true
             }
         }
     }
@@ -232,6 +368,14 @@ impl Worker {
                         self.blocking_recv = false;
                     }
                 }
+                Command::PruneLedger {
+                    least_readable_version,
+                } => {
+                    if least_readable_version > self.target_least_readable_ledger_version {
+                        self.target_least_readable_ledger_version = least_readable_version;
+                        self.blocking_recv = false;
+                    }
+                }
             }
         }
     }
@@ -348,10 +492,70 @@ pub fn prune_state(
             .into_iter()
             .map(|index| batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key))
             .collect::<Result<_>>()?;
+        batch.put::<PrunerProgressSchema>(&PrunerTag::StateNodes, &new_least_readable_version)?;
         db.write_schemas(batch)?;
         Ok(new_least_readable_version)
     }
 }
 
+/// Returns the persisted progress of the pruner on the kind of data denoted by `tag`, or `None` if
+/// that kind of data has never been pruned.
+fn get_pruner_progress(db: &DB, tag: PrunerTag) -> Result<Option<Version>> {
+    db.get::<PrunerProgressSchema>(&tag)
+}
+
+/// Returns where the ledger history pruner left off. For a DB pruned before the progress was
+/// persisted, falls back to the oldest version with a transaction info in the DB.
+fn get_least_readable_ledger_version(db: &DB) -> Result<Version> {
+    if let Some(version) = get_pruner_progress(db, PrunerTag::LedgerHistory)? {
+        return Ok(version);
+    }
+    let mut iter = db.iter::<TransactionInfoSchema>(ReadOptions::default())?;
+    iter.seek_to_first()?;
+    Ok(iter.next().transpose()?.map_or(0, |(version, _)| version))
+}
+
+/// Deletes the transactions, events, event accumulators, transaction infos and the indices on
+/// transactions and events for versions in [`begin`, `end`).
+pub fn prune_ledger(db: &DB, begin: Version, end: Version) -> Result<()> {
+    let mut batch = SchemaBatch::new();
+
+    for version in begin..end {
+        if let Some(txn) = db.get::<TransactionSchema>(&version)? {
+            batch.delete::<TransactionByHashSchema>(&txn.hash())?;
+            if let Transaction::UserTransaction(txn) = txn {
+                batch
+                    .delete::<TransactionByAccountSchema>(&(txn.sender(), txn.sequence_number()))?;
+            }
+            batch.delete::<TransactionSchema>(&version)?;
+        }
+        batch.delete::<TransactionInfoSchema>(&version)?;
+    }
+
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek(&begin)?;
+    for res in iter {
+        let ((version, index), event) = res?;
+        if version >= end {
+            break;
+        }
+        batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+        batch.delete::<EventSchema>(&(version, index))?;
+    }
+
+    let mut iter = db.iter::<EventAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek(&(begin, Position::from_inorder_index(0)))?;
+    for res in iter {
+        let ((version, position), _) = res?;
+        if version >= end {
+            break;
+        }
+        batch.delete::<EventAccumulatorSchema>(&(version, position))?;
+    }
+    batch.put::<PrunerProgressSchema>(&PrunerTag::LedgerHistory, &end)?;
+
+    db.write_schemas(batch)
+}
+
 #[cfg(test)]
 mod test;
@@ -139,6 +139,8 @@ fn test_worker_quit_eagerly() {
             Arc::clone(&db),
             command_receiver,
             Arc::new(AtomicU64::new(0)), /* progress */
+            Arc::new(AtomicU64::new(0)), /* ledger_progress */
+            Arc::new(AtomicU64::new(0)), /* least_readable_ledger_version */
         );
         command_sender
             .send(Command::Prune {
@@ -158,3 +160,39 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
 }
+
+#[test]
+fn test_pruner_progress_persisted() {
+    let address = AccountAddress::new([1u8; AccountAddress::LENGTH]);
+
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new(&tmp_dir).db;
+    let state_store = &StateStore::new(Arc::clone(&db));
+    for version in 0..3 {
+        put_account_state_set(
+            &db,
+            state_store,
+            vec![(address, AccountStateBlob::from(vec![version as u8]))],
+            version,
+        );
+    }
+
+    {
+        let pruner = Pruner::new(
+            Arc::clone(&db),
+            0, /* num_historical_versions_to_keep */
+        );
+        pruner.wake_and_wait(2 /* latest_version */).unwrap();
+    }
+    assert_eq!(
+        get_pruner_progress(&db, PrunerTag::StateNodes).unwrap(),
+        Some(2)
+    );
+
+    // A new pruner picks up from where the last one left off.
+    let pruner = Pruner::new(
+        Arc::clone(&db),
+        0, /* num_historical_versions_to_keep */
+    );
+    assert_eq!(pruner.worker_progress.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,67 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the progress of the pruner, so that it can pick
+//! up from where it left off after restarting.
+//!
+//! ```text
+//! |<--key-->|<-------------value------------->|
+//! |   tag   | least readable version (pruned) |
+//! ```
+//!
+//! `tag` is serialized in a single byte and `Version` is serialized in big endian.
+
+use super::PRUNER_PROGRESS_CF_NAME;
+use crate::schema::ensure_slice_len_eq;
+use anyhow::{format_err, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use libra_types::transaction::Version;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+#[cfg(test)]
+use proptest_derive::Arbitrary;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    PrunerProgressSchema,
+    PrunerTag,
+    Version,
+    PRUNER_PROGRESS_CF_NAME
+);
+
+/// Kinds of data the pruner deletes, each with its own progress.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub(crate) enum PrunerTag {
+    StateNodes = 0,
+    LedgerHistory = 1,
+}
+
+impl KeyCodec<PrunerProgressSchema> for PrunerTag {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.to_u8().expect("Tag should fit in a byte.")])
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u8>())?;
+        Self::from_u8(data[0]).ok_or_else(|| format_err!("Unknown pruner tag {}.", data[0]))
+    }
+}
+
+impl ValueCodec<PrunerProgressSchema> for Version {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
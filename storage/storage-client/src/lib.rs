@@ -22,7 +22,7 @@ use libra_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_state::AccountState,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof, AccountStateWithProof},
     contract_event::ContractEvent,
     event::EventKey,
     get_with_proof::{
@@ -214,9 +214,10 @@ impl StorageRead for StorageReadServiceClient {
     async fn backup_account_state(
         &self,
         version: Version,
+        start_key: HashValue,
     ) -> Result<BoxStream<'_, Result<BackupAccountStateResponse, Error>>> {
         let proto_req: storage_proto::proto::storage::BackupAccountStateRequest =
-            BackupAccountStateRequest::new(version).into();
+            BackupAccountStateRequest::new(version, start_key).into();
         let stream = self
             .client()
             .await?
@@ -466,6 +467,7 @@ pub trait StorageRead: Send + Sync {
     async fn backup_account_state(
         &self,
         version: u64,
+        start_key: HashValue,
     ) -> Result<BoxStream<'_, Result<BackupAccountStateResponse, Error>>>;
 
     /// See [`LibraDB::get_account_state_range_proof`].
@@ -587,6 +589,15 @@ impl DbReader for SyncStorageClient {
         unimplemented!()
     }
 
+    fn get_transaction_by_hash(
+        &self,
+        _hash: HashValue,
+        _ledger_version: Version,
+        _fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        unimplemented!()
+    }
+
     fn get_state_proof_with_ledger_info(
         &self,
         _known_version: u64,
@@ -635,6 +646,19 @@ impl DbReader for SyncStorageClient {
                 .spawn(async move { reader.get_latest_state_root().await }),
         )?
     }
+
+    fn get_account_states_in_range(
+        &self,
+        _version: Version,
+        _start_key: HashValue,
+        _limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        unimplemented!()
+    }
+
+    fn get_least_readable_version(&self) -> Result<Version> {
+        unimplemented!()
+    }
 }
 
 impl DbWriter for SyncStorageClient {
@@ -5,7 +5,7 @@ use anyhow::Result;
 use libra_crypto::HashValue;
 use libra_types::{
     account_address::AccountAddress,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof, AccountStateWithProof},
     contract_event::ContractEvent,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
@@ -77,6 +77,16 @@ pub trait DbReader: Send + Sync {
         fetch_events: bool,
     ) -> Result<Option<TransactionWithProof>>;
 
+    /// Returns the transaction whose hash, as recorded in its `TransactionInfo`, is `hash`, if it
+    /// was committed at or before `ledger_version`. Transactions committed before the DB started
+    /// indexing them by hash, or pruned since, aren't found.
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>>;
+
     /// Returns proof of new state for a given ledger info with signatures relative to version known
     /// to client
     fn get_state_proof_with_ledger_info(
@@ -114,8 +124,22 @@ pub trait DbReader: Send + Sync {
         version: Version,
     ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)>;
 
+    /// Gets up to `limit` accounts in the state tree at `version`, starting from the one whose
+    /// hashed address is `start_key` or the smallest one greater than it, together with a proof
+    /// of the range.
+    fn get_account_states_in_range(
+        &self,
+        version: Version,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof>;
+
     /// Gets the latest state root hash together with its version.
     fn get_latest_state_root(&self) -> Result<(Version, HashValue)>;
+
+    /// Returns the oldest version whose transaction, events and transaction info haven't been
+    /// pruned. Requests for anything older fail.
+    fn get_least_readable_version(&self) -> Result<Version>;
 }
 
 /// Trait that is implemented by a DB that supports certain public (to client) write APIs
@@ -762,12 +762,15 @@ impl From<GetEpochChangeLedgerInfosRequest>
 pub struct BackupAccountStateRequest {
     /// The version of state to backup.
     pub version: Version,
+
+    /// The smallest account key to backup.
+    pub start_key: HashValue,
 }
 
 impl BackupAccountStateRequest {
     /// Constructor.
-    pub fn new(version: Version) -> Self {
-        Self { version }
+    pub fn new(version: Version, start_key: HashValue) -> Self {
+        Self { version, start_key }
     }
 }
 
@@ -775,8 +778,14 @@ impl TryFrom<crate::proto::storage::BackupAccountStateRequest> for BackupAccount
     type Error = Error;
 
     fn try_from(proto: crate::proto::storage::BackupAccountStateRequest) -> Result<Self> {
+        let start_key = if proto.start_key.is_empty() {
+            HashValue::zero()
+        } else {
+            HashValue::from_slice(&proto.start_key)?
+        };
         Ok(Self {
             version: proto.version,
+            start_key,
         })
     }
 }
@@ -785,6 +794,7 @@ impl From<BackupAccountStateRequest> for crate::proto::storage::BackupAccountSta
     fn from(request: BackupAccountStateRequest) -> Self {
         Self {
             version: request.version,
+            start_key: request.start_key.to_vec(),
         }
     }
 }
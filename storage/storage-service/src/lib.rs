@@ -36,7 +36,16 @@ use storage_proto::proto::storage::{
 use tokio::runtime::Runtime;
 
 pub fn init_libra_db(config: &NodeConfig) -> (Arc<LibraDB>, DbReaderWriter) {
-    DbReaderWriter::wrap(LibraDB::new(&config.storage.dir()))
+    DbReaderWriter::wrap(open_libra_db(config))
+}
+
+fn open_libra_db(config: &NodeConfig) -> LibraDB {
+    LibraDB::open_with_ledger_pruning_window(
+        &config.storage.dir(),
+        false, /* readonly */
+        config.storage.ledger_pruning_window,
+    )
+    .expect("Unable to open LibraDB")
 }
 
 /// Starts storage service with a given LibraDB
@@ -47,7 +56,9 @@ pub fn start_storage_service_with_db(config: &NodeConfig, libra_db: Arc<LibraDB>
 
 /// Starts storage service according to config.
 pub fn start_storage_service(config: &NodeConfig) -> Runtime {
-    let storage_service = StorageService::new(&config.storage.dir());
+    let storage_service = StorageService {
+        db: Arc::new(open_libra_db(config)),
+    };
     start_storage_service_runtime(config, storage_service)
 }
 
@@ -334,7 +345,7 @@ impl Storage for StorageService {
         let iter = self
             .db
             .get_backup_handler()
-            .get_account_iter(req.version)
+            .get_account_iter_from(req.version, req.start_key)
             .map_err(|e| tonic::Status::new(tonic::Code::InvalidArgument, e.to_string()))?;
 
         let iter = iter.map(|res| match res {
@@ -351,7 +362,11 @@ impl Storage for StorageService {
 
         tokio::spawn(async move {
             for resp in iter {
-                tx.send(resp).await.unwrap();
+                // The client may stop reading early, e.g. once it reaches the end of the key
+                // range it asked for.
+                if tx.send(resp).await.is_err() {
+                    break;
+                }
             }
         });
 
@@ -119,6 +119,7 @@ impl StorageRead for MockStorageReadClient {
     async fn backup_account_state(
         &self,
         _version: u64,
+        _start_key: HashValue,
     ) -> Result<BoxStream<'_, Result<BackupAccountStateResponse, Error>>> {
         unimplemented!()
     }
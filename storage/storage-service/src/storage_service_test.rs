@@ -5,7 +5,7 @@ use super::*;
 use futures::stream::StreamExt;
 use itertools::zip_eq;
 use libra_config::config::NodeConfig;
-use libra_crypto::hash::CryptoHash;
+use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_types::get_with_proof::{RequestItem, ResponseItem};
 #[cfg(test)]
 use libradb::test_helper::arb_blocks_to_commit;
@@ -108,7 +108,7 @@ proptest! {
         // Check state backup for all account states.
         {
             let stream = rt
-                .block_on(read_client.backup_account_state(version - 1))
+                .block_on(read_client.backup_account_state(version - 1, HashValue::zero()))
                 .unwrap();
             let backup_responses = rt.block_on(stream.collect::<Vec<_>>());
             for ((hash, blob), response) in zip_eq(all_accounts, backup_responses) {
@@ -730,11 +730,13 @@ fn test_external_transaction_signer() {
             assert_eq!(p_max_gas_amount, max_gas_amount);
             match script {
                 ScriptView::PeerToPeer {
+                    currency,
                     receiver: p_receiver,
                     amount: p_amount,
                     auth_key_prefix,
                     metadata,
                 } => {
+                    assert_eq!(currency, "LBR");
                     assert_eq!(p_receiver, receiver_address.to_string());
                     assert_eq!(p_amount, amount);
                     assert_eq!(
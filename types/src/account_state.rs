@@ -13,7 +13,10 @@ use crate::{
     },
     event::EventHandle,
     libra_timestamp::{LibraTimestampResource, LIBRA_TIMESTAMP_RESOURCE_PATH},
-    on_chain_config::{ConfigurationResource, OnChainConfig, CONFIGURATION_RESOURCE_PATH},
+    on_chain_config::{
+        ConfigurationResource, GasScheduleResource, OnChainConfig, CONFIGURATION_RESOURCE_PATH,
+        GAS_SCHEDULE_RESOURCE_PATH,
+    },
     validator_config::{ValidatorConfigResource, VALIDATOR_CONFIG_RESOURCE_PATH},
     validator_set::ValidatorSet,
 };
@@ -59,6 +62,18 @@ impl AccountState {
         self.get_resource(&ValidatorSet::CONFIG_ID.access_path().path)
     }
 
+    /// Returns the on-chain config `T` if this account holds it.
+    pub fn get_config<T: OnChainConfig>(&self) -> Result<Option<T>> {
+        self.0
+            .get(&T::CONFIG_ID.access_path().path)
+            .map(|bytes| T::deserialize_into_config(bytes))
+            .transpose()
+    }
+
+    pub fn get_gas_schedule_resource(&self) -> Result<Option<GasScheduleResource>> {
+        self.get_resource(&*GAS_SCHEDULE_RESOURCE_PATH)
+    }
+
     pub fn get_libra_block_resource(&self) -> Result<Option<LibraBlockResource>> {
         self.get_resource(&*LIBRA_BLOCK_RESOURCE_PATH)
     }
@@ -106,6 +121,11 @@ impl AccountState {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Iterates over the (access path, resource) pairs in this account, ordered by access path.
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<u8>, &Vec<u8>)> {
+        self.0.iter()
+    }
 }
 
 impl fmt::Debug for AccountState {
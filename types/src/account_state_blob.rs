@@ -7,12 +7,12 @@ use crate::{
     account_state::AccountState,
     event::EventKey,
     ledger_info::LedgerInfo,
-    proof::AccountStateProof,
+    proof::{AccountStateProof, SparseMerkleRangeProof},
     transaction::Version,
 };
 use anyhow::{anyhow, ensure, format_err, Error, Result};
 use libra_crypto::{
-    hash::{CryptoHash, CryptoHasher},
+    hash::{CryptoHash, CryptoHasher, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
 };
 use libra_crypto_derive::CryptoHasher;
@@ -223,6 +223,58 @@ impl AccountStateWithProof {
     }
 }
 
+/// A chunk of the accounts in the state tree at some version, ordered by the hash of the account
+/// address, together with the proof that they are all the accounts up to the last one.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountStateChunkWithProof {
+    /// The version of the state tree the accounts are read from.
+    pub version: Version,
+    /// (hash of the account address, account state) pairs in ascending order of the hash.
+    pub account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    /// The siblings on the right of the path to the last account, empty if `account_blobs` is.
+    pub proof: SparseMerkleRangeProof,
+}
+
+impl AccountStateChunkWithProof {
+    /// Constructor.
+    pub fn new(
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Self {
+        Self {
+            version,
+            account_blobs,
+            proof,
+        }
+    }
+
+    /// Returns the smallest key greater than that of the last account in this chunk, which is where
+    /// the next chunk starts. `None` if there are no more accounts after this chunk, i.e. the chunk
+    /// is empty or all the siblings on the right of the last account are empty subtrees.
+    pub fn next_key(&self) -> Option<HashValue> {
+        let (last_key, _) = self.account_blobs.last()?;
+        if self
+            .proof
+            .right_siblings()
+            .iter()
+            .all(|sibling| *sibling == *SPARSE_MERKLE_PLACEHOLDER_HASH)
+        {
+            return None;
+        }
+        let mut bytes = last_key.to_vec();
+        for byte in bytes.iter_mut().rev() {
+            if *byte == u8::max_value() {
+                *byte = 0;
+            } else {
+                *byte += 1;
+                return Some(HashValue::from_slice(&bytes).expect("Length should match."));
+            }
+        }
+        None
+    }
+}
+
 impl TryFrom<crate::proto::types::AccountStateWithProof> for AccountStateWithProof {
     type Error = Error;
 
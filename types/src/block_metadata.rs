@@ -158,4 +158,8 @@ impl NewBlockEvent {
     pub fn votes(&self) -> Vec<AccountAddress> {
         self.votes.clone()
     }
+
+    pub fn timestamp_usecs(&self) -> u64 {
+        self.timestamp
+    }
 }
@@ -151,9 +151,66 @@ pub struct ConfigurationResource {
 }
 
 impl ConfigurationResource {
+    pub fn new(epoch: u64, last_reconfiguration_time: u64, events: EventHandle) -> Self {
+        Self {
+            epoch,
+            last_reconfiguration_time,
+            events,
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
     pub fn last_reconfiguration_time(&self) -> u64 {
         self.last_reconfiguration_time
     }
+
+    pub fn events(&self) -> &EventHandle {
+        &self.events
+    }
+}
+
+static GAS_SCHEDULE_MODULE_NAME: Lazy<Identifier> =
+    Lazy::new(|| Identifier::new("GasSchedule").unwrap());
+
+static GAS_SCHEDULE_STRUCT_NAME: Lazy<Identifier> = Lazy::new(|| Identifier::new("T").unwrap());
+
+pub fn gas_schedule_module_name() -> &'static IdentStr {
+    &*GAS_SCHEDULE_MODULE_NAME
+}
+
+pub fn gas_schedule_struct_name() -> &'static IdentStr {
+    &*GAS_SCHEDULE_STRUCT_NAME
+}
+
+pub fn gas_schedule_tag() -> StructTag {
+    StructTag {
+        address: account_config::CORE_CODE_ADDRESS,
+        name: gas_schedule_struct_name().to_owned(),
+        module: gas_schedule_module_name().to_owned(),
+        type_params: vec![],
+    }
+}
+
+/// Path to the gas schedule resource. Unlike the configs above, it is published directly under
+/// the association address rather than wrapped in a `LibraConfig::T`.
+pub static GAS_SCHEDULE_RESOURCE_PATH: Lazy<Vec<u8>> =
+    Lazy::new(|| AccessPath::resource_access_vec(&gas_schedule_tag(), &Accesses::empty()));
+
+/// The gas costs of each bytecode instruction and of each native function, indexed the same way
+/// as the VM's `CostTable`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GasScheduleResource {
+    pub instruction_schedule: Vec<GasScheduleCost>,
+    pub native_schedule: Vec<GasScheduleCost>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GasScheduleCost {
+    pub cpu: u64,
+    pub storage: u64,
 }
 
 /// Defines and holds the publishing policies for the VM. There are three possible configurations:
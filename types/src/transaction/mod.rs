@@ -436,6 +436,14 @@ impl SignedTransaction {
         Ok(SignatureCheckedTransaction(self))
     }
 
+    /// Treats the transaction as if its signature had been checked, without checking it. Only
+    /// meant for the VM to simulate transactions whose output is never committed, which is why
+    /// it's behind the `simulation` feature.
+    #[cfg(feature = "simulation")]
+    pub fn into_simulated(self) -> SignatureCheckedTransaction {
+        SignatureCheckedTransaction(self)
+    }
+
     pub fn format_for_client(&self, get_transaction_name: impl Fn(&[u8]) -> String) -> String {
         format!(
             "SignedTransaction {{ \n \
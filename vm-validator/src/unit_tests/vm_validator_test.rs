@@ -1,24 +1,28 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::vm_validator::{TransactionValidation, VMValidator};
+use crate::vm_validator::{simulate_transaction, TransactionValidation, VMValidator};
 use executor::db_bootstrapper::maybe_bootstrap_db;
 use libra_config::config::NodeConfig;
 use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
 use libra_types::{
     account_address, account_config,
-    account_config::lbr_type_tag,
+    account_config::{lbr_type_tag, AccountResource},
     test_helpers::transaction_test_helpers,
-    transaction::{Module, Script, TransactionArgument, MAX_TRANSACTION_SIZE_IN_BYTES},
-    vm_error::StatusCode,
+    transaction::{
+        authenticator::AuthenticationKey, Module, Script, TransactionArgument, TransactionStatus,
+        MAX_TRANSACTION_SIZE_IN_BYTES,
+    },
+    vm_error::{StatusCode, VMStatus},
 };
 use libra_vm::LibraVM;
 use rand::SeedableRng;
-use std::{sync::Arc, u64};
+use std::{convert::TryFrom, sync::Arc, u64};
 use storage_client::SyncStorageClient;
+use storage_interface::DbReader;
 use storage_service::{init_libra_db, start_storage_service_with_db};
 use tokio::runtime::Runtime;
-use transaction_builder::encode_transfer_script;
+use transaction_builder::{encode_create_account_script, encode_transfer_script};
 
 struct TestValidator {
     _storage: Runtime,
@@ -411,3 +415,84 @@ fn test_validate_non_genesis_write_set() {
         .unwrap();
     assert_eq!(ret.status().unwrap().major_status, StatusCode::ABORTED);
 }
+
+#[test]
+fn test_simulate_transaction() {
+    let (config, key) = config_builder::test_config();
+    let (_vm_validator, mut rt) = TestValidator::new(&config);
+    let db_reader: Arc<dyn DbReader> = Arc::new(SyncStorageClient::new(&config.storage.address));
+    let get_sequence_number = |address| {
+        // The storage client only serves account states by version.
+        let (version, _) = db_reader.get_latest_state_root().unwrap();
+        let (blob, _) = db_reader
+            .get_account_state_with_proof_by_version(address, version)
+            .unwrap();
+        AccountResource::try_from(&blob.unwrap())
+            .unwrap()
+            .sequence_number()
+    };
+
+    let address = account_config::association_address();
+    let sequence_number = get_sequence_number(address);
+    let mut rng = ::rand::rngs::StdRng::from_seed([1u8; 32]);
+    let other_private_key = Ed25519PrivateKey::generate(&mut rng);
+    let auth_key = AuthenticationKey::ed25519(&other_private_key.public_key());
+    let program =
+        encode_create_account_script(&auth_key.derived_address(), auth_key.prefix().to_vec(), 100);
+    let transaction = transaction_test_helpers::get_test_signed_txn(
+        address,
+        sequence_number,
+        &key,
+        key.public_key(),
+        Some(program.clone()),
+    );
+    let (version, output) = rt
+        .block_on(simulate_transaction(
+            Arc::clone(&db_reader),
+            transaction,
+            false,
+        ))
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    assert!(output.gas_used() > 0);
+    assert!(!output.events().is_empty());
+    assert!(!output.write_set().is_empty());
+
+    // Nothing was committed.
+    assert_eq!(db_reader.get_latest_state_root().unwrap().0, version);
+    assert_eq!(get_sequence_number(address), sequence_number);
+
+    // A bad signature is only accepted if signature checks are skipped.
+    let unsigned_transaction = transaction_test_helpers::get_test_unchecked_txn(
+        address,
+        sequence_number,
+        &other_private_key,
+        key.public_key(),
+        Some(program),
+    );
+    let (_, output) = rt
+        .block_on(simulate_transaction(
+            Arc::clone(&db_reader),
+            unsigned_transaction.clone(),
+            false,
+        ))
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+    );
+    let (_, output) = rt
+        .block_on(simulate_transaction(
+            Arc::clone(&db_reader),
+            unsigned_transaction,
+            true,
+        ))
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+}
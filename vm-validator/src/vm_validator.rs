@@ -6,9 +6,9 @@ use libra_types::{
     account_address::AccountAddress,
     account_config::AccountResource,
     on_chain_config::{OnChainConfigPayload, VMPublishingOption},
-    transaction::{SignedTransaction, VMValidatorResult},
+    transaction::{SignedTransaction, TransactionOutput, VMValidatorResult, Version},
 };
-use libra_vm::{on_chain_configs::VMConfig, LibraVM, VMVerifier};
+use libra_vm::{on_chain_configs::VMConfig, LibraVM, VMSimulator, VMVerifier};
 use scratchpad::SparseMerkleTree;
 use std::{convert::TryFrom, sync::Arc};
 use storage_client::{StorageRead, VerifiedStateView};
@@ -92,6 +92,28 @@ impl TransactionValidation for VMValidator {
     }
 }
 
+/// Executes `txn` against the latest committed state without committing it, and returns the
+/// version of that state along with the output. Unlike `validate_transaction`, this runs the whole
+/// transaction and not just the prologue.
+pub async fn simulate_transaction(
+    db_reader: Arc<dyn DbReader>,
+    txn: SignedTransaction,
+    skip_signature_check: bool,
+) -> Result<(Version, TransactionOutput)> {
+    let (version, state_root) = db_reader.get_latest_state_root()?;
+    // The state view reads storage synchronously, see `validate_transaction` for why this has to
+    // run on its own thread.
+    tokio::task::spawn_blocking(move || {
+        let smt = SparseMerkleTree::new(state_root);
+        let state_view = VerifiedStateView::new(db_reader, Some(version), state_root, &smt);
+
+        let output = LibraVM::simulate_transaction(txn, skip_signature_check, &state_view);
+        (version, output)
+    })
+    .await
+    .map_err(Into::into)
+}
+
 /// returns account's sequence number from storage
 pub async fn get_account_sequence_number(
     storage_read_client: Arc<dyn StorageRead>,